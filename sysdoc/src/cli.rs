@@ -14,6 +14,50 @@ pub enum OutputFormat {
     Html,
     /// PDF with embedded images and table of contents
     Pdf,
+    /// ReqIF 1.2 for requirements management tools
+    Reqif,
+}
+
+/// Output format for the trace coverage command
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CoverageFormat {
+    /// Human-readable summary
+    Text,
+    /// JSON report
+    Json,
+    /// CSV with one row per requirement and section
+    Csv,
+}
+
+/// Output format for the baseline diff and trace impact commands
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    /// Human-readable report
+    Text,
+    /// JSON report
+    Json,
+}
+
+/// Output format for the trace export command
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum TraceExportFormat {
+    /// CSV of the forward relation
+    Csv,
+    /// Excel workbook with Forward and Reverse sheets
+    Xlsx,
+    /// JSON of every identified section with its attributes and links
+    Json,
+}
+
+/// Diagnostic output format for the build, validate and lint commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MessageFormat {
+    /// Human-readable diagnostics with source snippets
+    Human,
+    /// JSON report of every diagnostic
+    Json,
+    /// SARIF 2.1.0 log for code scanning tools
+    Sarif,
 }
 
 /// CLI structure for the sysdoc application
@@ -72,6 +116,18 @@ pub enum Commands {
         /// Skip image embedding (DOCX only)
         #[arg(long)]
         no_images: bool,
+
+        /// Release build: fail if any TBD/TBR items remain
+        #[arg(long)]
+        release: bool,
+
+        /// Format of reported diagnostics
+        #[arg(long, value_enum, default_value = "human")]
+        message_format: MessageFormat,
+
+        /// Fail on warnings as well as errors
+        #[arg(long)]
+        deny_warnings: bool,
     },
 
     /// Validate document structure and references
@@ -91,8 +147,333 @@ pub enum Commands {
         /// Validate CSV table references
         #[arg(long)]
         check_tables: bool,
+
+        /// Also check release readiness: fail if any TBD/TBR items remain
+        #[arg(long)]
+        release: bool,
+
+        /// Add a word to the project spelling dictionary instead of validating (repeatable)
+        #[arg(long = "add-word", value_name = "WORD")]
+        add_words: Vec<String>,
+
+        /// Format of reported diagnostics
+        #[arg(long, value_enum, default_value = "human")]
+        message_format: MessageFormat,
+
+        /// Fail on warnings as well as errors
+        #[arg(long)]
+        deny_warnings: bool,
+    },
+
+    /// Check documentation quality with the lint rules configured in sysdoc.toml
+    Lint {
+        /// Input directory (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        input: PathBuf,
+
+        /// List the lint rules and their default severities instead of linting
+        #[arg(long)]
+        list_rules: bool,
+
+        /// Add a word to the project spelling dictionary instead of linting (repeatable)
+        #[arg(long = "add-word", value_name = "WORD")]
+        add_words: Vec<String>,
+
+        /// Format of reported diagnostics
+        #[arg(long, value_enum, default_value = "human")]
+        message_format: MessageFormat,
+
+        /// Fail on warnings as well as errors
+        #[arg(long)]
+        deny_warnings: bool,
     },
 
     /// List available DID templates
     ListTemplates,
+
+    /// Remove files under src/ that the document does not reference
+    CleanAssets {
+        /// Input directory (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        input: PathBuf,
+
+        /// List the files that would be removed without removing them (the default)
+        #[arg(long, conflicts_with = "apply")]
+        dry_run: bool,
+
+        /// Remove the files
+        #[arg(long)]
+        apply: bool,
+    },
+
+    /// Traceability analysis commands
+    Trace {
+        /// The trace subcommand to execute
+        #[command(subcommand)]
+        command: TraceCommands,
+    },
+
+    /// Import content from other tools
+    Import {
+        /// The import subcommand to execute
+        #[command(subcommand)]
+        command: ImportCommands,
+    },
+
+    /// Create and compare requirements baselines
+    Baseline {
+        /// The baseline subcommand to execute
+        #[command(subcommand)]
+        command: BaselineCommands,
+    },
+
+    /// Manage section IDs
+    Ids {
+        /// The ids subcommand to execute
+        #[command(subcommand)]
+        command: IdsCommands,
+    },
+
+    /// Insert and move sections, renaming files and updating links
+    Section {
+        /// The section subcommand to execute
+        #[command(subcommand)]
+        command: SectionCommands,
+    },
+
+    /// Number sections consecutively, closing gaps left by deleted sections
+    Renumber {
+        /// Input directory (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        input: PathBuf,
+
+        /// Print the files that would be renamed without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Available `trace` subcommands
+#[derive(Subcommand)]
+pub enum TraceCommands {
+    /// Report forward and backward traceability coverage
+    Coverage {
+        /// Input directory (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        input: PathBuf,
+
+        /// Report format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: CoverageFormat,
+
+        /// Write the report to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Fail if forward coverage (percent of requirements traced) is below this value
+        #[arg(long, value_name = "PERCENT")]
+        min_coverage: Option<f64>,
+
+        /// Report items with more links than this as over-traced
+        #[arg(long, value_name = "COUNT", default_value_t = 5)]
+        max_links: usize,
+
+        /// Treat local sections whose section_id starts with this prefix as requirements
+        #[arg(long, value_name = "PREFIX")]
+        requirement_prefix: Option<String>,
+
+        /// Only analyse local sections whose section_id starts with this prefix
+        #[arg(long, value_name = "PREFIX")]
+        section_prefix: Option<String>,
+    },
+
+    /// Export the full traceability matrix as a spreadsheet or JSON
+    Export {
+        /// Input directory (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        input: PathBuf,
+
+        /// Output file path
+        #[arg(short, long, default_value = "trace-matrix")]
+        output: PathBuf,
+
+        /// Output format (auto-detected from file extension if not specified)
+        #[arg(short, long, value_enum)]
+        format: Option<TraceExportFormat>,
+    },
+
+    /// Record the current content of traced targets as reviewed in trace.lock
+    Accept {
+        /// Input directory (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        input: PathBuf,
+
+        /// Only accept links from the section with this section_id
+        #[arg(long, value_name = "ID")]
+        section: Option<String>,
+
+        /// Only accept links to this traced ID
+        #[arg(long, value_name = "ID")]
+        target: Option<String>,
+    },
+
+    /// List every section affected by a change, following traces in both directions
+    Impact {
+        /// Input directory (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        input: PathBuf,
+
+        /// Changed ID (repeat or separate with commas)
+        #[arg(long = "id", value_name = "ID", value_delimiter = ',')]
+        ids: Vec<String>,
+
+        /// Also treat sections changed in src/ since this git revision or range as changed
+        #[arg(long, value_name = "REV")]
+        since: Option<String>,
+
+        /// Report format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: ReportFormat,
+
+        /// Write the report to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Draw a traceability graph as SVG, with a Graphviz DOT file alongside it
+    Graph {
+        /// Input directory (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        input: PathBuf,
+
+        /// Output SVG file (the DOT file uses the same name with a .dot extension)
+        #[arg(short, long, value_name = "FILE", default_value = "trace-graph.svg")]
+        output: PathBuf,
+
+        /// Only include sections whose section_id starts with this prefix
+        #[arg(long, value_name = "PREFIX")]
+        section_id_prefix: Option<String>,
+
+        /// Only include sections numbered under this chapter (e.g., "3" or "3.2")
+        #[arg(long, value_name = "NUMBER")]
+        chapter: Option<String>,
+    },
+}
+
+/// Available `import` subcommands
+#[derive(Subcommand)]
+pub enum ImportCommands {
+    /// Generate a source tree from a ReqIF file, or register it as a trace catalog
+    Reqif {
+        /// ReqIF file to import
+        file: PathBuf,
+
+        /// Document directory to write into (defaults to current directory)
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        into: PathBuf,
+
+        /// Register the file as a trace catalog in sysdoc.toml instead of generating sources
+        #[arg(long)]
+        catalog: bool,
+
+        /// Overwrite existing markdown files
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+/// Available `ids` subcommands
+#[derive(Subcommand)]
+pub enum IdsCommands {
+    /// Write new section IDs into sections that lack one and update id-registry.toml
+    Assign {
+        /// Input directory (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        input: PathBuf,
+
+        /// Number every section, not only those whose sysdoc block describes an item
+        #[arg(long)]
+        all: bool,
+
+        /// Print the IDs that would be assigned without changing any files
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Available `section` subcommands
+#[derive(Subcommand)]
+pub enum SectionCommands {
+    /// Create a section file, moving later sections at its level down by one
+    Insert {
+        /// Number of the new section (e.g., "3.2")
+        number: String,
+
+        /// Heading of the new section
+        title: String,
+
+        /// Input directory (defaults to current directory)
+        #[arg(short, long, value_name = "PATH", default_value = ".")]
+        input: PathBuf,
+
+        /// Print the files that would be created and renamed without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Move a section and its subsections to a new number
+    Move {
+        /// Current number of the section (e.g., "3.4")
+        from: String,
+
+        /// Number of the section after the move (e.g., "3.2")
+        to: String,
+
+        /// Input directory (defaults to current directory)
+        #[arg(short, long, value_name = "PATH", default_value = ".")]
+        input: PathBuf,
+
+        /// Print the files that would be renamed without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Available `baseline` subcommands
+#[derive(Subcommand)]
+pub enum BaselineCommands {
+    /// Snapshot every identified section into baselines/<NAME>.json
+    Create {
+        /// Baseline name (e.g., "v1.0")
+        name: String,
+
+        /// Input directory (defaults to current directory)
+        #[arg(short, long, value_name = "PATH", default_value = ".")]
+        input: PathBuf,
+
+        /// Overwrite an existing baseline with the same name
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Report items added, removed, moved and modified between two baselines
+    Diff {
+        /// Earlier baseline
+        from: String,
+
+        /// Later baseline (defaults to the current document)
+        to: Option<String>,
+
+        /// Input directory (defaults to current directory)
+        #[arg(short, long, value_name = "PATH", default_value = ".")]
+        input: PathBuf,
+
+        /// Report format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: ReportFormat,
+
+        /// Write the report to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
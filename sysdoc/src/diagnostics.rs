@@ -0,0 +1,609 @@
+//! Structured diagnostics
+//!
+//! Parse errors, validation errors, template conformance, suspect links, spelling
+//! and lint findings, and export failures are all reported as [`Diagnostic`]s
+//! with a code, severity and source location. `build`, `validate` and `lint`
+//! collect them in one pass, apply the `[diagnostics]` severity overrides from
+//! sysdoc.toml, and render them for people (with a source snippet), as JSON, or
+//! as SARIF 2.1.0 for code scanning tools.
+
+use crate::document_config::DiagnosticSeverity;
+use crate::source_model::LINT_RULES;
+use crate::text::edit_distance;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// SARIF schema referenced by [`render_sarif`]
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Codes of the diagnostics sysdoc reports, besides the lint rule names
+pub const CODES: &[&str] = &[
+    "invalid-config",
+    "invalid-heading-structure",
+    "invalid-metadata",
+    "invalid-filename",
+    "invalid-section-number",
+    "invalid-outline",
+    "unlisted-file",
+    "io-error",
+    "invalid-catalog",
+    "invalid-test-results",
+    "invalid-trace-lock",
+    "invalid-id-registry",
+    "invalid-baseline",
+    "invalid-id-scheme",
+    "linked-project",
+    "code-trace",
+    "missing-image",
+    "missing-table",
+    "missing-include",
+    "broken-link",
+    "duplicate-section-id",
+    "unknown-traced-id",
+    "unknown-code-reference",
+    "section-id-pattern",
+    "retired-section-id",
+    "invalid-attribute",
+    "missing-attribute",
+    "missing-template-section",
+    "renamed-template-section",
+    "moved-template-section",
+    "guidance-only-section",
+    "missing-template-metadata",
+    "unknown-template",
+    "open-item",
+    "suspect-link",
+    "spelling",
+    "orphaned-asset",
+    "duplicate-section-number",
+    "invalid-structure",
+    "trace-graph-failed",
+    "export-failed",
+    "unknown-diagnostic-code",
+];
+
+/// Location of a diagnostic within its file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Span {
+    /// Line number (1-indexed)
+    pub line: usize,
+    /// First column of the highlighted text (1-indexed, in characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    /// Column just past the highlighted text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
+}
+
+/// A problem found in the document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// Stable kebab-case code, e.g. "broken-link", used by `[diagnostics]` overrides
+    pub code: String,
+    /// Severity after overrides are applied
+    pub severity: DiagnosticSeverity,
+    /// Description of the problem
+    pub message: String,
+    /// Source file (relative to the document root once resolved)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    /// Line and columns within the file
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+    /// How to fix the problem
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    /// Text on the diagnostic's line to highlight once the file is read
+    #[serde(skip)]
+    highlight: Option<String>,
+}
+
+impl Diagnostic {
+    /// Create a diagnostic without a location
+    pub fn new(
+        code: impl Into<String>,
+        severity: DiagnosticSeverity,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            code: code.into(),
+            severity,
+            message: message.into(),
+            file: None,
+            span: None,
+            suggestion: None,
+            highlight: None,
+        }
+    }
+
+    /// Create an error diagnostic
+    pub fn error(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(code, DiagnosticSeverity::Error, message)
+    }
+
+    /// Create a warning diagnostic
+    pub fn warning(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(code, DiagnosticSeverity::Warning, message)
+    }
+
+    /// Locate the diagnostic in a file
+    pub fn in_file(mut self, file: impl Into<PathBuf>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    /// Locate the diagnostic on a line of a file (line 0 means unknown)
+    pub fn at_line(mut self, file: impl Into<PathBuf>, line: usize) -> Self {
+        self.file = Some(file.into());
+        self.span = (line > 0).then_some(Span {
+            line,
+            column: None,
+            end_column: None,
+        });
+        self
+    }
+
+    /// Locate the diagnostic at a line and column of a file (0 means unknown)
+    pub fn at(self, file: impl Into<PathBuf>, line: usize, column: usize) -> Self {
+        let mut diagnostic = self.at_line(file, line);
+        if let Some(span) = diagnostic.span.as_mut() {
+            span.column = (column > 0).then_some(column);
+        }
+        diagnostic
+    }
+
+    /// Highlight the first occurrence of `text` on the diagnostic's line, from
+    /// the diagnostic's column if it is known
+    pub fn highlighting(mut self, text: impl Into<String>) -> Self {
+        self.highlight = Some(text.into());
+        self
+    }
+
+    /// Attach a suggested fix
+    pub fn with_suggestion(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+}
+
+/// Count the diagnostics of a severity
+pub fn count(diagnostics: &[Diagnostic], severity: DiagnosticSeverity) -> usize {
+    diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == severity)
+        .count()
+}
+
+/// Check whether diagnostics fail a command
+///
+/// # Parameters
+/// * `diagnostics` - Resolved diagnostics
+/// * `deny_warnings` - Whether warnings fail the command too
+pub fn is_failure(diagnostics: &[Diagnostic], deny_warnings: bool) -> bool {
+    diagnostics.iter().any(|diagnostic| {
+        diagnostic.severity == DiagnosticSeverity::Error
+            || (deny_warnings && diagnostic.severity == DiagnosticSeverity::Warning)
+    })
+}
+
+/// Report `[diagnostics]` overrides for codes that sysdoc never reports
+///
+/// A misspelled code would otherwise leave the intended severity silently unapplied.
+///
+/// # Parameters
+/// * `overrides` - `[diagnostics]` severities from sysdoc.toml, by code
+/// * `config_path` - Path of sysdoc.toml
+///
+/// # Returns
+/// * An `unknown-diagnostic-code` warning per unknown code
+pub fn unknown_codes(
+    overrides: &BTreeMap<String, DiagnosticSeverity>,
+    config_path: &Path,
+) -> Vec<Diagnostic> {
+    let known = || {
+        CODES
+            .iter()
+            .copied()
+            .chain(LINT_RULES.iter().map(|rule| rule.name))
+    };
+    overrides
+        .keys()
+        .filter(|code| !known().any(|known| known == code.as_str()))
+        .map(|code| {
+            let diagnostic = Diagnostic::warning(
+                "unknown-diagnostic-code",
+                format!("Unknown diagnostic code '{code}' in [diagnostics]"),
+            )
+            .in_file(config_path);
+            match known()
+                .filter_map(|known| Some((edit_distance(code, known, 3)?, known)))
+                .min()
+            {
+                Some((_, known)) => diagnostic.with_suggestion(format!("did you mean '{known}'?")),
+                None => diagnostic,
+            }
+        })
+        .collect()
+}
+
+/// Apply severity overrides and resolve locations against the document root
+///
+/// Diagnostics whose code is overridden to `off` are dropped. File paths are made
+/// relative to `root`, and highlighted text is turned into a column range.
+///
+/// # Parameters
+/// * `diagnostics` - Diagnostics as reported
+/// * `root` - Root directory of the document
+/// * `overrides` - `[diagnostics]` severities from sysdoc.toml, by code
+///
+/// # Returns
+/// * The diagnostics to report, in their original order
+pub fn resolve(
+    diagnostics: Vec<Diagnostic>,
+    root: &Path,
+    overrides: &BTreeMap<String, DiagnosticSeverity>,
+) -> Vec<Diagnostic> {
+    let mut sources = SourceCache::new(root);
+    diagnostics
+        .into_iter()
+        .filter_map(|mut diagnostic| {
+            if let Some(severity) = overrides.get(&diagnostic.code) {
+                diagnostic.severity = *severity;
+            }
+            if diagnostic.severity == DiagnosticSeverity::Off {
+                return None;
+            }
+            if let Some(file) = &diagnostic.file {
+                diagnostic.file = Some(file.strip_prefix(root).unwrap_or(file).to_path_buf());
+            }
+            resolve_columns(&mut diagnostic, &mut sources);
+            Some(diagnostic)
+        })
+        .collect()
+}
+
+/// Turn a diagnostic's highlighted text into a column range on its line
+fn resolve_columns(diagnostic: &mut Diagnostic, sources: &mut SourceCache) {
+    let (Some(file), Some(span), Some(text)) = (
+        &diagnostic.file,
+        diagnostic.span.as_mut(),
+        diagnostic.highlight.as_deref(),
+    ) else {
+        return;
+    };
+    let Some(line) = sources.line(file, span.line) else {
+        return;
+    };
+    let start = span.column.map_or(0, |column| {
+        line.char_indices()
+            .nth(column - 1)
+            .map_or(line.len(), |(offset, _)| offset)
+    });
+    let found = line[start..].find(text).filter(|_| !text.is_empty());
+    if let Some(offset) = found {
+        let column = line[..start + offset].chars().count() + 1;
+        span.column = Some(column);
+        span.end_column = Some(column + text.chars().count());
+    }
+}
+
+/// Source files read while resolving and rendering diagnostics
+struct SourceCache<'a> {
+    root: &'a Path,
+    files: HashMap<PathBuf, Option<Vec<String>>>,
+}
+
+impl<'a> SourceCache<'a> {
+    fn new(root: &'a Path) -> Self {
+        Self {
+            root,
+            files: HashMap::new(),
+        }
+    }
+
+    /// Text of a line of a file (1-indexed), if the file can be read
+    fn line(&mut self, file: &Path, line: usize) -> Option<&str> {
+        let root = self.root;
+        let lines = self.files.entry(file.to_path_buf()).or_insert_with(|| {
+            std::fs::read_to_string(root.join(file))
+                .ok()
+                .map(|content| content.lines().map(str::to_string).collect())
+        });
+        lines
+            .as_ref()?
+            .get(line.checked_sub(1)?)
+            .map(String::as_str)
+    }
+}
+
+/// Render diagnostics for people, with a source snippet under each located one
+///
+/// ```text
+/// error[broken-link]: Broken link 'setup.md'
+///   --> src/01_intro.md:3:13
+///    |
+///  3 | See [setup](setup.md).
+///    |             ^^^^^^^^
+///    = help: link to an existing section file or anchor
+/// ```
+///
+/// # Parameters
+/// * `diagnostics` - Resolved diagnostics
+/// * `root` - Root directory of the document, used to read snippets
+pub fn render_human(diagnostics: &[Diagnostic], root: &Path) -> String {
+    let mut sources = SourceCache::new(root);
+    let mut output = String::new();
+    for diagnostic in diagnostics {
+        render_human_diagnostic(&mut output, diagnostic, &mut sources);
+    }
+    output
+}
+
+/// Render a single diagnostic for people
+fn render_human_diagnostic(
+    output: &mut String,
+    diagnostic: &Diagnostic,
+    sources: &mut SourceCache,
+) {
+    let _ = writeln!(
+        output,
+        "{}[{}]: {}",
+        diagnostic.severity, diagnostic.code, diagnostic.message
+    );
+    let gutter = diagnostic
+        .span
+        .as_ref()
+        .map_or(1, |span| span.line.to_string().len());
+    let pad = " ".repeat(gutter);
+
+    if let Some(file) = &diagnostic.file {
+        let location = match &diagnostic.span {
+            Some(Span {
+                line,
+                column: Some(column),
+                ..
+            }) => format!("{}:{}:{}", file.display(), line, column),
+            Some(span) => format!("{}:{}", file.display(), span.line),
+            None => file.display().to_string(),
+        };
+        let _ = writeln!(output, "{}--> {}", pad, location);
+        if let Some(span) = &diagnostic.span {
+            render_snippet(output, &pad, span, sources.line(file, span.line));
+        }
+    }
+    if let Some(suggestion) = &diagnostic.suggestion {
+        let _ = writeln!(output, "{} = help: {}", pad, suggestion);
+    }
+    output.push('\n');
+}
+
+/// Render the source line of a span, with the highlighted columns underlined
+fn render_snippet(output: &mut String, pad: &str, span: &Span, text: Option<&str>) {
+    let Some(text) = text else {
+        return;
+    };
+    let _ = writeln!(output, "{} |", pad);
+    let _ = writeln!(output, "{} | {}", span.line, text);
+    if let (Some(start), Some(end)) = (span.column, span.end_column) {
+        let marker = " ".repeat(start - 1) + &"^".repeat(end.saturating_sub(start).max(1));
+        let _ = writeln!(output, "{} | {}", pad, marker);
+    }
+}
+
+/// Render diagnostics as a JSON report
+///
+/// ```json
+/// {"diagnostics": [{"code": "broken-link", "severity": "error", ...}], "errors": 1, "warnings": 0, "notes": 0}
+/// ```
+pub fn render_json(diagnostics: &[Diagnostic]) -> Result<String, serde_json::Error> {
+    #[derive(Serialize)]
+    struct Report<'a> {
+        diagnostics: &'a [Diagnostic],
+        errors: usize,
+        warnings: usize,
+        notes: usize,
+    }
+
+    serde_json::to_string_pretty(&Report {
+        diagnostics,
+        errors: count(diagnostics, DiagnosticSeverity::Error),
+        warnings: count(diagnostics, DiagnosticSeverity::Warning),
+        notes: count(diagnostics, DiagnosticSeverity::Note),
+    })
+}
+
+/// Render diagnostics as a SARIF 2.1.0 log with one run of the `sysdoc` tool
+///
+/// Each diagnostic code becomes a rule, and each diagnostic a result whose
+/// location is relative to the document root. Suggestions are carried as a
+/// `suggestion` result property.
+pub fn render_sarif(diagnostics: &[Diagnostic]) -> Result<String, serde_json::Error> {
+    let mut codes: Vec<&str> = diagnostics.iter().map(|d| d.code.as_str()).collect();
+    codes.sort_unstable();
+    codes.dedup();
+    let rules: Vec<serde_json::Value> = codes
+        .iter()
+        .map(|code| serde_json::json!({ "id": code }))
+        .collect();
+    let results: Vec<serde_json::Value> = diagnostics.iter().map(sarif_result).collect();
+
+    serde_json::to_string_pretty(&serde_json::json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "sysdoc",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    }))
+}
+
+/// Convert a diagnostic into a SARIF result
+fn sarif_result(diagnostic: &Diagnostic) -> serde_json::Value {
+    let level = match diagnostic.severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+        DiagnosticSeverity::Note | DiagnosticSeverity::Off => "note",
+    };
+    let mut result = serde_json::json!({
+        "ruleId": diagnostic.code,
+        "level": level,
+        "message": { "text": diagnostic.message },
+    });
+
+    if let Some(file) = &diagnostic.file {
+        let uri = file.to_string_lossy().replace('\\', "/");
+        let mut location = serde_json::json!({
+            "physicalLocation": { "artifactLocation": { "uri": uri } }
+        });
+        if let Some(span) = &diagnostic.span {
+            let mut region = serde_json::json!({ "startLine": span.line });
+            if let (Some(start), Some(end)) = (span.column, span.end_column) {
+                region["startColumn"] = start.into();
+                region["endColumn"] = end.into();
+            }
+            location["physicalLocation"]["region"] = region;
+        }
+        result["locations"] = serde_json::json!([location]);
+    }
+    if let Some(suggestion) = &diagnostic.suggestion {
+        result["properties"] = serde_json::json!({ "suggestion": suggestion });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_source(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sysdoc-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(
+            dir.join("src/01_intro.md"),
+            "# Intro\n\nSee [setup](setup.md) for details.\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    fn broken_link(root: &Path) -> Diagnostic {
+        Diagnostic::error("broken-link", "Broken link 'setup.md'")
+            .at(root.join("src/01_intro.md"), 3, 5)
+            .highlighting("setup.md")
+            .with_suggestion("link to an existing section file")
+    }
+
+    #[test]
+    fn test_resolve_overrides_and_columns() {
+        let dir = write_source("diagnostics-resolve");
+        let overrides = BTreeMap::from([
+            ("spelling".to_string(), DiagnosticSeverity::Off),
+            ("suspect-link".to_string(), DiagnosticSeverity::Error),
+        ]);
+        let diagnostics = resolve(
+            vec![
+                broken_link(&dir),
+                Diagnostic::warning("spelling", "Unknown word 'detials'"),
+                Diagnostic::warning("suspect-link", "Suspect link"),
+            ],
+            &dir,
+            &overrides,
+        );
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics[0].file.as_deref(),
+            Some(Path::new("src/01_intro.md"))
+        );
+        assert_eq!(
+            diagnostics[0].span,
+            Some(Span {
+                line: 3,
+                column: Some(13),
+                end_column: Some(21),
+            })
+        );
+        assert_eq!(diagnostics[1].severity, DiagnosticSeverity::Error);
+        assert!(is_failure(&diagnostics, false));
+        assert!(!is_failure(&diagnostics[..0], true));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_render() {
+        let dir = write_source("diagnostics-render");
+        let diagnostics = resolve(vec![broken_link(&dir)], &dir, &BTreeMap::new());
+
+        assert_eq!(
+            render_human(&diagnostics, &dir),
+            "error[broken-link]: Broken link 'setup.md'\n \
+             --> src/01_intro.md:3:13\n  \
+             |\n\
+             3 | See [setup](setup.md) for details.\n  \
+             |             ^^^^^^^^\n  \
+             = help: link to an existing section file\n\n"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_json(&diagnostics).unwrap()).unwrap();
+        assert_eq!(json["errors"], 1);
+        assert_eq!(json["diagnostics"][0]["line"], 3);
+        assert_eq!(json["diagnostics"][0]["file"], "src/01_intro.md");
+
+        let sarif: serde_json::Value =
+            serde_json::from_str(&render_sarif(&diagnostics).unwrap()).unwrap();
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], "broken-link");
+        assert_eq!(result["level"], "error");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["region"]["startColumn"],
+            13
+        );
+        assert_eq!(
+            sarif["runs"][0]["tool"]["driver"]["rules"][0]["id"],
+            "broken-link"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_codes() {
+        let overrides = BTreeMap::from([
+            ("broken-link".to_string(), DiagnosticSeverity::Warning),
+            ("vague-word".to_string(), DiagnosticSeverity::Off),
+            ("broken-links".to_string(), DiagnosticSeverity::Off),
+            ("not-a-code".to_string(), DiagnosticSeverity::Error),
+        ]);
+        let unknown = unknown_codes(&overrides, Path::new("/doc/sysdoc.toml"));
+        let reported: Vec<(&str, Option<&str>)> = unknown
+            .iter()
+            .map(|d| (d.message.as_str(), d.suggestion.as_deref()))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (
+                    "Unknown diagnostic code 'broken-links' in [diagnostics]",
+                    Some("did you mean 'broken-link'?")
+                ),
+                (
+                    "Unknown diagnostic code 'not-a-code' in [diagnostics]",
+                    None
+                ),
+            ]
+        );
+        assert!(unknown
+            .iter()
+            .all(|d| d.code == "unknown-diagnostic-code"
+                && d.severity == DiagnosticSeverity::Warning));
+    }
+}
@@ -1,6 +1,7 @@
 //! Document configuration from sysdoc.toml
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
@@ -34,7 +35,9 @@ pub struct DocumentConfig {
     /// Standard the document follows (e.g., DI-IPSC-81435B)
     pub document_standard: String,
 
-    /// Template used to create the document
+    /// Built-in template the document was created from (empty if it was not
+    /// created from one, as for ReqIF imports)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub document_template: String,
 
     /// Path to the .docx file to use as template for generated docx files
@@ -58,6 +61,64 @@ pub struct DocumentConfig {
     /// Default: "#2B579A" (a professional blue)
     #[serde(default = "default_heading_color")]
     pub heading_color: String,
+
+    /// Upstream requirement catalogs whose IDs may appear in `traced_ids`
+    ///
+    /// When at least one catalog is declared, validation rejects traced IDs that
+    /// are not defined in a catalog or as a `section_id` in this document.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub catalogs: Vec<CatalogConfig>,
+
+    /// Sibling sysdoc projects whose `section_id`s may appear in `traced_ids`
+    /// (`[[upstream]]` in sysdoc.toml)
+    #[serde(default, rename = "upstream", skip_serializing_if = "Vec::is_empty")]
+    pub upstreams: Vec<UpstreamConfig>,
+
+    /// Sysdoc projects that trace to this document, followed by `sysdoc trace impact`
+    /// (`[[downstream]]` in sysdoc.toml)
+    #[serde(default, rename = "downstream", skip_serializing_if = "Vec::is_empty")]
+    pub downstreams: Vec<UpstreamConfig>,
+
+    /// Source code scanned for annotations naming the sections it implements
+    /// (`[code_trace]` in sysdoc.toml)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_trace: Option<CodeTraceConfig>,
+
+    /// Test result files mapped onto sections through their `test_cases`
+    /// (`[[test_results]]` in sysdoc.toml)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub test_results: Vec<TestResultsConfig>,
+
+    /// Section ID schemes keyed by document type (`[id_schemes.<document_type>]`
+    /// in sysdoc.toml); the scheme matching `document_type` applies
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub id_schemes: BTreeMap<String, IdSchemeConfig>,
+
+    /// Declarations of custom section attributes (`[attributes.<name>]` in
+    /// sysdoc.toml); undeclared attributes are accepted without checks
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, AttributeConfig>,
+
+    /// Lint rule settings (`[lint]` in sysdoc.toml)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lint: Option<LintConfig>,
+
+    /// Spell checking settings (`[spelling]` in sysdoc.toml)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spelling: Option<SpellingConfig>,
+
+    /// Severity per diagnostic code, overriding the code's default (`[diagnostics]` in sysdoc.toml)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub diagnostics: BTreeMap<String, DiagnosticSeverity>,
+
+    /// Orphaned asset detection settings (`[assets]` in sysdoc.toml)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assets: Option<AssetsConfig>,
+
+    /// Markdown files in document order, with optional numbering overrides
+    /// (`[outline]` in sysdoc.toml)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outline: Option<OutlineConfig>,
 }
 
 fn default_revision_tag_pattern() -> String {
@@ -68,6 +129,459 @@ fn default_heading_color() -> String {
     "#2B579A".to_string()
 }
 
+/// Declaration of an upstream requirement catalog (`[[catalogs]]` in sysdoc.toml)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogConfig {
+    /// Path to the catalog file, relative to the document root
+    pub path: String,
+
+    /// File format; inferred from the file extension when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<CatalogFormat>,
+
+    /// Name of the column (CSV) or key (JSON) holding the requirement ID
+    #[serde(default = "default_id_column")]
+    pub id_column: String,
+
+    /// Name of the column (CSV) or key (JSON) holding the requirement title
+    #[serde(default = "default_title_column")]
+    pub title_column: String,
+
+    /// Name of the column (CSV) or key (JSON) holding the requirement text
+    #[serde(default = "default_text_column")]
+    pub text_column: String,
+}
+
+fn default_id_column() -> String {
+    "id".to_string()
+}
+
+fn default_title_column() -> String {
+    "title".to_string()
+}
+
+fn default_text_column() -> String {
+    "text".to_string()
+}
+
+/// Declaration of a linked sysdoc project (`[[upstream]]` or `[[downstream]]` in sysdoc.toml)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpstreamConfig {
+    /// Path to the linked project's root directory, relative to the document root
+    pub path: String,
+}
+
+/// Supported requirement catalog file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CatalogFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// A JSON array of objects
+    Json,
+    /// A ReqIF exchange file (spec objects become catalog entries)
+    Reqif,
+}
+
+impl CatalogFormat {
+    /// Infer the catalog format from a file extension
+    ///
+    /// # Returns
+    /// * `Some(CatalogFormat)` - Recognised extension (.csv, .json or .reqif)
+    /// * `None` - Unknown or missing extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase)
+            .as_deref()
+        {
+            Some("csv") => Some(Self::Csv),
+            Some("json") => Some(Self::Json),
+            Some("reqif") => Some(Self::Reqif),
+            _ => None,
+        }
+    }
+}
+
+/// Source code annotation scanning (`[code_trace]` in sysdoc.toml)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeTraceConfig {
+    /// Directories (or files) to scan, relative to the document root
+    pub paths: Vec<String>,
+
+    /// Regexes matching annotations; capture group 1 holds one or more IDs
+    /// separated by commas or whitespace
+    #[serde(default = "default_code_trace_patterns")]
+    pub patterns: Vec<String>,
+
+    /// File extensions to scan (all text files when empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<String>,
+
+    /// Append an "Implemented By" table to each section referenced from code
+    #[serde(default = "default_true")]
+    pub implemented_by_table: bool,
+}
+
+fn default_code_trace_patterns() -> Vec<String> {
+    vec![r"@implements\s+([\w.\-]+(?:\s*,\s*[\w.\-]+)*)".to_string()]
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Declaration of a test results file (`[[test_results]]` in sysdoc.toml)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestResultsConfig {
+    /// Path to the results file, relative to the document root
+    pub path: String,
+
+    /// File format; inferred from the file extension when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<TestResultsFormat>,
+
+    /// Name of the CSV column holding the test name
+    #[serde(default = "default_test_column")]
+    pub test_column: String,
+
+    /// Name of the CSV column holding the result (pass, fail, skip, ...)
+    #[serde(default = "default_status_column")]
+    pub status_column: String,
+}
+
+fn default_test_column() -> String {
+    "test".to_string()
+}
+
+fn default_status_column() -> String {
+    "status".to_string()
+}
+
+/// Supported test results file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestResultsFormat {
+    /// JUnit XML report
+    Junit,
+    /// Comma-separated values with test and status columns
+    Csv,
+}
+
+impl TestResultsFormat {
+    /// Infer the results format from a file extension
+    ///
+    /// # Returns
+    /// * `Some(TestResultsFormat)` - Recognised extension (.xml or .csv)
+    /// * `None` - Unknown or missing extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase)
+            .as_deref()
+        {
+            Some("xml") => Some(Self::Junit),
+            Some("csv") => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// Section ID scheme for a document type (`[id_schemes.<document_type>]` in sysdoc.toml)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdSchemeConfig {
+    /// Template for new IDs, with `{n}` or a zero-padded `{n:03}` placeholder
+    /// for the sequence number (e.g., "SDD-{n:03}")
+    pub format: String,
+
+    /// Regex that every `section_id` must match; derived from `format` when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+/// Declaration of a custom section attribute (`[attributes.<name>]` in sysdoc.toml)
+///
+/// Sections set attributes in the `[attributes]` table of their sysdoc block.
+/// Validation checks the value of each declared attribute against its type and
+/// allowed values, and that sections with a `section_id` set it where required.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AttributeConfig {
+    /// Type of the attribute value
+    #[serde(default, rename = "type")]
+    pub kind: AttributeKind,
+
+    /// Allowed values (for lists, of each element); any value when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+
+    /// Every section with a `section_id` must set the attribute
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+
+    /// Sections with a `section_id` at these depths (1 for chapters, 2 for
+    /// their subsections, ...) must set the attribute
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_depths: Vec<usize>,
+
+    /// Optional description of the attribute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl AttributeConfig {
+    /// Check whether a section at the given depth (1 for chapters) must set the attribute
+    pub fn is_required_at(&self, depth: usize) -> bool {
+        self.required || self.required_depths.contains(&depth)
+    }
+}
+
+/// Type of a custom section attribute
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeKind {
+    /// A string (e.g., `owner = "J. Smith"`)
+    #[default]
+    String,
+    /// An integer (e.g., `priority = 2`)
+    Integer,
+    /// A floating point number (e.g., `mass_kg = 2.5`)
+    Float,
+    /// A boolean (e.g., `safety_critical = true`)
+    Boolean,
+    /// A date, time or date-time (e.g., `reviewed = 2024-03-01`)
+    Datetime,
+    /// An array of strings (e.g., `platforms = ["linux", "windows"]`)
+    List,
+    /// An inline table (e.g., `limits = { min = 0, max = 10 }`)
+    Table,
+}
+
+impl std::fmt::Display for AttributeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Boolean => "boolean",
+            Self::Datetime => "datetime",
+            Self::List => "list",
+            Self::Table => "table",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Lint rule settings (`[lint]` in sysdoc.toml)
+///
+/// ```toml
+/// [lint]
+/// max_paragraph_words = 120
+///
+/// [lint.rules]
+/// vague-word = "error"
+/// long-paragraph = "off"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    /// Severity per rule name, overriding the rule's default; `"off"` disables a rule
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rules: BTreeMap<String, LintSeverity>,
+
+    /// Maximum number of words in a paragraph (`long-paragraph`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_paragraph_words: Option<usize>,
+
+    /// Words and phrases reported by `vague-word`, replacing the built-in list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vague_words: Option<Vec<String>>,
+}
+
+/// Severity of a lint rule's findings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    /// The rule is disabled
+    Off,
+    /// Findings are reported but do not fail `sysdoc lint`
+    Warning,
+    /// Findings fail `sysdoc lint`
+    Error,
+}
+
+impl std::fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Off => "off",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        };
+        f.pad(name)
+    }
+}
+
+/// Severity of a diagnostic reported by `build`, `validate` or `lint`
+///
+/// `[diagnostics]` in sysdoc.toml maps diagnostic codes to a severity:
+///
+/// ```toml
+/// [diagnostics]
+/// broken-link = "warning"
+/// suspect-link = "error"
+/// spelling = "off"
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    /// The diagnostic is not reported
+    Off,
+    /// Informational, never fails a command
+    Note,
+    /// Reported, fails a command only with `--deny-warnings`
+    Warning,
+    /// Fails the command
+    Error,
+}
+
+impl std::fmt::Display for DiagnosticSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Off => "off",
+            Self::Note => "note",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        };
+        f.pad(name)
+    }
+}
+
+impl From<LintSeverity> for DiagnosticSeverity {
+    fn from(severity: LintSeverity) -> Self {
+        match severity {
+            LintSeverity::Off => Self::Off,
+            LintSeverity::Warning => Self::Warning,
+            LintSeverity::Error => Self::Error,
+        }
+    }
+}
+
+/// Spell checking settings (`[spelling]` in sysdoc.toml)
+///
+/// ```toml
+/// [spelling]
+/// language = "en-GB"
+/// word_list = "words/en_GB.txt"
+/// dictionary = "dictionary.txt"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpellingConfig {
+    /// Spelling variant of the document (`en-GB` requires `word_list`)
+    #[serde(default)]
+    pub language: SpellingLanguage,
+
+    /// Word list replacing the bundled American English one, one word per line
+    /// (relative to the document root)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_list: Option<String>,
+
+    /// Project dictionary of extra words, one per line (relative to the document root)
+    #[serde(default = "default_project_dictionary")]
+    pub dictionary: String,
+}
+
+impl Default for SpellingConfig {
+    fn default() -> Self {
+        Self {
+            language: SpellingLanguage::default(),
+            word_list: None,
+            dictionary: default_project_dictionary(),
+        }
+    }
+}
+
+fn default_project_dictionary() -> String {
+    "dictionary.txt".to_string()
+}
+
+/// Orphaned asset detection settings (`[assets]` in sysdoc.toml)
+///
+/// ```toml
+/// [assets]
+/// ignore = ["src/**/*.psd", "src/logos/*"]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AssetsConfig {
+    /// Glob patterns of files under `src/` that are never reported as orphaned
+    ///
+    /// Patterns containing `/` match the path relative to the document root;
+    /// others match the file name. `*` and `?` do not cross `/`, `**` does.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
+/// Explicit document outline (`[outline]` in sysdoc.toml)
+///
+/// Section numbers of the listed files are computed from their order and
+/// level instead of being read from their file names.
+///
+/// ```toml
+/// [outline]
+/// files = [
+///     "src/introduction.md",
+///     { file = "src/purpose.md", level = 2 },
+///     { file = "src/glossary.md", number = "9" },
+/// ]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutlineConfig {
+    /// Markdown files in document order
+    #[serde(default)]
+    pub files: Vec<OutlineFileConfig>,
+}
+
+/// A file listed in `[outline]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum OutlineFileConfig {
+    /// Path to the file, relative to the document root
+    Path(String),
+    /// File with a numbering override, level or title
+    Entry {
+        /// Path to the file, relative to the document root
+        file: String,
+        /// Section number of the file (e.g., "3.2"); later files are numbered on from it
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        number: Option<String>,
+        /// Outline level (1 for chapters, 2 for their sections, ...); defaults to
+        /// the level of the previous file
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        level: Option<usize>,
+        /// Section title; defaults to one derived from the file name
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+    },
+}
+
+/// Spelling variant of a document
+///
+/// The bundled word list is American English; British English needs a
+/// `word_list` in `[spelling]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpellingLanguage {
+    /// American English
+    #[default]
+    #[serde(rename = "en-US")]
+    EnUs,
+    /// British English
+    #[serde(rename = "en-GB")]
+    EnGb,
+}
+
 /// Person information (owner, approver, etc.)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Person {
@@ -96,6 +610,11 @@ impl DocumentConfig {
         Ok(config)
     }
 
+    /// Section ID scheme declared for this document's `document_type`, if any
+    pub fn id_scheme(&self) -> Option<&IdSchemeConfig> {
+        self.id_schemes.get(&self.document_type)
+    }
+
     /// Save configuration to a sysdoc.toml file
     ///
     /// # Parameters
@@ -174,6 +693,18 @@ mod tests {
             title_page_background: None,
             revision_tag_pattern: default_revision_tag_pattern(),
             heading_color: default_heading_color(),
+            catalogs: Vec::new(),
+            upstreams: Vec::new(),
+            downstreams: Vec::new(),
+            code_trace: None,
+            test_results: Vec::new(),
+            id_schemes: BTreeMap::new(),
+            attributes: BTreeMap::new(),
+            lint: None,
+            spelling: None,
+            diagnostics: BTreeMap::new(),
+            assets: None,
+            outline: None,
         };
 
         // Serialize to TOML
@@ -223,5 +754,119 @@ email = "bob.martinez@aerospace.com"
         assert_eq!(config.document_id, "SRS-2024-001");
         assert_eq!(config.document_owner.name, "Alice Johnson");
         assert_eq!(config.document_approver.email, "bob.martinez@aerospace.com");
+        assert!(config.catalogs.is_empty());
+    }
+
+    #[test]
+    fn test_document_template_optional() {
+        let toml_content = r#"
+document_id = "SDD-001"
+document_title = "Design"
+document_type = "SDD"
+document_standard = "DI-IPSC-81435B"
+
+[document_owner]
+name = "Owner"
+email = "owner@example.com"
+
+[document_approver]
+name = "Approver"
+email = "approver@example.com"
+"#;
+
+        let config: DocumentConfig = toml::from_str(toml_content).unwrap();
+        assert!(config.document_template.is_empty());
+        let serialized = toml::to_string(&config).unwrap();
+        assert!(!serialized.contains("document_template"));
+    }
+
+    #[test]
+    fn test_parse_trace_sources() {
+        let toml_content = r#"
+document_id = "SDD-001"
+document_title = "Design"
+document_type = "SDD"
+document_standard = "DI-IPSC-81435B"
+document_template = "sdd-standard-v1"
+
+[document_owner]
+name = "Owner"
+email = "owner@example.com"
+
+[document_approver]
+name = "Approver"
+email = "approver@example.com"
+
+[[catalogs]]
+path = "catalogs/srs.csv"
+
+[[catalogs]]
+path = "catalogs/sss.export"
+format = "json"
+id_column = "key"
+
+[[upstream]]
+path = "../srs"
+
+[code_trace]
+paths = ["../src"]
+extensions = ["rs"]
+
+[[test_results]]
+path = "results/junit.xml"
+"#;
+
+        let config: DocumentConfig = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.catalogs.len(), 2);
+        assert_eq!(config.catalogs[0].format, None);
+        assert_eq!(config.catalogs[0].id_column, "id");
+        assert_eq!(config.catalogs[0].title_column, "title");
+        assert_eq!(config.catalogs[1].format, Some(CatalogFormat::Json));
+        assert_eq!(config.catalogs[1].id_column, "key");
+        assert_eq!(config.upstreams.len(), 1);
+        assert_eq!(config.upstreams[0].path, "../srs");
+        let code_trace = config.code_trace.unwrap();
+        assert_eq!(code_trace.paths, vec!["../src"]);
+        assert_eq!(code_trace.patterns, default_code_trace_patterns());
+        assert!(code_trace.implemented_by_table);
+        assert_eq!(config.test_results[0].format, None);
+        assert_eq!(config.test_results[0].test_column, "test");
+    }
+
+    #[test]
+    fn test_parse_outline() {
+        let toml_content = r#"
+system_id = "SYS"
+document_id = "SDD-001"
+document_title = "Test"
+document_type = "SDD"
+document_standard = "DI-IPSC-81435B"
+document_template = "sdd-standard-v1"
+
+[document_owner]
+name = "Owner"
+email = "owner@example.com"
+
+[document_approver]
+name = "Approver"
+email = "approver@example.com"
+
+[outline]
+files = [
+    "src/introduction.md",
+    { file = "src/purpose.md", level = 2, title = "Purpose and Scope" },
+]
+"#;
+
+        let config: DocumentConfig = toml::from_str(toml_content).unwrap();
+        let files = config.outline.unwrap().files;
+        assert!(
+            matches!(&files[0], OutlineFileConfig::Path(path) if path == "src/introduction.md")
+        );
+        assert!(matches!(
+            &files[1],
+            OutlineFileConfig::Entry { file, number: None, level: Some(2), title: Some(_) }
+                if file == "src/purpose.md"
+        ));
     }
 }
@@ -21,6 +21,7 @@
 //! - Document properties
 
 use crate::source_model::{Alignment, ListItem, MarkdownBlock, MarkdownSection, TextRun};
+use crate::text::escape_xml;
 use crate::unified_document::{DocumentMetadata, UnifiedDocument};
 use std::collections::HashMap;
 use std::io::{Read, Write};
@@ -836,15 +837,6 @@ fn block_type_name(block: &MarkdownBlock) -> &'static str {
     }
 }
 
-/// Escape special XML characters
-fn escape_xml(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&apos;")
-}
-
 /// Format ISO 8601 datetime for use in docProps with W3CDTF type
 ///
 /// Simply validates the string isn't empty. Git already provides ISO 8601 format.
@@ -19,11 +19,14 @@
 #![allow(dead_code)]
 
 mod cli;
+mod diagnostics;
 mod document_config;
 mod document_model;
 mod document_section;
+mod paths;
 mod template_config;
 mod templates;
+mod text;
 mod walker;
 
 // New three-stage pipeline modules
@@ -43,9 +46,45 @@ mod html_exporter;
 // PDF exporter (Typst-based)
 mod typst_exporter;
 
+// ReqIF exporter
+mod reqif_exporter;
+
+// Traceability matrix exporter (CSV/XLSX)
+mod trace_matrix_exporter;
+
+// ReqIF importer
+mod reqif_importer;
+
+// Section insertion, moves and renumbering
+mod renumber;
+
 use anyhow::{Context, Result};
 use clap::Parser;
-use cli::{Cli, Commands, OutputFormat};
+use cli::{
+    BaselineCommands, Cli, Commands, CoverageFormat, IdsCommands, ImportCommands, MessageFormat,
+    OutputFormat, ReportFormat, SectionCommands, TraceCommands, TraceExportFormat,
+};
+use diagnostics::Diagnostic;
+use document_config::DiagnosticSeverity;
+use std::collections::BTreeMap;
+
+/// How `build`, `validate` and `lint` report diagnostics
+#[derive(Debug, Clone, Copy)]
+struct DiagnosticOptions {
+    /// Output format of the diagnostics
+    format: MessageFormat,
+    /// Whether warnings fail the command
+    deny_warnings: bool,
+}
+
+impl DiagnosticOptions {
+    fn new(format: MessageFormat, deny_warnings: bool) -> Self {
+        Self {
+            format,
+            deny_warnings,
+        }
+    }
+}
 
 /// Main entry point for the sysdoc CLI application
 fn main() {
@@ -76,8 +115,12 @@ fn run() -> Result<()> {
             verbose,
             no_toc: _,
             no_images,
+            release,
+            message_format,
+            deny_warnings,
         } => {
-            handle_build_command(input, output, format, verbose, no_images)?;
+            let options = DiagnosticOptions::new(message_format, deny_warnings);
+            handle_build_command(input, output, format, verbose, no_images, release, options)?;
         }
 
         Commands::Validate {
@@ -85,13 +128,162 @@ fn run() -> Result<()> {
             verbose,
             check_images,
             check_tables,
+            release,
+            add_words,
+            message_format,
+            deny_warnings,
+        } => {
+            let options = DiagnosticOptions::new(message_format, deny_warnings);
+            if add_words.is_empty() {
+                handle_validate_command(
+                    input,
+                    verbose,
+                    check_images,
+                    check_tables,
+                    release,
+                    options,
+                )?;
+            } else {
+                handle_add_words_command(input, &add_words)?;
+            }
+        }
+
+        Commands::Lint {
+            input,
+            list_rules,
+            add_words,
+            message_format,
+            deny_warnings,
         } => {
-            handle_validate_command(input, verbose, check_images, check_tables)?;
+            if list_rules {
+                handle_lint_list_rules_command();
+            } else if !add_words.is_empty() {
+                handle_add_words_command(input, &add_words)?;
+            } else {
+                handle_lint_command(input, DiagnosticOptions::new(message_format, deny_warnings))?;
+            }
         }
 
         Commands::ListTemplates => {
             handle_list_templates_command();
         }
+
+        Commands::CleanAssets {
+            input,
+            dry_run: _,
+            apply,
+        } => {
+            handle_clean_assets_command(input, apply)?;
+        }
+
+        Commands::Trace { command } => match command {
+            TraceCommands::Coverage {
+                input,
+                format,
+                output,
+                min_coverage,
+                max_links,
+                requirement_prefix,
+                section_prefix,
+            } => {
+                let options = source_model::CoverageOptions {
+                    requirement_prefix,
+                    section_prefix,
+                    over_trace_threshold: max_links,
+                };
+                handle_trace_coverage_command(input, format, output, min_coverage, &options)?;
+            }
+            TraceCommands::Export {
+                input,
+                output,
+                format,
+            } => {
+                handle_trace_export_command(input, output, format)?;
+            }
+            TraceCommands::Accept {
+                input,
+                section,
+                target,
+            } => {
+                handle_trace_accept_command(input, section, target)?;
+            }
+            TraceCommands::Impact {
+                input,
+                ids,
+                since,
+                format,
+                output,
+            } => {
+                handle_trace_impact_command(input, ids, since, format, output)?;
+            }
+            TraceCommands::Graph {
+                input,
+                output,
+                section_id_prefix,
+                chapter,
+            } => {
+                handle_trace_graph_command(input, output, section_id_prefix, chapter)?;
+            }
+        },
+
+        Commands::Import { command } => match command {
+            ImportCommands::Reqif {
+                file,
+                into,
+                catalog,
+                force,
+            } => {
+                handle_import_reqif_command(file, into, catalog, force)?;
+            }
+        },
+        Commands::Baseline { command } => match command {
+            BaselineCommands::Create { name, input, force } => {
+                handle_baseline_create_command(input, name, force)?;
+            }
+            BaselineCommands::Diff {
+                from,
+                to,
+                input,
+                format,
+                output,
+            } => {
+                handle_baseline_diff_command(input, from, to, format, output)?;
+            }
+        },
+        Commands::Ids { command } => match command {
+            IdsCommands::Assign {
+                input,
+                all,
+                dry_run,
+            } => {
+                handle_ids_assign_command(input, all, dry_run)?;
+            }
+        },
+
+        Commands::Section { command } => match command {
+            SectionCommands::Insert {
+                number,
+                title,
+                input,
+                dry_run,
+            } => {
+                let plan = renumber::plan_insert(&input, &number, &title);
+                handle_layout_plan(&input, plan, dry_run)?;
+            }
+            SectionCommands::Move {
+                from,
+                to,
+                input,
+                dry_run,
+            } => {
+                let plan = renumber::plan_move(&input, &from, &to);
+                handle_layout_plan(&input, plan, dry_run)?;
+            }
+        },
+
+        Commands::Renumber { input, dry_run } => {
+            handle_layout_plan(&input, renumber::plan_renumber(&input), dry_run)?;
+        }
     }
 
     Ok(())
@@ -163,6 +355,8 @@ fn handle_build_command(
     format_arg: Option<OutputFormat>,
     verbose: bool,
     no_images: bool,
+    release: bool,
+    options: DiagnosticOptions,
 ) -> Result<()> {
     // Canonicalize input path to get absolute path with drive letter on Windows
     let input = input
@@ -179,6 +373,7 @@ fn handle_build_command(
                     OutputFormat::Markdown => "md",
                     OutputFormat::Html => "html",
                     OutputFormat::Pdf => "pdf",
+                    OutputFormat::Reqif => "reqif",
                 };
                 output.set_extension(ext);
             }
@@ -191,9 +386,10 @@ fn handle_build_command(
                 Some("md") | Some("markdown") => OutputFormat::Markdown,
                 Some("html") | Some("htm") => OutputFormat::Html,
                 Some("pdf") => OutputFormat::Pdf,
+                Some("reqif") => OutputFormat::Reqif,
                 Some(ext) => {
                     anyhow::bail!(
-                        "Unknown output format for extension '.{}'. Supported: .docx, .md, .html, .pdf\nUse --format to specify explicitly.",
+                        "Unknown output format for extension '.{}'. Supported: .docx, .md, .html, .pdf, .reqif\nUse --format to specify explicitly.",
                         ext
                     );
                 }
@@ -207,31 +403,49 @@ fn handle_build_command(
     };
 
     // Initialize logging if verbose
+    let human = options.format == MessageFormat::Human;
     if verbose {
         env_logger::Builder::from_default_env()
             .filter_level(log::LevelFilter::Info)
             .init();
-        print_build_info(&input, &output, format, no_images);
+        if human {
+            print_build_info(&input, &output, format, no_images);
+        }
     }
 
-    println!("Building documentation...");
-    println!("Input: {}", input.display());
-    println!("Output: {}", output.display());
+    if human {
+        println!("Building documentation...");
+        println!("Input: {}", input.display());
+        println!("Output: {}", output.display());
 
-    // Stage 1: Parse all source files (includes validation)
-    println!("\n[Stage 1/3] Parsing source files...");
-    let source_model = match pipeline::parse_sources(&input) {
-        Ok(model) => model,
-        Err(e) => {
-            eprintln!("✗ Validation failed:\n");
-            eprintln!("{}", format_parse_error(&e));
+        // Stage 1: Parse all source files (includes validation)
+        println!("\n[Stage 1/3] Parsing source files...");
+    }
+    let (source_model, mut found) = pipeline::check_sources(&input);
+    if let (Some(model), true) = (&source_model, release) {
+        found.extend(release_diagnostics(model));
+    }
+    let overrides = diagnostic_overrides(&input, source_model.as_ref());
+    found.extend(diagnostics::unknown_codes(
+        &overrides,
+        &input.join("sysdoc.toml"),
+    ));
+    let mut diagnostics = diagnostics::resolve(found, &input, &overrides);
+    let mut source_model = match source_model {
+        Some(model) if !diagnostics::is_failure(&diagnostics, options.deny_warnings) => model,
+        _ => {
+            report_diagnostics(&input, &diagnostics, options)?;
             anyhow::bail!("Build failed due to validation errors");
         }
     };
-    println!(
-        "✓ Parsed {} markdown files, validation passed",
-        source_model.markdown_files.len()
-    );
+    if human {
+        println!(
+            "✓ Parsed {} markdown files, validation passed",
+            source_model.markdown_files.len()
+        );
+    }
+    let graph_failures = source_model.write_trace_graphs();
+    diagnostics.extend(diagnostics::resolve(graph_failures, &input, &overrides));
 
     // Extract template path from config before consuming source_model
     let docx_template_path = source_model
@@ -241,61 +455,174 @@ fn handle_build_command(
         .map(|p| input.join(p));
 
     // Stage 2: Transform to unified document
-    println!("\n[Stage 2/3] Transforming to unified document...");
-    let unified_doc = pipeline::transform(source_model)
-        .with_context(|| "Failed to transform source model to unified document")?;
-
-    println!("✓ Transformed {} sections", unified_doc.sections.len());
-    if verbose {
-        println!("  - {} words", unified_doc.word_count());
-        println!("  - {} images", unified_doc.image_count());
-        println!("  - {} tables", unified_doc.table_count());
+    if human {
+        println!("\n[Stage 2/3] Transforming to unified document...");
     }
+    let unified_doc = match pipeline::transform(source_model) {
+        Ok(doc) => doc,
+        Err(e) => {
+            diagnostics.extend(diagnostics::resolve(
+                vec![e.diagnostic()],
+                &input,
+                &overrides,
+            ));
+            report_diagnostics(&input, &diagnostics, options)?;
+            anyhow::bail!("Failed to transform source model to unified document");
+        }
+    };
 
-    // Stage 3: Export to output format
-    println!(
-        "\n[Stage 3/3] Exporting to {}...",
-        match format {
-            OutputFormat::Docx => "DOCX",
-            OutputFormat::Markdown => "Markdown",
-            OutputFormat::Html => "HTML",
-            OutputFormat::Pdf => "PDF",
+    if human {
+        println!("✓ Transformed {} sections", unified_doc.sections.len());
+        if verbose {
+            println!("  - {} words", unified_doc.word_count());
+            println!("  - {} images", unified_doc.image_count());
+            println!("  - {} tables", unified_doc.table_count());
         }
-    );
 
+        // Stage 3: Export to output format
+        println!(
+            "\n[Stage 3/3] Exporting to {}...",
+            match format {
+                OutputFormat::Docx => "DOCX",
+                OutputFormat::Markdown => "Markdown",
+                OutputFormat::Html => "HTML",
+                OutputFormat::Pdf => "PDF",
+                OutputFormat::Reqif => "ReqIF",
+            }
+        );
+    }
+
+    let exported = export_document(&unified_doc, format, docx_template_path.as_deref(), &output);
+    if let Err(e) = &exported {
+        let diagnostic = Diagnostic::error("export-failed", format!("{:#}", e));
+        diagnostics.extend(diagnostics::resolve(vec![diagnostic], &input, &overrides));
+    }
+    if report_diagnostics(&input, &diagnostics, options)? {
+        anyhow::bail!("Build failed");
+    }
+
+    if human {
+        println!("✓ Successfully wrote: {}", output.display());
+        println!("\n✓ Build completed successfully!");
+    }
+
+    Ok(())
+}
+
+/// Export the unified document in the requested format
+///
+/// # Parameters
+/// * `doc` - The unified document
+/// * `format` - Output format
+/// * `docx_template_path` - The `docx_template_path` of sysdoc.toml, resolved against the input
+/// * `output` - Output file path
+///
+/// # Returns
+/// * `Ok(())` - The document was written
+/// * `Err` - DOCX export has no template, or the exporter failed
+fn export_document(
+    doc: &unified_document::UnifiedDocument,
+    format: OutputFormat,
+    docx_template_path: Option<&std::path::Path>,
+    output: &std::path::Path,
+) -> Result<()> {
     match format {
         OutputFormat::Docx => {
-            let template_path = docx_template_path.as_ref().ok_or_else(|| {
+            let template_path = docx_template_path.ok_or_else(|| {
                 anyhow::anyhow!(
                     "DOCX export requires a template. Set 'docx_template_path' in sysdoc.toml"
                 )
             })?;
-            docx_template_exporter::to_docx(&unified_doc, template_path, &output)
-                .with_context(|| format!("Failed to export DOCX to {}", output.display()))?;
-            println!("✓ Successfully wrote: {}", output.display());
-        }
-        OutputFormat::Markdown => {
-            pipeline::export::to_markdown(&unified_doc, &output)
-                .with_context(|| format!("Failed to export Markdown to {}", output.display()))?;
-            println!("✓ Successfully wrote: {}", output.display());
-        }
-        OutputFormat::Html => {
-            pipeline::export::to_html(&unified_doc, &output)
-                .with_context(|| format!("Failed to export HTML to {}", output.display()))?;
-            println!("✓ Successfully wrote: {}", output.display());
-        }
-        OutputFormat::Pdf => {
-            typst_exporter::to_pdf(&unified_doc, &output)
-                .with_context(|| format!("Failed to export PDF to {}", output.display()))?;
-            println!("✓ Successfully wrote: {}", output.display());
+            docx_template_exporter::to_docx(doc, template_path, output)
+                .with_context(|| format!("Failed to export DOCX to {}", output.display()))
         }
+        OutputFormat::Markdown => pipeline::export::to_markdown(doc, output)
+            .with_context(|| format!("Failed to export Markdown to {}", output.display())),
+        OutputFormat::Html => pipeline::export::to_html(doc, output)
+            .with_context(|| format!("Failed to export HTML to {}", output.display())),
+        OutputFormat::Pdf => typst_exporter::to_pdf(doc, output)
+            .with_context(|| format!("Failed to export PDF to {}", output.display())),
+        OutputFormat::Reqif => reqif_exporter::to_reqif(doc, output)
+            .with_context(|| format!("Failed to export ReqIF to {}", output.display())),
     }
+}
 
-    println!("\n✓ Build completed successfully!");
+/// Handle the lint command
+///
+/// # Parameters
+/// * `input` - Path to the document directory containing sysdoc.toml
+/// * `options` - Diagnostic output format and whether warnings fail the command
+///
+/// # Returns
+/// * `Ok(())` - Parsing and validation passed and no lint rule reported an error
+/// * `Err` - Parsing failed, the lint configuration is invalid, or errors were found
+fn handle_lint_command(input: std::path::PathBuf, options: DiagnosticOptions) -> Result<()> {
+    // Lint the authored content, before generated tables are added
+    let (model, mut found) = pipeline::check_sources_with(&input, |model| -> Result<_> {
+        let dictionary = model.spelling_dictionary()?;
+        let findings = source_model::lint(
+            &model.markdown_files,
+            model.config.lint.as_ref(),
+            Some(&dictionary),
+        )?;
+        Ok(findings.iter().map(Diagnostic::from).collect())
+    })?;
 
+    let overrides = diagnostic_overrides(&input, model.as_ref());
+    found.extend(diagnostics::unknown_codes(
+        &overrides,
+        &input.join("sysdoc.toml"),
+    ));
+    let diagnostics = diagnostics::resolve(found, &input, &overrides);
+    let failed = report_diagnostics(&input, &diagnostics, options)?;
+    if options.format == MessageFormat::Human && diagnostics.is_empty() {
+        println!("✓ No lint findings");
+    }
+    if failed {
+        anyhow::bail!("Lint failed");
+    }
+    Ok(())
+}
+
+/// Handle the lint/validate --add-word option
+///
+/// # Parameters
+/// * `input` - Path to the document directory containing sysdoc.toml
+/// * `words` - Words to add to the project spelling dictionary
+///
+/// # Returns
+/// * `Ok(())` - Words added (or already present)
+/// * `Err` - sysdoc.toml or the dictionary could not be read, or the dictionary could not be written
+fn handle_add_words_command(input: std::path::PathBuf, words: &[String]) -> Result<()> {
+    let config_path = input.join("sysdoc.toml");
+    let config = document_config::DocumentConfig::load(&config_path)
+        .with_context(|| format!("Failed to load {}", config_path.display()))?;
+    let spelling = config.spelling.unwrap_or_default();
+
+    let mut added: std::collections::HashSet<String> =
+        source_model::add_words(&input, &spelling, words)?
+            .into_iter()
+            .collect();
+    for word in words {
+        if added.remove(word.trim()) {
+            println!("✓ Added '{}' to {}", word, spelling.dictionary);
+        } else {
+            println!("  '{}' is already in {}", word, spelling.dictionary);
+        }
+    }
     Ok(())
 }
 
+/// Handle the lint --list-rules command
+fn handle_lint_list_rules_command() {
+    for rule in source_model::LINT_RULES {
+        println!(
+            "{:<24} {:<8} {}",
+            rule.name, rule.default_severity, rule.description
+        );
+    }
+}
+
 /// Handle the validate command
 ///
 /// Validates the document structure and references. By default, all validation
@@ -306,6 +633,8 @@ fn handle_build_command(
 /// * `verbose` - Show detailed validation output
 /// * `check_images` - Only check image references
 /// * `check_tables` - Only check CSV table references
+/// * `release` - Also fail if any TBD/TBR items remain
+/// * `options` - Diagnostic output format and whether warnings fail the command
 ///
 /// # Returns
 /// * `Ok(())` - All validation checks passed
@@ -315,9 +644,13 @@ fn handle_validate_command(
     verbose: bool,
     check_images: bool,
     check_tables: bool,
+    release: bool,
+    options: DiagnosticOptions,
 ) -> Result<()> {
     // Determine if we're running selective checks or all checks
     let selective_mode = check_images || check_tables;
+    let human = options.format == MessageFormat::Human;
+    let verbose = verbose && human;
 
     if verbose {
         println!("Validating document at: {}", input.display());
@@ -342,30 +675,672 @@ fn handle_validate_command(
         );
     }
 
-    // Parse sources - this runs validation internally
+    // Parse and validate sources, then run the checks that need a parsed model
     // Note: validation is all-or-nothing. The --check-images and --check-tables flags
     // are kept for backwards compatibility but don't filter which validations run.
-    match pipeline::parse_sources(&input) {
-        Ok(model) => {
-            // All checks passed (parse_sources would have failed if validation failed)
-            if verbose {
-                let section_count = count_sections(&model);
-                let image_count = count_images(&model);
-                let table_count = count_tables(&model);
-                println!("  Found {} sections", section_count);
-                println!("  Found {} image references", image_count);
-                println!("  Found {} table references", table_count);
+    // Spelling is checked on the authored content, before generated tables are added
+    let (model, mut found) = pipeline::check_sources_with(&input, spelling_diagnostics)?;
+    if let Some(model) = &model {
+        if verbose {
+            println!("  Found {} sections", count_sections(model));
+            println!("  Found {} image references", count_images(model));
+            println!("  Found {} table references", count_tables(model));
+        }
+        if release {
+            found.extend(release_diagnostics(model));
+        }
+        found.extend(template_conformance_diagnostics(model, release)?);
+        found.extend(model.suspect_links().iter().map(Diagnostic::from));
+        found.extend(model.orphaned_assets()?.iter().map(Diagnostic::from));
+    }
+
+    let overrides = diagnostic_overrides(&input, model.as_ref());
+    found.extend(diagnostics::unknown_codes(
+        &overrides,
+        &input.join("sysdoc.toml"),
+    ));
+    let diagnostics = diagnostics::resolve(found, &input, &overrides);
+    if report_diagnostics(&input, &diagnostics, options)? {
+        anyhow::bail!("Validation failed");
+    }
+    if human {
+        println!("✓ Validation passed");
+    }
+    Ok(())
+}
+
+/// Handle the clean-assets command
+///
+/// The sources must parse, so that no referenced file is mistaken for an
+/// orphan; validation errors such as broken links do not prevent cleaning.
+///
+/// # Parameters
+/// * `input` - Path to the document directory containing sysdoc.toml
+/// * `apply` - Remove the orphaned assets instead of only listing them
+///
+/// # Returns
+/// * `Ok(())` - Orphaned assets listed, or removed with `apply`
+/// * `Err` - The sources could not be parsed, `[assets] ignore` is invalid, or a
+///   file could not be removed
+fn handle_clean_assets_command(input: std::path::PathBuf, apply: bool) -> Result<()> {
+    let (model, found) = pipeline::check_sources(&input);
+    let Some(model) = model else {
+        let overrides = diagnostic_overrides(&input, None);
+        let diagnostics = diagnostics::resolve(found, &input, &overrides);
+        let options = DiagnosticOptions::new(MessageFormat::Human, false);
+        report_diagnostics(&input, &diagnostics, options)?;
+        anyhow::bail!("Cannot look for orphaned assets: the sources could not be parsed");
+    };
+
+    let orphaned = model.orphaned_assets()?;
+    for asset in &orphaned {
+        println!("  {}", asset.path.display());
+    }
+    if orphaned.is_empty() {
+        println!("✓ No orphaned assets");
+    } else if apply {
+        source_model::remove_assets(&input, &orphaned)?;
+        println!("✓ Removed {} orphaned asset(s)", orphaned.len());
+    } else {
+        println!(
+            "{} orphaned asset(s) would be removed; run with --apply to remove them",
+            orphaned.len()
+        );
+    }
+    Ok(())
+}
+
+/// Check the document against the DID template named by `document_template`
+///
+/// Documents without a template are not checked, and a template that is not
+/// built in is reported as a warning. Sections still holding only template
+/// guidance are warnings, and errors in a release check.
+///
+/// # Parameters
+/// * `model` - The parsed document
+/// * `release` - Whether release readiness is being checked
+///
+/// # Returns
+/// * `Ok(Vec<Diagnostic>)` - Missing, changed or unwritten templated sections and
+///   missing required metadata
+/// * `Err` - The built-in template could not be parsed
+fn template_conformance_diagnostics(
+    model: &source_model::SourceModel,
+    release: bool,
+) -> Result<Vec<Diagnostic>> {
+    let name = &model.config.document_template;
+    if name.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(info) = templates::get_template(name) else {
+        let message = format!(
+            "Unknown document template '{}'; template conformance not checked",
+            name
+        );
+        return Ok(vec![Diagnostic::warning("unknown-template", message)
+            .in_file("sysdoc.toml")
+            .with_suggestion(
+                "run 'sysdoc list-templates' for the built-in templates",
+            )]);
+    };
+    let template = templates::parse_template(&info)
+        .with_context(|| format!("Failed to parse template '{}'", info.id))?;
+
+    let mut diagnostics = model
+        .validate_template(&template)
+        .err()
+        .map_or_else(Vec::new, |e| e.diagnostics());
+    if release {
+        for diagnostic in &mut diagnostics {
+            diagnostic.severity = DiagnosticSeverity::Error;
+        }
+    }
+    Ok(diagnostics)
+}
+
+/// Report the TBD/TBR items that block a release
+fn release_diagnostics(model: &source_model::SourceModel) -> Vec<Diagnostic> {
+    model
+        .validate_release()
+        .err()
+        .map_or_else(Vec::new, |e| e.diagnostics())
+}
+
+/// Handle the trace coverage command
+///
+/// # Parameters
+/// * `input` - Path to the document directory containing sysdoc.toml
+/// * `format` - Report format (text, JSON, or CSV)
+/// * `output` - Optional file to write the report to (stdout if None)
+/// * `min_coverage` - Minimum forward coverage percentage required to succeed
+/// * `options` - Requirement/section selection and over-trace threshold
+///
+/// # Returns
+/// * `Ok(())` - Report written and coverage meets the threshold
+/// * `Err` - Parsing failed, the report could not be written, or coverage is too low
+fn handle_trace_coverage_command(
+    input: std::path::PathBuf,
+    format: CoverageFormat,
+    output: Option<std::path::PathBuf>,
+    min_coverage: Option<f64>,
+    options: &source_model::CoverageOptions,
+) -> Result<()> {
+    let model = load_model(&input, "Coverage analysis")?;
+
+    let report = source_model::CoverageReport::analyze(&model, options);
+    let rendered = match format {
+        CoverageFormat::Text => report.to_text(),
+        CoverageFormat::Json => {
+            report
+                .to_json()
+                .context("Failed to serialize JSON report")?
+                + "\n"
+        }
+        CoverageFormat::Csv => report.to_csv().context("Failed to write CSV report")?,
+    };
+
+    match output {
+        Some(path) => std::fs::write(&path, rendered)
+            .with_context(|| format!("Failed to write report to {}", path.display()))?,
+        None => print!("{}", rendered),
+    }
+
+    if let Some(min) = min_coverage {
+        match report.forward.percent {
+            None => anyhow::bail!(
+                "Forward coverage is N/A (no requirements found), so the required {:.1}% \
+                 cannot be met",
+                min
+            ),
+            Some(percent) if percent < min => anyhow::bail!(
+                "Forward coverage {:.1}% is below the required {:.1}%",
+                percent,
+                min
+            ),
+            Some(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the trace export command
+///
+/// # Parameters
+/// * `input` - Path to the document directory containing sysdoc.toml
+/// * `output` - Output file path (extension added if missing)
+/// * `format_arg` - Explicit format, or None to detect from the output extension
+///
+/// # Returns
+/// * `Ok(())` - Trace matrix written
+/// * `Err` - Parsing failed or the matrix could not be written
+fn handle_trace_export_command(
+    input: std::path::PathBuf,
+    mut output: std::path::PathBuf,
+    format_arg: Option<TraceExportFormat>,
+) -> Result<()> {
+    let format = match format_arg {
+        Some(fmt) => {
+            if output.extension().is_none() {
+                output.set_extension(match fmt {
+                    TraceExportFormat::Csv => "csv",
+                    TraceExportFormat::Xlsx => "xlsx",
+                    TraceExportFormat::Json => "json",
+                });
+            }
+            fmt
+        }
+        None => match output.extension().and_then(|s| s.to_str()) {
+            Some("csv") => TraceExportFormat::Csv,
+            Some("xlsx") => TraceExportFormat::Xlsx,
+            Some("json") => TraceExportFormat::Json,
+            Some(ext) => {
+                anyhow::bail!(
+                    "Unknown trace export format for extension '.{}'. Supported: .csv, .xlsx, .json\nUse --format to specify explicitly.",
+                    ext
+                );
+            }
+            None => {
+                // No extension, default to XLSX
+                output.set_extension("xlsx");
+                TraceExportFormat::Xlsx
             }
-            println!("✓ Validation passed");
-            Ok(())
+        },
+    };
+
+    let model = load_model(&input, "Trace export")?;
+
+    let rows = trace_matrix_exporter::collect_rows(&model);
+    match format {
+        TraceExportFormat::Csv => trace_matrix_exporter::to_csv(&rows, &output),
+        TraceExportFormat::Xlsx => trace_matrix_exporter::to_xlsx(&rows, &output),
+        TraceExportFormat::Json => trace_matrix_exporter::to_json(&model, &rows, &output),
+    }
+    .with_context(|| format!("Failed to export trace matrix to {}", output.display()))?;
+
+    println!(
+        "✓ Wrote {} trace links to: {}",
+        rows.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Handle the trace accept command
+///
+/// # Parameters
+/// * `input` - Path to the document directory containing sysdoc.toml
+/// * `section` - Only accept links from this section_id
+/// * `target` - Only accept links to this traced ID
+fn handle_trace_accept_command(
+    input: std::path::PathBuf,
+    section: Option<String>,
+    target: Option<String>,
+) -> Result<()> {
+    let model = load_model(&input, "Trace accept")?;
+
+    let (lock, accepted) = model.accept_trace_links(section.as_deref(), target.as_deref());
+    if accepted == 0 && (section.is_some() || target.is_some()) {
+        anyhow::bail!("No trace links match the given --section/--target");
+    }
+
+    lock.save(&input).with_context(|| {
+        format!(
+            "Failed to write {}",
+            input.join(source_model::TRACE_LOCK_FILE).display()
+        )
+    })?;
+    println!(
+        "✓ Accepted {} trace links in {}",
+        accepted,
+        input.join(source_model::TRACE_LOCK_FILE).display()
+    );
+    Ok(())
+}
+
+/// Handle the trace impact command
+fn handle_trace_impact_command(
+    input: std::path::PathBuf,
+    ids: Vec<String>,
+    since: Option<String>,
+    format: ReportFormat,
+    output: Option<std::path::PathBuf>,
+) -> Result<()> {
+    if ids.is_empty() && since.is_none() {
+        anyhow::bail!("Give the changed items with --id, --since, or both");
+    }
+
+    let model = load_model(&input, "Trace impact")?;
+    let downstreams =
+        pipeline::load_downstreams(&model).context("Failed to load downstream projects")?;
+
+    let network = source_model::TraceNetwork::new(&model, &downstreams);
+    let unknown: Vec<&str> = ids
+        .iter()
+        .map(String::as_str)
+        .filter(|id| !network.contains(id) && !model.catalog.contains(id))
+        .collect();
+    if !unknown.is_empty() {
+        anyhow::bail!(
+            "Unknown ID(s) given with --id: {}. They are not sections or traced items of this \
+             project, its linked projects, or its trace catalogs",
+            unknown.join(", ")
+        );
+    }
+
+    let mut changed: std::collections::BTreeSet<String> = ids.into_iter().collect();
+    if let Some(revision) = &since {
+        let (changed_ids, deleted) = source_model::changed_section_ids(&model, revision)
+            .with_context(|| format!("Failed to diff sources against '{}'", revision))?;
+        for path in deleted {
+            eprintln!(
+                "⚠ {} was deleted since {}; IDs it defined are not included",
+                path.display(),
+                revision
+            );
         }
-        Err(e) => {
-            // Validation failed - format the error nicely
-            eprintln!("✗ Validation failed:\n");
-            eprintln!("{}", format_parse_error(&e));
-            anyhow::bail!("Validation failed")
+        changed.extend(changed_ids);
+    }
+
+    let report = network.analyze(&changed);
+    let rendered = match format {
+        ReportFormat::Text => report.to_text(),
+        ReportFormat::Json => {
+            report
+                .to_json()
+                .context("Failed to serialize JSON report")?
+                + "\n"
         }
+    };
+
+    match output {
+        Some(path) => std::fs::write(&path, rendered)
+            .with_context(|| format!("Failed to write report to {}", path.display()))?,
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+/// Handle the trace graph command
+fn handle_trace_graph_command(
+    input: std::path::PathBuf,
+    output: std::path::PathBuf,
+    section_id_prefix: Option<String>,
+    chapter: Option<String>,
+) -> Result<()> {
+    let model = load_model(&input, "Trace graph")?;
+
+    let spec = source_model::TraceGraphSpec {
+        section_id_prefix,
+        chapter,
+        caption: None,
+    };
+    let traces = model.collect_all_section_traceability();
+    let targets = model.collect_trace_targets(&traces);
+    let graph =
+        source_model::TraceGraph::build(&spec, &traces, &targets, &model.config.document_id);
+    if graph.clusters.is_empty() {
+        anyhow::bail!("No sections with a section_id match the given filters");
+    }
+
+    graph
+        .write(&output)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    println!(
+        "✓ Wrote trace graph to {} and {}",
+        output.display(),
+        output.with_extension("dot").display()
+    );
+    Ok(())
+}
+
+/// Handle the baseline create command
+fn handle_baseline_create_command(
+    input: std::path::PathBuf,
+    name: String,
+    force: bool,
+) -> Result<()> {
+    let model = load_model(&input, "Baseline creation")?;
+
+    let baseline = model.snapshot(&name);
+    let path = baseline
+        .save(&input, force)
+        .with_context(|| format!("Failed to write baseline '{}'", name))?;
+    println!(
+        "✓ Baseline '{}' with {} items written to {}",
+        name,
+        baseline.items.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Handle the baseline diff command
+fn handle_baseline_diff_command(
+    input: std::path::PathBuf,
+    from: String,
+    to: Option<String>,
+    format: ReportFormat,
+    output: Option<std::path::PathBuf>,
+) -> Result<()> {
+    let earlier = source_model::Baseline::load(&input, &from)
+        .with_context(|| format!("Failed to load baseline '{}'", from))?;
+    let later = match to {
+        Some(name) => source_model::Baseline::load(&input, &name)
+            .with_context(|| format!("Failed to load baseline '{}'", name))?,
+        None => load_model(&input, "Baseline diff")?.snapshot("current"),
+    };
+
+    let diff = earlier.diff(&later);
+    let rendered = match format {
+        ReportFormat::Text => diff.to_text(),
+        ReportFormat::Json => diff.to_json().context("Failed to serialize JSON report")? + "\n",
+    };
+
+    match output {
+        Some(path) => std::fs::write(&path, rendered)
+            .with_context(|| format!("Failed to write report to {}", path.display()))?,
+        None => print!("{}", rendered),
     }
+    Ok(())
+}
+
+/// Handle the ids assign command
+fn handle_ids_assign_command(input: std::path::PathBuf, all: bool, dry_run: bool) -> Result<()> {
+    let model = load_model(&input, "ID assignment")?;
+
+    let Some(scheme) = &model.id_scheme else {
+        anyhow::bail!(
+            "No ID scheme for document type '{}'; add [id_schemes.{}] to sysdoc.toml",
+            model.config.document_type,
+            model.config.document_type
+        );
+    };
+
+    let (assignments, registry) = model.plan_section_ids(scheme, all);
+    for assignment in &assignments {
+        println!(
+            "  {} -> {}:{} {}",
+            assignment.section_id,
+            assignment.source_file.display(),
+            assignment.line,
+            assignment.heading
+        );
+    }
+    if dry_run {
+        println!("{} section IDs would be assigned", assignments.len());
+        return Ok(());
+    }
+
+    let files = model
+        .write_section_ids(&assignments)
+        .context("Failed to write section IDs")?;
+    registry.save(&input).with_context(|| {
+        format!(
+            "Failed to write {}",
+            input.join(source_model::ID_REGISTRY_FILE).display()
+        )
+    })?;
+    println!(
+        "✓ Assigned {} section IDs in {} files; registry: {}",
+        assignments.len(),
+        files,
+        input.join(source_model::ID_REGISTRY_FILE).display()
+    );
+    Ok(())
+}
+
+/// Print a section insert, move or renumbering plan, and carry it out unless
+/// `dry_run` is set
+///
+/// # Parameters
+/// * `input` - Path to the document directory containing sysdoc.toml
+/// * `plan` - The planned file changes, or why they cannot be made
+/// * `dry_run` - Only print the plan
+///
+/// # Returns
+/// * `Ok(())` - The plan was printed, and applied unless `dry_run` is set
+/// * `Err` - The document has no sysdoc.toml, the plan could not be made, or a
+///   file could not be renamed or written
+fn handle_layout_plan(
+    input: &std::path::Path,
+    plan: Result<renumber::LayoutPlan, renumber::RenumberError>,
+    dry_run: bool,
+) -> Result<()> {
+    if !input.join("sysdoc.toml").exists() {
+        anyhow::bail!(
+            "No sysdoc.toml found in '{}'. Is this a sysdoc project directory?",
+            input.display()
+        );
+    }
+    let plan = plan?;
+    if let Some(new_file) = &plan.new_file {
+        println!("  create {}", new_file.path.display());
+    }
+    for rename in &plan.renames {
+        println!(
+            "  rename {} -> {}",
+            rename.from.display(),
+            rename.to.display()
+        );
+    }
+    for update in &plan.link_updates {
+        println!(
+            "  update {} ({} link(s))",
+            update.path.display(),
+            update.count
+        );
+    }
+
+    let summary = format!(
+        "{} file(s) renamed, links updated in {} file(s)",
+        plan.renames.len(),
+        plan.link_updates.len()
+    );
+    if plan.is_empty() {
+        println!("✓ Section numbering is already consecutive");
+    } else if dry_run {
+        println!("Dry run: {}; nothing was changed", summary);
+    } else {
+        plan.apply(input)
+            .context("Failed to apply the section changes")?;
+        println!("✓ {}", summary);
+    }
+    Ok(())
+}
+
+/// Report possible misspellings as warnings
+///
+/// Skipped when `[lint.rules]` turns the `spelling` rule off.
+fn spelling_diagnostics(model: &source_model::SourceModel) -> Result<Vec<Diagnostic>> {
+    let disabled = model
+        .config
+        .lint
+        .as_ref()
+        .and_then(|lint| lint.rules.get("spelling"))
+        .is_some_and(|severity| *severity == document_config::LintSeverity::Off);
+    if disabled {
+        return Ok(Vec::new());
+    }
+
+    let dictionary = model.spelling_dictionary()?;
+    let misspellings = source_model::check_spelling(&model.markdown_files, &dictionary);
+    Ok(misspellings.iter().map(Diagnostic::from).collect())
+}
+
+/// Severity overrides from `[diagnostics]` in sysdoc.toml
+///
+/// Read from the parsed model, or from sysdoc.toml directly when the sources could
+/// not be parsed (none if sysdoc.toml cannot be loaded either).
+fn diagnostic_overrides(
+    input: &std::path::Path,
+    model: Option<&source_model::SourceModel>,
+) -> BTreeMap<String, DiagnosticSeverity> {
+    match model {
+        Some(model) => model.config.diagnostics.clone(),
+        None => document_config::DocumentConfig::load(input.join("sysdoc.toml"))
+            .map(|config| config.diagnostics)
+            .unwrap_or_default(),
+    }
+}
+
+/// Parse and validate the sources for a command that works on the whole model
+///
+/// Diagnostics are resolved against `[diagnostics]` in sysdoc.toml and, if they
+/// fail the command, printed like those of `sysdoc validate`.
+///
+/// # Parameters
+/// * `input` - Path to the document directory containing sysdoc.toml
+/// * `action` - Name of the command for the failure message (e.g., "Trace export")
+///
+/// # Returns
+/// * `Ok(SourceModel)` - The parsed and validated model, with generated tables
+/// * `Err` - The sources could not be parsed or have errors
+fn load_model(input: &std::path::Path, action: &str) -> Result<source_model::SourceModel> {
+    let (model, found) = pipeline::check_sources(input);
+    let overrides = diagnostic_overrides(input, model.as_ref());
+    let diagnostics = diagnostics::resolve(found, input, &overrides);
+    match model {
+        Some(model) if !diagnostics::is_failure(&diagnostics, false) => Ok(model),
+        _ => {
+            let options = DiagnosticOptions::new(MessageFormat::Human, false);
+            report_diagnostics(input, &diagnostics, options)?;
+            anyhow::bail!("{} failed due to validation errors", action)
+        }
+    }
+}
+
+/// Print resolved diagnostics in the selected format
+///
+/// Human-readable diagnostics and their summary go to stderr; JSON and SARIF go
+/// to stdout.
+///
+/// # Parameters
+/// * `input` - Path to the document directory, used to read source snippets
+/// * `diagnostics` - Resolved diagnostics
+/// * `options` - Diagnostic output format and whether warnings fail the command
+///
+/// # Returns
+/// * `Ok(true)` - The diagnostics fail the command
+/// * `Ok(false)` - The diagnostics are only warnings and notes, or there are none
+/// * `Err` - The diagnostics could not be serialized
+fn report_diagnostics(
+    input: &std::path::Path,
+    diagnostics: &[Diagnostic],
+    options: DiagnosticOptions,
+) -> Result<bool> {
+    let failed = diagnostics::is_failure(diagnostics, options.deny_warnings);
+    match options.format {
+        MessageFormat::Human if diagnostics.is_empty() => {}
+        MessageFormat::Human => {
+            eprint!("{}", diagnostics::render_human(diagnostics, input));
+            eprintln!(
+                "{} {} error(s), {} warning(s)",
+                if failed { "✗" } else { "⚠" },
+                diagnostics::count(diagnostics, DiagnosticSeverity::Error),
+                diagnostics::count(diagnostics, DiagnosticSeverity::Warning)
+            );
+        }
+        MessageFormat::Json => println!("{}", diagnostics::render_json(diagnostics)?),
+        MessageFormat::Sarif => println!("{}", diagnostics::render_sarif(diagnostics)?),
+    }
+    Ok(failed)
+}
+
+/// Handle the import reqif command
+fn handle_import_reqif_command(
+    file: std::path::PathBuf,
+    into: std::path::PathBuf,
+    catalog: bool,
+    force: bool,
+) -> Result<()> {
+    if catalog {
+        let added = reqif_importer::register_catalog(&into, &file)
+            .with_context(|| format!("Failed to register {} as a catalog", file.display()))?;
+        if added {
+            println!(
+                "✓ Registered {} as a trace catalog in {}",
+                file.display(),
+                into.join("sysdoc.toml").display()
+            );
+        } else {
+            println!("✓ {} is already registered as a catalog", file.display());
+        }
+        return Ok(());
+    }
+
+    let reqif = reqif_importer::read_reqif(&file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let files_created = reqif_importer::write_tree(&reqif, &into, force)
+        .with_context(|| format!("Failed to import {}", file.display()))?;
+
+    println!(
+        "✓ Imported {} requirements into {} files under {}",
+        reqif.requirements().count(),
+        files_created,
+        into.join(paths::SOURCE_DIR).display()
+    );
+    Ok(())
 }
 
 /// Count the number of image references in the model
@@ -395,30 +1370,6 @@ fn count_sections(model: &source_model::SourceModel) -> usize {
     model.markdown_files.iter().map(|f| f.sections.len()).sum()
 }
 
-/// Format a parse error for user-friendly display
-fn format_parse_error(error: &pipeline::ParseError) -> String {
-    match error {
-        pipeline::ParseError::ValidationError(ve) => format_validation_error(ve),
-        _ => format!("  {}", error),
-    }
-}
-
-/// Format a validation error for user-friendly display
-fn format_validation_error(error: &source_model::ValidationError) -> String {
-    use source_model::ValidationError;
-
-    match error {
-        ValidationError::Multiple(errors) => {
-            let mut output = String::new();
-            for e in errors {
-                output.push_str(&format!("  - {}\n", e));
-            }
-            output
-        }
-        _ => format!("  - {}", error),
-    }
-}
-
 /// Handle the list-templates command
 fn handle_list_templates_command() {
     println!("Available DID templates:\n");
@@ -550,6 +1501,12 @@ fn print_build_info(
                 println!("Warning: --no-images has no effect in PDF format");
             }
         }
+        OutputFormat::Reqif => {
+            println!("Format: ReqIF 1.2 (sections with a section_id)");
+            if no_images {
+                println!("Warning: --no-images has no effect in ReqIF format");
+            }
+        }
     }
 }
 
@@ -0,0 +1,77 @@
+//! Path helpers for paths written into documents and sysdoc.toml
+
+use std::path::{Component, Path, PathBuf};
+
+/// Directory holding the document sources, relative to the document root
+pub const SOURCE_DIR: &str = "src";
+
+/// Path of `target` relative to the directory `from`, with `/` separators
+///
+/// # Parameters
+/// * `from` - Directory the path is relative to
+/// * `target` - Path to express relative to `from` (both absolute or both relative)
+///
+/// # Returns
+/// * The relative path, climbing out of `from` with `..` where needed
+pub fn relative_path(from: &Path, target: &Path) -> String {
+    let from: Vec<_> = from.components().collect();
+    let target: Vec<_> = target.components().collect();
+    let common = from.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    let parents = std::iter::repeat_n("..".to_string(), from.len() - common);
+    let rest = target[common..]
+        .iter()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned());
+    parents.chain(rest).collect::<Vec<_>>().join("/")
+}
+
+/// Resolve `.` and `..` components of a relative path without touching the file system
+///
+/// # Returns
+/// * `None` if the path climbs above its start with `..` or is absolute
+pub fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path(Path::new("/a/b"), Path::new("/a/b/c.md")),
+            "c.md"
+        );
+        assert_eq!(
+            relative_path(Path::new("/a/b"), Path::new("/a/x/c.md")),
+            "../x/c.md"
+        );
+        assert_eq!(
+            relative_path(Path::new(""), Path::new("src/c.md")),
+            "src/c.md"
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize(Path::new("src/./a/../b.md")),
+            Some(PathBuf::from("src/b.md"))
+        );
+        assert_eq!(normalize(Path::new("src/../../b.md")), None);
+        assert_eq!(normalize(Path::new("/etc/passwd")), None);
+    }
+}
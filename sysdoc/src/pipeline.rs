@@ -5,8 +5,14 @@
 //! 2. **Transformation**: Convert source model into unified document model
 //! 3. **Export**: Generate output formats (docx, markdown, etc.)
 
+use crate::diagnostics::Diagnostic;
 use crate::document_config::DocumentConfig;
-use crate::source_model::{MarkdownSection, MarkdownSource, SectionNumber, SourceModel};
+use crate::paths::SOURCE_DIR;
+use crate::source_model::{
+    scan_code_references, Baseline, IdRegistry, IdScheme, MarkdownSection, MarkdownSource, Outline,
+    OutlineEntry, OutlineError, SectionNumber, SourceModel, TestResults, TraceCatalog, TraceLock,
+    UnlistedFile, UpstreamDocument, ID_REGISTRY_FILE, SUMMARY_FILE, TRACE_LOCK_FILE,
+};
 use crate::unified_document::{
     DocumentBuilder, DocumentMetadata, Person, RevisionHistoryEntry, UnifiedDocument,
 };
@@ -28,6 +34,84 @@ use rayon::prelude::*;
 /// * `Ok(SourceModel)` - Successfully parsed source model with all discovered files
 /// * `Err(ParseError)` - Error loading configuration, parsing files, or validating references
 pub fn parse_sources(root: &Path) -> Result<SourceModel, ParseError> {
+    let mut model = load_sources(root)?;
+
+    // Validate all references
+    model.validate()?;
+
+    generate_tables(&mut model);
+    Ok(model)
+}
+
+/// Stage 1 for `build`, `validate` and `lint`: parse and validate, collecting diagnostics
+///
+/// Unlike [`parse_sources`], validation errors do not discard the model, so that
+/// further checks can run and every problem is reported in one pass.
+///
+/// # Parameters
+/// * `root` - Root directory of the document source containing sysdoc.toml and markdown files
+///
+/// # Returns
+/// * `(Some(SourceModel), diagnostics)` - The sources were parsed; the diagnostics hold
+///   any validation errors
+/// * `(None, diagnostics)` - The sources could not be parsed; the diagnostics say why
+pub fn check_sources(root: &Path) -> (Option<SourceModel>, Vec<Diagnostic>) {
+    let checked = check_sources_with(root, |_| Ok::<_, std::convert::Infallible>(Vec::new()));
+    checked.unwrap_or_else(|never| match never {})
+}
+
+/// Like [`check_sources`], also running a check on the authored content
+///
+/// `check` sees each section as written, before the generated tables (trace,
+/// query, open item and other tables built from the whole document) are added,
+/// so that checks such as spelling do not report on generated text.
+///
+/// # Parameters
+/// * `root` - Root directory of the document source containing sysdoc.toml and markdown files
+/// * `check` - Check of the parsed and validated model, returning further diagnostics
+///
+/// # Returns
+/// * `Ok((model, diagnostics))` - As for [`check_sources`], with the diagnostics of
+///   `check` (which is not run if the sources could not be parsed)
+/// * `Err(E)` - `check` failed
+pub fn check_sources_with<E>(
+    root: &Path,
+    check: impl FnOnce(&SourceModel) -> Result<Vec<Diagnostic>, E>,
+) -> Result<(Option<SourceModel>, Vec<Diagnostic>), E> {
+    let mut model = match load_sources(root) {
+        Ok(model) => model,
+        Err(e) => return Ok((None, e.diagnostics())),
+    };
+    let mut diagnostics = model
+        .validate()
+        .err()
+        .map_or_else(Vec::new, |e| e.diagnostics());
+    diagnostics.extend(model.unlisted_files.iter().map(Diagnostic::from));
+    diagnostics.extend(check(&model)?);
+
+    generate_tables(&mut model);
+    Ok((Some(model), diagnostics))
+}
+
+/// Generate the tables built from data across all sections
+///
+/// Runs after all files are parsed and validated, so that tables can include
+/// data from all sections across all files.
+fn generate_tables(model: &mut SourceModel) {
+    model.generate_open_items_tables();
+    model.generate_traceability_tables();
+    model.generate_query_tables();
+}
+
+/// Load the configuration, linked projects and markdown sources of a document
+///
+/// # Parameters
+/// * `root` - Root directory of the document source containing sysdoc.toml and markdown files
+///
+/// # Returns
+/// * `Ok(SourceModel)` - The parsed, not yet validated, source model
+/// * `Err(ParseError)` - Error loading configuration or parsing files
+fn load_sources(root: &Path) -> Result<SourceModel, ParseError> {
     // Load document configuration
     let config_path = root.join("sysdoc.toml");
     let config = DocumentConfig::load(&config_path)
@@ -35,60 +119,212 @@ pub fn parse_sources(root: &Path) -> Result<SourceModel, ParseError> {
 
     let mut model = SourceModel::new(root.to_path_buf(), config);
 
-    // Discover all markdown files with section numbering in the src/ directory
-    let src_dir = root.join("src");
-    let markdown_paths: Vec<PathBuf> = WalkDir::new(&src_dir)
+    // Load upstream requirement catalogs used as trace targets
+    model.catalog = TraceCatalog::load(root, &model.config.catalogs)
+        .map_err(|(path, e)| ParseError::CatalogError(path, e))?;
+
+    // Load upstream sysdoc projects whose sections may be traced to
+    model.upstreams = model
+        .config
+        .upstreams
+        .iter()
+        .map(|upstream| load_linked_project(root, &upstream.path, ParseError::UpstreamError))
+        .collect::<Result<_, _>>()?;
+
+    // Load reviewed trace fingerprints used to detect suspect links
+    model.trace_lock = TraceLock::load(root)
+        .map_err(|e| ParseError::TraceLockError(root.join(TRACE_LOCK_FILE), e))?;
+
+    // Compile the section ID scheme and load issued/retired IDs
+    model.id_scheme = model
+        .config
+        .id_scheme()
+        .map(IdScheme::new)
+        .transpose()
+        .map_err(ParseError::IdSchemeError)?;
+    model.id_registry = IdRegistry::load(root)
+        .map_err(|e| ParseError::IdRegistryError(root.join(ID_REGISTRY_FILE), e))?;
+
+    // Scan source code for annotations naming the sections it implements
+    if let Some(code_trace) = &model.config.code_trace {
+        model.code_references =
+            scan_code_references(root, code_trace).map_err(ParseError::CodeTraceError)?;
+    }
+
+    // Load test results mapped onto test case sections
+    model.test_results = TestResults::load(root, &model.config.test_results)
+        .map_err(|(path, e)| ParseError::TestResultsError(path, e))?;
+
+    (model.markdown_files, model.unlisted_files) = parse_markdown_files(root, &model.config)?;
+
+    // Load baselines compared against by "Changes since baseline" tables
+    model.baselines = model
+        .markdown_files
+        .iter()
+        .flat_map(|md_file| md_file.sections.iter())
+        .filter_map(|section| section.metadata.as_ref()?.changes_since_baseline.clone())
+        .unique()
+        .map(|name| {
+            Baseline::load(root, &name)
+                .map(|baseline| (name.clone(), baseline))
+                .map_err(|e| ParseError::BaselineError(name, e))
+        })
+        .collect::<Result<_, _>>()?;
+
+    // Note: Images are now embedded directly in MarkdownBlock::Image with metadata
+    // resolved during parsing, so we don't need to collect them separately
+
+    // Note: CSV tables are now embedded directly in MarkdownBlock::CsvTable with data
+    // loaded during parsing, so we don't need to collect them separately
+
+    Ok(model)
+}
+
+/// Discover and parse the markdown files in the `src/` directory
+///
+/// A file is part of the document when its name is numbered (`XX.YY_name.md`)
+/// or it is listed in the outline (`[outline]` in sysdoc.toml or `src/SUMMARY.md`),
+/// which then also gives its section number.
+///
+/// # Parameters
+/// * `root` - Root directory of the document
+/// * `config` - Document configuration (for `[outline]`)
+///
+/// # Returns
+/// * `Ok((files, unlisted))` - Parsed markdown sources in discovery order, and the
+///   markdown files that are neither numbered nor listed
+/// * `Err(ParseError)` - Error loading the outline, or reading or parsing the files;
+///   every failing file is reported, in `ParseError::Multiple` when there is more than one
+fn parse_markdown_files(
+    root: &Path,
+    config: &DocumentConfig,
+) -> Result<(Vec<MarkdownSource>, Vec<UnlistedFile>), ParseError> {
+    let outline = Outline::load(root, config).map_err(|e| {
+        let source = match &config.outline {
+            Some(_) => PathBuf::from("sysdoc.toml"),
+            None => Path::new(SOURCE_DIR).join(SUMMARY_FILE),
+        };
+        ParseError::OutlineError(source, e)
+    })?;
+
+    // Discover all markdown files in the src/ directory
+    let src_dir = root.join(SOURCE_DIR);
+    let summary_path = src_dir.join(SUMMARY_FILE);
+    let mut markdown_paths = Vec::new();
+    let mut unlisted = Vec::new();
+    let discovered = WalkDir::new(&src_dir)
         .follow_links(false)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(|e| e.ok())
-        .filter(|e| {
-            if !e.path().is_file() {
-                return false;
-            }
-            if e.path().extension().and_then(|s| s.to_str()) != Some("md") {
-                return false;
-            }
-            // Only include markdown files that match the section numbering pattern (XX.YY_name.md)
-            if let Some(filename) = e.path().file_stem().and_then(|s| s.to_str()) {
-                // Check if filename starts with digits followed by a dot (e.g., "01." or "01.02")
-                filename.chars().next().is_some_and(|c| c.is_ascii_digit())
-                    && filename.contains('_')
-            } else {
-                false
-            }
-        })
         .map(|e| e.path().to_path_buf())
-        .collect();
+        .filter(|path| path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md"))
+        .filter(|path| outline.is_none() || *path != summary_path);
+    for path in discovered {
+        let relative_path = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+        let entry = outline
+            .as_ref()
+            .and_then(|outline| outline.entry(&relative_path));
+        if entry.is_some() || is_numbered(&path) {
+            markdown_paths.push((path, entry));
+        } else {
+            unlisted.push(UnlistedFile {
+                path: relative_path,
+            });
+        }
+    }
 
     // Parse markdown files (optionally in parallel)
     #[cfg(feature = "parallel")]
-    let markdown_files: Result<Vec<_>, _> = markdown_paths
+    let results: Vec<Result<MarkdownSource, ParseError>> = markdown_paths
         .par_iter()
-        .map(|path| parse_markdown_file(path, root))
+        .map(|(path, entry)| parse_markdown_file(path, root, *entry))
         .collect();
 
     #[cfg(not(feature = "parallel"))]
-    let markdown_files: Result<Vec<_>, _> = markdown_paths
+    let results: Vec<Result<MarkdownSource, ParseError>> = markdown_paths
         .iter()
-        .map(|path| parse_markdown_file(path, root))
+        .map(|(path, entry)| parse_markdown_file(path, root, *entry))
         .collect();
 
-    model.markdown_files = markdown_files?;
+    let (markdown_files, mut errors): (Vec<_>, Vec<_>) = results.into_iter().partition_result();
+    match errors.len() {
+        0 => Ok((markdown_files, unlisted)),
+        1 => Err(errors.remove(0)),
+        _ => Err(ParseError::Multiple(errors)),
+    }
+}
 
-    // Note: Images are now embedded directly in MarkdownBlock::Image with metadata
-    // resolved during parsing, so we don't need to collect them separately
+/// Whether a markdown file name matches the section numbering pattern (XX.YY_name.md)
+fn is_numbered(path: &Path) -> bool {
+    // Check if filename starts with digits followed by a dot (e.g., "01." or "01.02")
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .is_some_and(|filename| {
+            filename.chars().next().is_some_and(|c| c.is_ascii_digit()) && filename.contains('_')
+        })
+}
 
-    // Note: CSV tables are now embedded directly in MarkdownBlock::CsvTable with data
-    // loaded during parsing, so we don't need to collect them separately
+/// Load the downstream projects declared with `[[downstream]]`
+///
+/// Downstream projects are only needed for change impact analysis, so they are
+/// not loaded by [`parse_sources`].
+///
+/// # Parameters
+/// * `model` - The parsed document
+///
+/// # Returns
+/// * `Ok(Vec<UpstreamDocument>)` - The parsed downstream projects
+/// * `Err(ParseError)` - A downstream project could not be found or parsed
+pub fn load_downstreams(model: &SourceModel) -> Result<Vec<UpstreamDocument>, ParseError> {
+    model
+        .config
+        .downstreams
+        .iter()
+        .map(|downstream| {
+            load_linked_project(&model.root, &downstream.path, ParseError::DownstreamError)
+        })
+        .collect()
+}
 
-    // Validate all references
-    model.validate()?;
+/// Load a linked sysdoc project declared with `[[upstream]]` or `[[downstream]]`
+///
+/// The project's configuration and markdown sources are parsed, but it is not
+/// validated and its own linked projects are not followed.
+///
+/// # Parameters
+/// * `root` - Root directory of the document declaring the link
+/// * `project_path` - Path to the linked project's root as declared in sysdoc.toml
+/// * `wrap_error` - Error variant naming the kind of link
+///
+/// # Returns
+/// * `Ok(UpstreamDocument)` - The parsed project
+/// * `Err(ParseError)` - The project could not be found or parsed
+fn load_linked_project(
+    root: &Path,
+    project_path: &str,
+    wrap_error: fn(PathBuf, Box<ParseError>) -> ParseError,
+) -> Result<UpstreamDocument, ParseError> {
+    let declared_path = PathBuf::from(project_path);
+    let wrap = |e: ParseError| wrap_error(declared_path.clone(), Box::new(e));
+
+    let upstream_root = root
+        .join(&declared_path)
+        .canonicalize()
+        .map_err(|e| wrap(ParseError::IoError(root.join(&declared_path), e)))?;
+
+    let config_path = upstream_root.join("sysdoc.toml");
+    let config = DocumentConfig::load(&config_path)
+        .map_err(|e| wrap(ParseError::ConfigError(config_path.clone(), Box::new(e))))?;
 
-    // Generate traceability tables after all files are parsed and validated
-    // This allows tables to include data from all sections across all files
-    model.generate_traceability_tables();
+    let (markdown_files, _) = parse_markdown_files(&upstream_root, &config).map_err(wrap)?;
 
-    Ok(model)
+    Ok(UpstreamDocument {
+        path: declared_path.clone(),
+        root: upstream_root,
+        config,
+        markdown_files,
+    })
 }
 
 /// Parse a single markdown file
@@ -96,11 +332,17 @@ pub fn parse_sources(root: &Path) -> Result<SourceModel, ParseError> {
 /// # Parameters
 /// * `path` - Absolute path to the markdown file to parse
 /// * `root` - Root directory of the document (used for calculating relative paths)
+/// * `entry` - The file's outline entry, giving its section number (None to read
+///   the number from the filename)
 ///
 /// # Returns
 /// * `Ok(MarkdownSource)` - Successfully parsed markdown source with content and metadata
 /// * `Err(ParseError)` - Error reading file or parsing filename/section number
-fn parse_markdown_file(path: &Path, root: &Path) -> Result<MarkdownSource, ParseError> {
+fn parse_markdown_file(
+    path: &Path,
+    root: &Path,
+    entry: Option<&OutlineEntry>,
+) -> Result<MarkdownSource, ParseError> {
     let content =
         std::fs::read_to_string(path).map_err(|e| ParseError::IoError(path.to_path_buf(), e))?;
 
@@ -110,11 +352,24 @@ fn parse_markdown_file(path: &Path, root: &Path) -> Result<MarkdownSource, Parse
         .and_then(|s| s.to_str())
         .ok_or_else(|| ParseError::InvalidFilename(path.to_path_buf()))?;
 
-    // Parse section number and title
-    let (number_str, title) = parse_filename(filename, path)?;
-
-    let section_number = SectionNumber::parse(number_str)
-        .ok_or_else(|| ParseError::InvalidSectionNumber(path.to_path_buf()))?;
+    // Take the section number from the outline, or parse it and the title from the filename
+    let (section_number, title) = match entry {
+        Some(entry) => {
+            let slug = if is_numbered(path) {
+                filename.split_once('_').map_or(filename, |(_, slug)| slug)
+            } else {
+                filename
+            };
+            let title = entry.title.clone().unwrap_or_else(|| title_from_slug(slug));
+            (entry.number.clone(), title)
+        }
+        None => {
+            let (number_str, title) = parse_filename(filename, path)?;
+            let section_number = SectionNumber::parse(number_str)
+                .ok_or_else(|| ParseError::InvalidSectionNumber(path.to_path_buf()))?;
+            (section_number, title)
+        }
+    };
 
     let relative_path = path.strip_prefix(root).unwrap_or(path).to_path_buf();
 
@@ -153,12 +408,12 @@ fn parse_filename<'a>(filename: &'a str, path: &Path) -> Result<(&'a str, String
         return Err(ParseError::InvalidFilename(path.to_path_buf()));
     }
 
-    let number_str = parts[0];
-    let title_slug = parts[1];
+    Ok((parts[0], title_from_slug(parts[1])))
+}
 
-    // Convert slug to title
-    let title = title_slug
-        .replace(['-', '_'], " ")
+/// Convert a filename slug to a title (e.g., "system-context" to "System Context")
+fn title_from_slug(slug: &str) -> String {
+    slug.replace(['-', '_'], " ")
         .split_whitespace()
         .map(|word| {
             let mut chars = word.chars();
@@ -168,9 +423,7 @@ fn parse_filename<'a>(filename: &'a str, path: &Path) -> Result<(&'a str, String
             }
         })
         .collect::<Vec<_>>()
-        .join(" ");
-
-    Ok((number_str, title))
+        .join(" ")
 }
 
 /// Get the git version using `git describe --tags --dirty`
@@ -527,11 +780,80 @@ pub enum ParseError {
     #[error("Error parsing {path}: {source}", path = .0.display(), source = .1)]
     SourceModelError(PathBuf, #[source] crate::source_model::SourceModelError),
 
+    #[error("Error loading catalog {path}: {source}", path = .0.display(), source = .1)]
+    CatalogError(PathBuf, #[source] crate::source_model::CatalogError),
+
+    #[error("Error loading upstream document {path}: {source}", path = .0.display(), source = .1)]
+    UpstreamError(PathBuf, #[source] Box<ParseError>),
+
+    #[error("Error loading downstream document {path}: {source}", path = .0.display(), source = .1)]
+    DownstreamError(PathBuf, #[source] Box<ParseError>),
+
+    #[error("Error loading test results {path}: {source}", path = .0.display(), source = .1)]
+    TestResultsError(PathBuf, #[source] crate::source_model::TestResultsError),
+
+    #[error("Error scanning source code: {0}")]
+    CodeTraceError(#[source] crate::source_model::CodeTraceError),
+
+    #[error("Error loading trace lock {path}: {source}", path = .0.display(), source = .1)]
+    TraceLockError(PathBuf, #[source] crate::source_model::TraceLockError),
+
+    #[error("Error loading baseline '{0}': {1}")]
+    BaselineError(String, #[source] crate::source_model::BaselineError),
+
+    #[error("Invalid section ID scheme: {0}")]
+    IdSchemeError(#[source] crate::source_model::IdSchemeError),
+
+    #[error("Error loading ID registry {path}: {source}", path = .0.display(), source = .1)]
+    IdRegistryError(PathBuf, #[source] crate::source_model::IdRegistryError),
+
+    #[error("Invalid outline in {path}: {source}", path = .0.display(), source = .1)]
+    OutlineError(PathBuf, #[source] OutlineError),
+
     #[error("Config error loading {path}: {source}", path = .0.display(), source = .1)]
     ConfigError(
         PathBuf,
         #[source] Box<crate::document_config::DocumentConfigError>,
     ),
+
+    #[error("Multiple parse errors: {}", .0.iter().map(|e| format!("\n  - {}", e)).collect::<String>())]
+    Multiple(Vec<ParseError>),
+}
+
+impl ParseError {
+    /// Convert the error into diagnostics, one per error in `Multiple` and `ValidationError`
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let error = |code: &str| Diagnostic::error(code, self.to_string());
+        match self {
+            Self::ValidationError(e) => e.diagnostics(),
+            Self::Multiple(errors) => errors.iter().flat_map(Self::diagnostics).collect(),
+            Self::SourceModelError(
+                path,
+                crate::source_model::SourceModelError::MetadataParseError { line_number, .. },
+            ) => vec![error("invalid-metadata").at_line(path, *line_number)],
+            Self::SourceModelError(path, _) => {
+                vec![error("invalid-heading-structure").in_file(path)]
+            }
+            Self::IoError(path, _) => vec![error("io-error").in_file(path)],
+            Self::InvalidFilename(path) => vec![error("invalid-filename").in_file(path)],
+            Self::InvalidSectionNumber(path) => {
+                vec![error("invalid-section-number").in_file(path)]
+            }
+            Self::ConfigError(path, _) => vec![error("invalid-config").in_file(path)],
+            Self::OutlineError(path, e) => match e.line() {
+                Some(line) => vec![error("invalid-outline").at_line(path, line)],
+                None => vec![error("invalid-outline").in_file(path)],
+            },
+            Self::CatalogError(path, _) => vec![error("invalid-catalog").in_file(path)],
+            Self::TestResultsError(path, _) => vec![error("invalid-test-results").in_file(path)],
+            Self::TraceLockError(path, _) => vec![error("invalid-trace-lock").in_file(path)],
+            Self::IdRegistryError(path, _) => vec![error("invalid-id-registry").in_file(path)],
+            Self::UpstreamError(..) | Self::DownstreamError(..) => vec![error("linked-project")],
+            Self::CodeTraceError(_) => vec![error("code-trace")],
+            Self::BaselineError(..) => vec![error("invalid-baseline")],
+            Self::IdSchemeError(_) => vec![error("invalid-id-scheme")],
+        }
+    }
 }
 
 /// Transformation errors
@@ -540,7 +862,7 @@ pub enum TransformError {
     #[error("Invalid document structure: {0}")]
     InvalidStructure(String),
 
-    #[error("Duplicate section number '{section_number}':\n  First occurrence:  {first_file}:{first_line}\n  Second occurrence: {second_file}:{second_line}", first_file = first_file.display(), second_file = second_file.display())]
+    #[error("Duplicate section number '{section_number}'")]
     DuplicateSectionNumber {
         section_number: SectionNumber,
         first_file: PathBuf,
@@ -550,9 +872,131 @@ pub enum TransformError {
     },
 }
 
+impl TransformError {
+    /// Convert the error into a diagnostic
+    pub fn diagnostic(&self) -> Diagnostic {
+        match self {
+            Self::InvalidStructure(_) => Diagnostic::error("invalid-structure", self.to_string()),
+            Self::DuplicateSectionNumber {
+                first_file,
+                first_line,
+                second_file,
+                second_line,
+                ..
+            } => Diagnostic::error("duplicate-section-number", self.to_string())
+                .at_line(second_file, *second_line)
+                .with_suggestion(format!(
+                    "renumber one of the sections (also used at {}:{})",
+                    first_file.display(),
+                    first_line
+                )),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::source_model::{check_spelling, MarkdownBlock};
+
+    /// Create a document directory with a minimal sysdoc.toml
+    fn write_config(root: &Path) {
+        let _ = std::fs::remove_dir_all(root);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("sysdoc.toml"),
+            "document_id = \"SDD\"\ndocument_title = \"Design\"\ndocument_type = \"SDD\"\n\
+             document_standard = \"s\"\n[document_owner]\nname = \"a\"\nemail = \"a\"\n\
+             [document_approver]\nname = \"b\"\nemail = \"b\"\n",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_check_sources_with_sees_authored_content() {
+        let root = std::env::temp_dir().join(format!("sysdoc-check-with-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        write_config(&root);
+        std::fs::write(
+            root.join("src/01_requirements.md"),
+            "# Parsr Input\n\n```sysdoc\nsection_id = \"SDD-1\"\n```\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/02_tests.md"),
+            "# Tests\n\n```sysdoc\nsection_id = \"SDD-2\"\ntraced_ids = [\"SDD-1\"]\n\n\
+             [[trace_table]]\ncolumns = [\"traced_id\", \"traced_title\"]\n```\n",
+        )
+        .unwrap();
+
+        let (model, diagnostics) = check_sources_with(&root, |model| {
+            let dictionary = model.spelling_dictionary()?;
+            let misspellings = check_spelling(&model.markdown_files, &dictionary);
+            Ok::<_, crate::source_model::SpellingError>(
+                misspellings.iter().map(Diagnostic::from).collect(),
+            )
+        })
+        .unwrap();
+
+        // The misspelt title is only reported where it was written ...
+        let spelling: Vec<(Option<&Path>, usize)> = diagnostics
+            .iter()
+            .filter(|d| d.code == "spelling")
+            .map(|d| (d.file.as_deref(), d.span.as_ref().map_or(0, |s| s.line)))
+            .collect();
+        assert_eq!(
+            spelling,
+            vec![(Some(Path::new("src/01_requirements.md")), 1)]
+        );
+
+        // ... although the generated trace table repeats it
+        let model = model.unwrap();
+        let tests = &model.markdown_files[1].sections[0];
+        assert!(tests.content.iter().any(|block| matches!(
+            block,
+            MarkdownBlock::InlineTable { rows, .. }
+                if rows.iter().any(|row| row[1][0].text == "Parsr Input")
+        )));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_missing_references_located() {
+        let root = std::env::temp_dir().join(format!("sysdoc-missing-{}", std::process::id()));
+        write_config(&root);
+        std::fs::write(
+            root.join("src/01_design.md"),
+            "# Design\n\n```sysdoc\ninclude_file = \"src/main.rs\"\n```\n\n\
+             See ![the pump](pump.png) and [the limits](limits.csv).\n\n\
+             Read [the setup](02_setup.md) first.\n",
+        )
+        .unwrap();
+
+        let (_, found) = check_sources(&root);
+        let diagnostics = crate::diagnostics::resolve(found, &root, &Default::default());
+        // Each message names only the reference; the span locates it
+        let located: Vec<String> = diagnostics
+            .iter()
+            .filter_map(|d| {
+                let span = d.span.as_ref()?;
+                Some(format!(
+                    "{}:{}-{} {}",
+                    span.line, span.column?, span.end_column?, d.message
+                ))
+            })
+            .sorted()
+            .collect();
+        assert_eq!(
+            located,
+            vec![
+                "4:17-28 Missing include file 'src/main.rs'",
+                "7:17-25 Missing image 'pump.png'",
+                "7:44-54 Missing table 'limits.csv'",
+                "9:18-29 Broken link '02_setup.md'",
+            ]
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
 
     #[test]
     fn test_parse_filename() {
@@ -0,0 +1,939 @@
+//! Section insertion, moves and renumbering
+//!
+//! Sections are laid out on disk as numbered markdown files (`03.02_data.md`),
+//! optionally grouped in numbered directories (`03-detailed-design/`). A number
+//! ending in `.00` stands for its parent section, so `03.00_detailed-design.md`
+//! is section 3. Each operation maps old section numbers to new ones, renames
+//! every numbered file and directory under `src/` to match, and rewrites the
+//! relative links, images and `include_file` paths that pointed at moved files.
+
+use crate::paths::{normalize, relative_path, SOURCE_DIR};
+use crate::source_model::{SectionNumber, MAX_SECTION_DEPTH};
+use crate::text::slug;
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use thiserror::Error;
+use walkdir::WalkDir;
+
+/// Directory that files are moved through while a plan is applied, so that
+/// renames which swap or shift names never overwrite each other
+const STAGING_DIR: &str = ".sysdoc/renumber";
+
+/// The path of an `include_file` setting in a sysdoc block
+static INCLUDE_FILE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?m)^[ \t>]*include_file\s*=\s*"([^"]*)""#).expect("valid include_file regex")
+});
+
+/// Errors that can occur while planning or applying a renumbering
+#[derive(Error, Debug)]
+pub enum RenumberError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid section number '{0}' (expected e.g. '3' or '3.2', without a .00 part)")]
+    InvalidNumber(String),
+
+    #[error("Section {0} does not exist")]
+    UnknownSection(SectionNumber),
+
+    #[error("Cannot place section {0}: its parent section {1} does not exist")]
+    MissingParent(SectionNumber, SectionNumber),
+
+    #[error("Cannot place section {number}: the next free number at that level is {next}")]
+    Gap {
+        number: SectionNumber,
+        next: SectionNumber,
+    },
+
+    #[error("Cannot move section {0} into its own subsection {1}")]
+    IntoItself(SectionNumber, SectionNumber),
+
+    #[error(
+        "Moving section {0} would nest its subsections deeper than {MAX_SECTION_DEPTH} levels"
+    )]
+    TooDeep(SectionNumber),
+
+    #[error("Cannot write '{path}': the file already exists", path = .0.display())]
+    Conflict(PathBuf),
+}
+
+/// A file renamed by a plan (paths relative to the document root)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// A markdown file whose links are rewritten by a plan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkUpdate {
+    /// Path of the file after the renames (relative to the document root)
+    pub path: PathBuf,
+    /// Content with the rewritten links
+    pub content: String,
+    /// Number of links and include paths rewritten
+    pub count: usize,
+}
+
+/// A markdown file created by a plan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewFile {
+    /// Path relative to the document root
+    pub path: PathBuf,
+    /// Markdown content
+    pub content: String,
+}
+
+/// File changes that carry out an insert, move or renumbering
+#[derive(Debug, Default)]
+pub struct LayoutPlan {
+    /// Files to rename, sorted by their current path
+    pub renames: Vec<Rename>,
+    /// Markdown files whose links change
+    pub link_updates: Vec<LinkUpdate>,
+    /// File to create for an inserted section
+    pub new_file: Option<NewFile>,
+}
+
+impl LayoutPlan {
+    /// Whether the plan changes nothing
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty() && self.link_updates.is_empty() && self.new_file.is_none()
+    }
+
+    /// Carry out the plan
+    ///
+    /// Renamed files are first moved into a staging directory and then to their
+    /// new paths. Directories under `src/` left empty are removed.
+    ///
+    /// # Parameters
+    /// * `root` - Root directory of the document
+    pub fn apply(&self, root: &Path) -> Result<(), RenumberError> {
+        let staging = root.join(STAGING_DIR);
+        std::fs::create_dir_all(&staging)?;
+        for (index, rename) in self.renames.iter().enumerate() {
+            std::fs::rename(root.join(&rename.from), staging.join(index.to_string()))?;
+        }
+        for (index, rename) in self.renames.iter().enumerate() {
+            let target = root.join(&rename.to);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::rename(staging.join(index.to_string()), target)?;
+        }
+        std::fs::remove_dir(&staging)?;
+        if let Some(parent) = staging.parent() {
+            // Only removed when nothing else (such as trace graphs) is kept there
+            let _ = std::fs::remove_dir(parent);
+        }
+        for rename in &self.renames {
+            remove_empty_dirs(root, &rename.from);
+        }
+
+        for update in &self.link_updates {
+            std::fs::write(root.join(&update.path), &update.content)?;
+        }
+        if let Some(new_file) = &self.new_file {
+            let path = root.join(&new_file.path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, &new_file.content)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plan inserting a new section, shifting later sections at its level down by one
+///
+/// The new file follows the layout of the neighbouring sections: when they are
+/// numbered directories, the section gets a directory and a `.00` file too.
+///
+/// # Parameters
+/// * `root` - Root directory of the document
+/// * `number` - Number of the new section (e.g., "3.2")
+/// * `title` - Heading of the new section
+pub fn plan_insert(root: &Path, number: &str, title: &str) -> Result<LayoutPlan, RenumberError> {
+    let layout = Layout::scan(root)?;
+    let number = parse_number(number)?;
+    layout.check_position(&number, None)?;
+
+    let map = layout.map_numbers(|n| shifted(n, &number, true));
+    let (container, as_dir) = layout.container(&number, &map, None)?;
+    let slug = slug(title);
+    let formatted = format_number(&number);
+    let path = if as_dir {
+        container
+            .join(format!("{}-{}", formatted, slug))
+            .join(format!("{}.00_{}.md", formatted, slug))
+    } else {
+        container.join(format!("{}_{}.md", formatted, slug))
+    };
+
+    let mut plan = layout.plan(&map, None)?;
+    if root.join(&path).exists() || plan.renames.iter().any(|rename| rename.to == path) {
+        return Err(RenumberError::Conflict(path));
+    }
+    plan.new_file = Some(NewFile {
+        path,
+        content: format!("# {}\n", title),
+    });
+    Ok(plan)
+}
+
+/// Plan moving a section and its subsections so that it becomes section `to`
+///
+/// Later sections at the old position move up to close the gap; sections at or
+/// after `to` move down to make room.
+///
+/// # Parameters
+/// * `root` - Root directory of the document
+/// * `from` - Current number of the section (e.g., "3.4")
+/// * `to` - Number of the section after the move (e.g., "3.2" or "4.1")
+pub fn plan_move(root: &Path, from: &str, to: &str) -> Result<LayoutPlan, RenumberError> {
+    let layout = Layout::scan(root)?;
+    let from = parse_number(from)?;
+    let to = parse_number(to)?;
+    if !layout.numbers.contains(&from) {
+        return Err(RenumberError::UnknownSection(from));
+    }
+    if to.parts().starts_with(from.parts()) {
+        return Err(RenumberError::IntoItself(from, to));
+    }
+    let deepest = layout
+        .numbers
+        .iter()
+        .filter(|n| n.parts().starts_with(from.parts()))
+        .map(|n| n.parts().len())
+        .max()
+        .unwrap_or(from.parts().len());
+    if deepest - from.parts().len() + to.parts().len() > MAX_SECTION_DEPTH {
+        return Err(RenumberError::TooDeep(from));
+    }
+
+    // Positions for `to` are counted with the moved section taken out
+    let remaining = Layout {
+        numbers: (layout.numbers.iter())
+            .filter(|n| !n.parts().starts_with(from.parts()))
+            .map(|n| shifted(n, &from, false))
+            .collect(),
+        ..layout.clone()
+    };
+    remaining.check_position(&to, Some(&from))?;
+
+    let map = layout.map_numbers(|n| match n.parts().strip_prefix(from.parts()) {
+        Some(rest) => to.extend(rest).unwrap_or_else(|_| n.clone()),
+        None => shifted(&shifted(n, &from, false), &to, true),
+    });
+    layout.plan(&map, Some((&from, &to)))
+}
+
+/// Plan closing the gaps in section numbering
+///
+/// The sections at each level are numbered consecutively from 1 (from 0 when
+/// the first one is numbered 0), keeping their order.
+///
+/// # Parameters
+/// * `root` - Root directory of the document
+pub fn plan_renumber(root: &Path) -> Result<LayoutPlan, RenumberError> {
+    let layout = Layout::scan(root)?;
+
+    let mut map: BTreeMap<SectionNumber, SectionNumber> = BTreeMap::new();
+    let mut next_child: BTreeMap<Vec<u32>, u32> = BTreeMap::new();
+    // Parents sort before their subsections, so each parent is mapped first
+    for number in &layout.numbers {
+        let (last, parent) = number.parts().split_last().unwrap_or((&0, &[]));
+        let next = next_child
+            .entry(parent.to_vec())
+            .or_insert(u32::from(*last != 0));
+        let renumbered = match SectionNumber::parse(&join_parts(parent)) {
+            Some(parent) => map.get(&parent).unwrap_or(&parent).extend(&[*next]).ok(),
+            None => SectionNumber::parse(&next.to_string()),
+        };
+        *next += 1;
+        map.insert(number.clone(), renumbered.unwrap_or_else(|| number.clone()));
+    }
+    layout.plan(&map, None)
+}
+
+/// Mapped number, directory and directory flag of a section beside a new position
+type Sibling = (SectionNumber, PathBuf, bool);
+
+/// The numbered files and directories under `src/`
+#[derive(Debug, Clone)]
+struct Layout {
+    root: PathBuf,
+    /// Every file under `src/`, relative to the document root
+    files: Vec<PathBuf>,
+    /// Numbers of every section with a file or directory, and their ancestors
+    numbers: BTreeSet<SectionNumber>,
+}
+
+impl Layout {
+    /// Find the files and section numbers of a document
+    fn scan(root: &Path) -> Result<Self, RenumberError> {
+        let mut files = Vec::new();
+        for entry in WalkDir::new(root.join(SOURCE_DIR)).sort_by_file_name() {
+            let entry = entry.map_err(std::io::Error::other)?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            files.extend(entry.path().strip_prefix(root).map(Path::to_path_buf));
+        }
+
+        let mut numbers = BTreeSet::new();
+        for file in &files {
+            for name in numbered_components(file).into_iter().flatten() {
+                let parts = name.number.parts();
+                numbers.extend(
+                    (1..=parts.len())
+                        .filter_map(|depth| SectionNumber::parse(&join_parts(&parts[..depth]))),
+                );
+            }
+        }
+        Ok(Self {
+            root: root.to_path_buf(),
+            files,
+            numbers,
+        })
+    }
+
+    /// Map every known section number
+    fn map_numbers(
+        &self,
+        f: impl Fn(&SectionNumber) -> SectionNumber,
+    ) -> BTreeMap<SectionNumber, SectionNumber> {
+        self.numbers.iter().map(|n| (n.clone(), f(n))).collect()
+    }
+
+    /// Check that a section can be placed at `number` without leaving a gap
+    fn check_position(
+        &self,
+        number: &SectionNumber,
+        moving: Option<&SectionNumber>,
+    ) -> Result<(), RenumberError> {
+        let (last, parent) = number.parts().split_last().unwrap_or((&0, &[]));
+        if let Some(parent) = SectionNumber::parse(&join_parts(parent)) {
+            if !self.numbers.contains(&parent) {
+                return Err(RenumberError::MissingParent(number.clone(), parent));
+            }
+        }
+        let previous = self
+            .numbers
+            .iter()
+            .filter(|n| Some(*n) != moving && is_sibling(n, number))
+            .filter(|n| n.parts().last() < Some(last))
+            .max();
+        let first = *last <= 1;
+        let next = match previous.and_then(|p| p.parts().last()) {
+            Some(previous) => previous + 1,
+            None if first => return Ok(()),
+            None => 1,
+        };
+        if next >= *last {
+            return Ok(());
+        }
+        let mut next_parts = parent.to_vec();
+        next_parts.push(next);
+        Err(RenumberError::Gap {
+            number: number.clone(),
+            next: SectionNumber::parse(&join_parts(&next_parts)).unwrap_or_else(|| number.clone()),
+        })
+    }
+
+    /// Directory that holds a section placed at `number`, and whether its
+    /// neighbours are numbered directories
+    ///
+    /// The directory of the nearest section at the same level is used; without
+    /// one, the parent's directory (or the directory of its file) is used.
+    fn container(
+        &self,
+        number: &SectionNumber,
+        map: &BTreeMap<SectionNumber, SectionNumber>,
+        moving: Option<&SectionNumber>,
+    ) -> Result<(PathBuf, bool), RenumberError> {
+        let mut siblings = Vec::new();
+        let mut parent_dirs = Vec::new();
+        let parent = &number.parts()[..number.parts().len() - 1];
+        for file in self.files.iter().filter(|file| !is_moved(file, moving)) {
+            let (sibling, dirs) = self.placements(file, number, map);
+            siblings.extend(sibling);
+            parent_dirs.extend(dirs);
+        }
+
+        let before = siblings
+            .iter()
+            .filter(|(n, ..)| n < number)
+            .max_by(|a, b| a.0.cmp(&b.0));
+        let after = siblings
+            .iter()
+            .filter(|(n, ..)| n > number)
+            .min_by(|a, b| a.0.cmp(&b.0));
+        if let Some((_, dir, is_dir)) = before.or(after) {
+            return Ok((dir.clone(), *is_dir));
+        }
+        // A parent directory is preferred over the directory of the parent's file
+        if let Some((_, dir)) = parent_dirs.into_iter().min() {
+            return Ok((dir, false));
+        }
+        match SectionNumber::parse(&join_parts(parent)) {
+            Some(parent) => Err(RenumberError::MissingParent(number.clone(), parent)),
+            None => Ok((PathBuf::from(SOURCE_DIR), false)),
+        }
+    }
+
+    /// Where `file` suggests placing a section numbered `number`
+    ///
+    /// # Returns
+    /// * The mapped number, directory and directory flag of a sibling section
+    ///   named along the path, if any
+    /// * The directories of parent sections named along the path, ranked so
+    ///   that numbered directories come first
+    fn placements(
+        &self,
+        file: &Path,
+        number: &SectionNumber,
+        map: &BTreeMap<SectionNumber, SectionNumber>,
+    ) -> (Option<Sibling>, Vec<(bool, PathBuf)>) {
+        let parent = &number.parts()[..number.parts().len() - 1];
+        let mut parent_dirs = Vec::new();
+        let names = numbered_components(file);
+        let named = names
+            .iter()
+            .enumerate()
+            .filter_map(|(index, name)| name.as_ref().map(|name| (index, name)));
+        for (index, name) in named {
+            let mapped = map.get(&name.number).unwrap_or(&name.number);
+            let prefix = self.map_components(file, index, map);
+            if is_sibling(mapped, number) {
+                return (Some((mapped.clone(), prefix, name.is_dir)), parent_dirs);
+            }
+            if mapped.parts() != parent {
+                continue;
+            }
+            let dir = if name.is_dir {
+                self.map_components(file, index + 1, map)
+            } else {
+                prefix
+            };
+            parent_dirs.push((!name.is_dir, dir));
+        }
+        (None, parent_dirs)
+    }
+
+    /// Rename the numbered components of a path
+    fn map_path(&self, path: &Path, map: &BTreeMap<SectionNumber, SectionNumber>) -> PathBuf {
+        let count = path.components().count();
+        self.map_components(path, count, map)
+    }
+
+    /// Rename the numbered components among the first `count` components of a path
+    fn map_components(
+        &self,
+        path: &Path,
+        count: usize,
+        map: &BTreeMap<SectionNumber, SectionNumber>,
+    ) -> PathBuf {
+        let names = numbered_components(path);
+        path.components()
+            .zip(names)
+            .take(count)
+            .map(|(component, name)| match name {
+                Some(name) => name.renamed(map.get(&name.number).unwrap_or(&name.number)),
+                None => component.as_os_str().to_string_lossy().into_owned(),
+            })
+            .collect()
+    }
+
+    /// Build the renames and link updates for a number mapping
+    ///
+    /// # Parameters
+    /// * `map` - New number of every known section number
+    /// * `moved` - For a move, the moved section's old and new numbers; its files
+    ///   are relocated next to their new neighbours
+    fn plan(
+        &self,
+        map: &BTreeMap<SectionNumber, SectionNumber>,
+        moved: Option<(&SectionNumber, &SectionNumber)>,
+    ) -> Result<LayoutPlan, RenumberError> {
+        let container = match moved {
+            Some((from, to)) => Some(self.container(to, map, Some(from))?),
+            None => None,
+        };
+
+        let mut targets: BTreeMap<PathBuf, PathBuf> = BTreeMap::new();
+        for file in &self.files {
+            let anchor = moved.and_then(|(from, _)| moved_component(file, from));
+            let target = match (anchor, &container) {
+                (Some(index), Some((dir, _))) => {
+                    let rest = self.map_path(file, map);
+                    dir.join(rest.components().skip(index).collect::<PathBuf>())
+                }
+                _ => self.map_path(file, map),
+            };
+            targets.insert(file.clone(), target);
+        }
+
+        let mut seen = BTreeSet::new();
+        let mut renames = Vec::new();
+        for (from, to) in &targets {
+            if !seen.insert(to.clone()) {
+                return Err(RenumberError::Conflict(to.clone()));
+            }
+            if from == to {
+                continue;
+            }
+            if self.root.join(to).exists() && !targets.contains_key(to) {
+                return Err(RenumberError::Conflict(to.clone()));
+            }
+            renames.push(Rename {
+                from: from.clone(),
+                to: to.clone(),
+            });
+        }
+
+        let mut link_updates = Vec::new();
+        for (from, to) in targets.iter().filter(|(from, _)| is_markdown(from)) {
+            let content = std::fs::read_to_string(self.root.join(from))?;
+            let (content, count) = self.rewrite_links(&content, from, to, &targets);
+            if count > 0 {
+                link_updates.push(LinkUpdate {
+                    path: to.clone(),
+                    content,
+                    count,
+                });
+            }
+        }
+
+        Ok(LayoutPlan {
+            renames,
+            link_updates,
+            new_file: None,
+        })
+    }
+
+    /// Rewrite the links of a markdown file that moves from `old` to `new`
+    ///
+    /// References are resolved like sysdoc resolves them: links relative to the
+    /// document root, images and `include_file` relative to the file's directory.
+    /// References to files that do not exist are left alone.
+    ///
+    /// # Returns
+    /// * The new content and the number of rewritten references
+    fn rewrite_links(
+        &self,
+        content: &str,
+        old: &Path,
+        new: &Path,
+        targets: &BTreeMap<PathBuf, PathBuf>,
+    ) -> (String, usize) {
+        let old_dir = old.parent().unwrap_or(Path::new(""));
+        let new_dir = new.parent().unwrap_or(Path::new(""));
+        let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+        for (range, base) in reference_ranges(content) {
+            let url = &content[range.clone()];
+            if url.contains("://") || url.starts_with(['#', '/']) || url.starts_with("mailto:") {
+                continue;
+            }
+            let (path, anchor) = url.split_at(url.find('#').unwrap_or(url.len()));
+            let dir = match base {
+                ReferenceBase::Root => Path::new(""),
+                ReferenceBase::File => old_dir,
+            };
+            let Some(target) = normalize(&dir.join(path)) else {
+                continue;
+            };
+            if !targets.contains_key(&target) && !self.root.join(&target).exists() {
+                continue;
+            }
+            let moved_target = targets.get(&target).unwrap_or(&target);
+            let new_path = match base {
+                ReferenceBase::Root if *moved_target == target => continue,
+                ReferenceBase::Root => to_slash(moved_target),
+                ReferenceBase::File => relative_path(new_dir, moved_target),
+            };
+            if new_path != path {
+                edits.push((range, format!("{}{}", new_path, anchor)));
+            }
+        }
+
+        let count = edits.len();
+        let mut content = content.to_string();
+        edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+        for (range, replacement) in edits {
+            content.replace_range(range, &replacement);
+        }
+        (content, count)
+    }
+}
+
+/// A numbered file or directory name, such as `03.02_data.md` or `03-design`
+#[derive(Debug, Clone)]
+struct NumberedName {
+    /// The name as written
+    text: String,
+    /// Section number, without a trailing `.00`
+    number: SectionNumber,
+    /// Whether the name ends in `.00` (stands for the parent section)
+    marker: bool,
+    /// Text after the number and its separator
+    slug: String,
+    is_dir: bool,
+}
+
+impl NumberedName {
+    /// Parse a path component (`_` separates a file's number, `-` a directory's)
+    fn parse(text: &str, is_dir: bool) -> Option<Self> {
+        let (stem, separator) = if is_dir {
+            (text, '-')
+        } else {
+            (text.strip_suffix(".md")?, '_')
+        };
+        if !stem.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        let (number, slug) = stem.split_once(separator)?;
+        let number = SectionNumber::parse(number)?;
+        let parent = number.without_parent_marker();
+        Some(Self {
+            text: text.to_string(),
+            marker: parent.is_some(),
+            number: parent.unwrap_or(number),
+            slug: slug.to_string(),
+            is_dir,
+        })
+    }
+
+    /// The name with a different section number
+    fn renamed(&self, number: &SectionNumber) -> String {
+        let marker = if self.marker { ".00" } else { "" };
+        if self.is_dir {
+            format!("{}{}-{}", format_number(number), marker, self.slug)
+        } else {
+            format!("{}{}_{}.md", format_number(number), marker, self.slug)
+        }
+    }
+}
+
+/// Parse the numbered components of a file path below `src/`
+///
+/// The returned vector has one entry per component of `path`.
+fn numbered_components(path: &Path) -> Vec<Option<NumberedName>> {
+    let count = path.components().count();
+    path.components()
+        .enumerate()
+        .map(|(index, component)| {
+            let text = component.as_os_str().to_str()?;
+            (index > 0).then(|| NumberedName::parse(text, index + 1 < count))?
+        })
+        .collect()
+}
+
+/// Index of the first component of `path` inside the section being moved
+fn moved_component(path: &Path, from: &SectionNumber) -> Option<usize> {
+    numbered_components(path).iter().position(|name| {
+        name.as_ref()
+            .is_some_and(|name| name.number.parts().starts_with(from.parts()))
+    })
+}
+
+/// Whether a file belongs to the section being moved
+fn is_moved(path: &Path, moving: Option<&SectionNumber>) -> bool {
+    moving.is_some_and(|from| moved_component(path, from).is_some())
+}
+
+/// Shift a section number to open (`open`) or close a gap at `at`
+///
+/// Sections at the same level as `at` from its position onward, and their
+/// subsections, move down by one when opening and up by one when closing.
+fn shifted(number: &SectionNumber, at: &SectionNumber, open: bool) -> SectionNumber {
+    let depth = at.parts().len() - 1;
+    let parts = number.parts();
+    let affected = parts.len() > depth
+        && parts[..depth] == at.parts()[..depth]
+        && if open {
+            parts[depth] >= at.parts()[depth]
+        } else {
+            parts[depth] > at.parts()[depth]
+        };
+    if !affected {
+        return number.clone();
+    }
+    let mut parts = parts.to_vec();
+    parts[depth] = if open {
+        parts[depth] + 1
+    } else {
+        parts[depth] - 1
+    };
+    SectionNumber::parse(&join_parts(&parts)).unwrap_or_else(|| number.clone())
+}
+
+/// Whether two section numbers are at the same level under the same parent
+fn is_sibling(a: &SectionNumber, b: &SectionNumber) -> bool {
+    let (a, b) = (a.parts(), b.parts());
+    a.len() == b.len() && a[..a.len() - 1] == b[..b.len() - 1]
+}
+
+/// Parse a section number given on the command line
+fn parse_number(text: &str) -> Result<SectionNumber, RenumberError> {
+    SectionNumber::parse(text)
+        .filter(|number| number.parts().iter().skip(1).all(|part| *part > 0))
+        .filter(|number| number.parts().last() != Some(&0) || number.parts().len() == 1)
+        .ok_or_else(|| RenumberError::InvalidNumber(text.to_string()))
+}
+
+/// Format a section number as in file names (`03.02`)
+fn format_number(number: &SectionNumber) -> String {
+    number
+        .parts()
+        .iter()
+        .map(|part| format!("{:02}", part))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Join number parts with dots (`[3, 2]` -> `"3.2"`)
+fn join_parts(parts: &[u32]) -> String {
+    parts
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// What a reference destination is relative to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReferenceBase {
+    /// The document root (links and reference definitions)
+    Root,
+    /// The directory of the markdown file (images and `include_file`)
+    File,
+}
+
+/// Byte ranges of the link, image, reference definition and `include_file`
+/// destinations in markdown content, with what each is relative to
+fn reference_ranges(content: &str) -> Vec<(Range<usize>, ReferenceBase)> {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    let mut parser = Parser::new_ext(content, options).into_offset_iter();
+
+    let mut ranges = Vec::new();
+    for (event, range) in parser.by_ref() {
+        let (dest_url, base) = match event {
+            Event::Start(Tag::Link { dest_url, .. }) => (dest_url, ReferenceBase::Root),
+            Event::Start(Tag::Image { dest_url, .. }) => (dest_url, ReferenceBase::File),
+            _ => continue,
+        };
+        ranges.extend(find_in(content, range, &dest_url).map(|found| (found, base)));
+    }
+    for (_, definition) in parser.reference_definitions().iter() {
+        let found = find_in(content, definition.span.clone(), &definition.dest);
+        ranges.extend(found.map(|found| (found, ReferenceBase::Root)));
+    }
+
+    ranges.extend(
+        INCLUDE_FILE
+            .captures_iter(content)
+            .filter_map(|captures| Some((captures.get(1)?.range(), ReferenceBase::File))),
+    );
+    ranges.sort_by_key(|(range, _)| range.start);
+    ranges.dedup();
+    ranges
+}
+
+/// Range of the last occurrence of `text` within `range` of `content`
+fn find_in(content: &str, range: Range<usize>, text: &str) -> Option<Range<usize>> {
+    if text.is_empty() {
+        return None;
+    }
+    let offset = content.get(range.clone())?.rfind(text)?;
+    let start = range.start + offset;
+    Some(start..start + text.len())
+}
+
+/// A relative path with `/` separators
+fn to_slash(path: &Path) -> String {
+    relative_path(Path::new(""), path)
+}
+
+/// Whether a path names a markdown file
+fn is_markdown(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("md")
+}
+
+/// Remove the directories under `src/` that contain `path` while they are empty
+fn remove_empty_dirs(root: &Path, path: &Path) {
+    let source_dir = root.join(SOURCE_DIR);
+    let path = root.join(path);
+    for dir in path.ancestors().skip(1) {
+        if !dir.starts_with(&source_dir) || dir == source_dir || std::fs::remove_dir(dir).is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write a document with two chapters in numbered directories
+    fn write_document(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("sysdoc-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let files = [
+            (
+                "src/01-intro/01.00_intro.md",
+                "# Intro\n\nSee [data](src/02-design/02.02_data.md#schema).\n",
+            ),
+            ("src/01-intro/01.01_purpose.md", "# Purpose\n"),
+            ("src/02-design/02.00_design.md", "# Design\n"),
+            (
+                "src/02-design/02.01_ui.md",
+                "# UI\n\n![Screen](diagrams/ui.png)\n",
+            ),
+            (
+                "src/02-design/02.02_data.md",
+                "# Data\n\n```sysdoc\ninclude_file = \"schemas/data.json\"\n```\n",
+            ),
+            ("src/02-design/diagrams/ui.png", "png"),
+            ("src/02-design/schemas/data.json", "{}"),
+        ];
+        for (path, content) in files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        root
+    }
+
+    fn renames(plan: &LayoutPlan) -> Vec<(String, String)> {
+        plan.renames
+            .iter()
+            .map(|r| (to_slash(&r.from), to_slash(&r.to)))
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_shifts_later_sections() {
+        let root = write_document("insert");
+        let plan = plan_insert(&root, "2.2", "Storage Layer").unwrap();
+
+        assert_eq!(
+            renames(&plan),
+            vec![(
+                "src/02-design/02.02_data.md".to_string(),
+                "src/02-design/02.03_data.md".to_string()
+            )]
+        );
+        assert_eq!(
+            plan.new_file.as_ref().unwrap().path,
+            PathBuf::from("src/02-design/02.02_storage-layer.md")
+        );
+        assert_eq!(plan.link_updates.len(), 1);
+        assert!(plan.link_updates[0]
+            .content
+            .contains("](src/02-design/02.03_data.md#schema)"));
+
+        plan.apply(&root).unwrap();
+        assert!(root.join("src/02-design/02.03_data.md").exists());
+        assert!(root.join("src/02-design/02.02_storage-layer.md").exists());
+
+        // A new chapter follows the layout of the other chapters
+        let plan = plan_insert(&root, "2", "Architecture").unwrap();
+        assert_eq!(
+            plan.new_file.as_ref().unwrap().path,
+            PathBuf::from("src/02-architecture/02.00_architecture.md")
+        );
+        assert!(renames(&plan).contains(&(
+            "src/02-design/diagrams/ui.png".to_string(),
+            "src/03-design/diagrams/ui.png".to_string()
+        )));
+
+        assert!(matches!(
+            plan_insert(&root, "2.5", "Far"),
+            Err(RenumberError::Gap { .. })
+        ));
+        assert!(matches!(
+            plan_insert(&root, "4.1", "Orphan"),
+            Err(RenumberError::MissingParent(..))
+        ));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_move_rewrites_relative_paths() {
+        let root = write_document("move");
+        let plan = plan_move(&root, "2.2", "1.1").unwrap();
+
+        assert_eq!(
+            renames(&plan),
+            vec![
+                (
+                    "src/01-intro/01.01_purpose.md".to_string(),
+                    "src/01-intro/01.02_purpose.md".to_string()
+                ),
+                (
+                    "src/02-design/02.02_data.md".to_string(),
+                    "src/01-intro/01.01_data.md".to_string()
+                ),
+            ]
+        );
+        let updates: BTreeMap<String, &str> = plan
+            .link_updates
+            .iter()
+            .map(|u| (to_slash(&u.path), u.content.as_str()))
+            .collect();
+        assert!(
+            updates["src/01-intro/01.00_intro.md"].contains("](src/01-intro/01.01_data.md#schema)")
+        );
+        assert!(updates["src/01-intro/01.01_data.md"]
+            .contains("include_file = \"../02-design/schemas/data.json\""));
+
+        plan.apply(&root).unwrap();
+        assert!(!root.join("src/02-design/02.02_data.md").exists());
+        assert!(root.join("src/01-intro/01.01_data.md").exists());
+
+        assert!(matches!(
+            plan_move(&root, "2", "2.1"),
+            Err(RenumberError::IntoItself(..))
+        ));
+        assert!(matches!(
+            plan_move(&root, "9", "1"),
+            Err(RenumberError::UnknownSection(_))
+        ));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_renumber_closes_gaps() {
+        let root = write_document("renumber");
+        std::fs::rename(root.join("src/02-design"), root.join("src/04-design")).unwrap();
+        for (from, to) in [
+            ("02.00_design", "04.00_design"),
+            ("02.01_ui", "04.03_ui"),
+            ("02.02_data", "04.05_data"),
+        ] {
+            std::fs::rename(
+                root.join(format!("src/04-design/{}.md", from)),
+                root.join(format!("src/04-design/{}.md", to)),
+            )
+            .unwrap();
+        }
+
+        let plan = plan_renumber(&root).unwrap();
+        let renamed: Vec<String> = plan.renames.iter().map(|r| to_slash(&r.to)).collect();
+        assert!(renamed.contains(&"src/02-design/02.00_design.md".to_string()));
+        assert!(renamed.contains(&"src/02-design/02.01_ui.md".to_string()));
+        assert!(renamed.contains(&"src/02-design/02.02_data.md".to_string()));
+        assert!(renamed.contains(&"src/02-design/diagrams/ui.png".to_string()));
+
+        plan.apply(&root).unwrap();
+        assert!(plan_renumber(&root).unwrap().is_empty());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,809 @@
+//! ReqIF 1.2 exporter for requirements management tools
+//!
+//! This module exports a UnifiedDocument to a ReqIF (Requirements Interchange
+//! Format) file that DOORS and other ReqIF-capable tools can import:
+//! - Every section with a `section_id` becomes a SpecObject carrying its ID,
+//!   heading, section number, status, verification method and XHTML text
+//! - Every `traced_ids` entry becomes a SpecRelation; traced IDs defined outside
+//!   the document become placeholder SpecObjects of an "External Requirement" type
+//! - Sections without a `section_id` become SpecObjects of a "Heading" type
+//!   carrying their heading, section number and text, so that chapters stay in
+//!   the hierarchy
+//! - The section hierarchy is preserved in a single Specification
+//!
+//! # Determinism
+//! All identifiers are derived from the document and section IDs, elements are
+//! written in section-number or ID order, and every timestamp is the document's
+//! last-modified date (the HEAD commit date), so rebuilding an unchanged commit
+//! produces an identical file.
+
+use crate::source_model::{ListItem, MarkdownBlock, MarkdownSection, TextRun, HEADING_TYPE};
+use crate::text::escape_xml;
+use crate::unified_document::UnifiedDocument;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write as _;
+use std::path::Path;
+use thiserror::Error;
+
+/// Errors that can occur during ReqIF export
+#[derive(Error, Debug)]
+pub enum ReqifExportError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// Timestamp used when the document has no last-modified date (not in git)
+const FALLBACK_TIMESTAMP: &str = "1970-01-01T00:00:00Z";
+
+/// Identifiers of the fixed datatype, type and attribute definitions
+const DATATYPE_STRING: &str = "_sysdoc-DT-String";
+const DATATYPE_XHTML: &str = "_sysdoc-DT-XHTML";
+const TYPE_SECTION: &str = "_sysdoc-SOT-Section";
+const TYPE_HEADING: &str = "_sysdoc-SOT-Heading";
+const TYPE_EXTERNAL: &str = "_sysdoc-SOT-External";
+const TYPE_TRACES_TO: &str = "_sysdoc-SRT-TracesTo";
+const TYPE_DOCUMENT: &str = "_sysdoc-ST-Document";
+
+/// String attributes of section SpecObjects: (identifier suffix, long name)
+const STRING_ATTRIBUTES: [(&str, &str); 5] = [
+    ("ForeignID", "ReqIF.ForeignID"),
+    ("ChapterName", "ReqIF.ChapterName"),
+    ("SectionNumber", "Section Number"),
+    ("Status", "Status"),
+    ("VerificationMethod", "Verification Method"),
+];
+
+/// Export a unified document to ReqIF format
+///
+/// # Parameters
+/// * `doc` - The unified document to export
+/// * `output_path` - Path where the .reqif file will be written
+///
+/// # Returns
+/// * `Ok(())` - Successfully exported to ReqIF
+/// * `Err(ReqifExportError)` - Error writing the output file
+pub fn to_reqif(doc: &UnifiedDocument, output_path: &Path) -> Result<(), ReqifExportError> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(output_path, generate_reqif(doc))?;
+    Ok(())
+}
+
+/// String attributes of heading SpecObjects: (identifier suffix, long name)
+const HEADING_ATTRIBUTES: [(&str, &str); 2] = [
+    ("ChapterName", "ReqIF.ChapterName"),
+    ("SectionNumber", "Section Number"),
+];
+
+/// A section exported as a SpecObject
+struct ExportedSection<'a> {
+    /// The section's `section_id` (None for a heading object)
+    section_id: Option<&'a str>,
+    /// The source section
+    section: &'a MarkdownSection,
+}
+
+impl ExportedSection<'_> {
+    /// Key the section's SpecObject and SpecHierarchy identifiers are derived from
+    fn key(&self) -> String {
+        match self.section_id {
+            Some(section_id) => section_id.to_string(),
+            None => format!("H-{}", self.section.section_number),
+        }
+    }
+}
+
+/// Generate the complete ReqIF XML for a document
+fn generate_reqif(doc: &UnifiedDocument) -> String {
+    let timestamp = doc
+        .metadata
+        .modified
+        .as_deref()
+        .filter(|date| !date.is_empty())
+        .unwrap_or(FALLBACK_TIMESTAMP);
+
+    let sections: Vec<ExportedSection> = doc
+        .sections
+        .iter()
+        .map(|section| ExportedSection {
+            section_id: section
+                .metadata
+                .as_ref()
+                .and_then(|m| m.section_id.as_deref()),
+            section,
+        })
+        .collect();
+
+    let local_ids: BTreeSet<&str> = sections.iter().filter_map(|s| s.section_id).collect();
+    let relations: BTreeSet<(&str, &str)> = sections
+        .iter()
+        .filter_map(|s| Some((s.section_id?, s.section.metadata.as_ref()?)))
+        .flat_map(|(section_id, metadata)| {
+            let traced_ids = metadata.traced_ids.as_deref().unwrap_or_default();
+            traced_ids.iter().map(move |id| (section_id, id.as_str()))
+        })
+        .collect();
+    let external_ids: BTreeSet<&str> = relations
+        .iter()
+        .map(|(_, target)| *target)
+        .filter(|target| !local_ids.contains(target))
+        .collect();
+    let keys: Vec<String> = sections.iter().map(ExportedSection::key).collect();
+    let ids = XmlIds::new(
+        keys.iter()
+            .map(String::as_str)
+            .chain(external_ids.iter().copied()),
+    );
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<REQ-IF xmlns=\"http://www.omg.org/spec/ReqIF/20110401/reqif.xsd\" xmlns:xhtml=\"http://www.w3.org/1999/xhtml\">\n");
+    write_header(&mut out, doc, timestamp);
+    out.push_str("  <CORE-CONTENT>\n    <REQ-IF-CONTENT>\n");
+    write_datatypes(&mut out, timestamp);
+    write_spec_types(&mut out, timestamp);
+
+    out.push_str("      <SPEC-OBJECTS>\n");
+    for exported in &sections {
+        match exported.section_id {
+            Some(section_id) => {
+                write_section_object(&mut out, &ids, section_id, exported, timestamp)
+            }
+            None => write_heading_object(&mut out, &ids, exported, timestamp),
+        }
+    }
+    for id in &external_ids {
+        write_external_object(&mut out, &ids, id, timestamp);
+    }
+    out.push_str("      </SPEC-OBJECTS>\n");
+
+    out.push_str("      <SPEC-RELATIONS>\n");
+    for (source, target) in &relations {
+        write_relation(&mut out, &ids, source, target, timestamp);
+    }
+    out.push_str("      </SPEC-RELATIONS>\n");
+
+    write_specification(&mut out, doc, &ids, &sections, timestamp);
+    out.push_str("    </REQ-IF-CONTENT>\n  </CORE-CONTENT>\n</REQ-IF>\n");
+    out
+}
+
+/// Write the REQ-IF header
+fn write_header(out: &mut String, doc: &UnifiedDocument, timestamp: &str) {
+    let _ = write!(
+        out,
+        r#"  <THE-HEADER>
+    <REQ-IF-HEADER IDENTIFIER="{id}">
+      <CREATION-TIME>{timestamp}</CREATION-TIME>
+      <REQ-IF-TOOL-ID>sysdoc</REQ-IF-TOOL-ID>
+      <REQ-IF-VERSION>1.2</REQ-IF-VERSION>
+      <SOURCE-TOOL-ID>sysdoc</SOURCE-TOOL-ID>
+      <TITLE>{title}</TITLE>
+    </REQ-IF-HEADER>
+  </THE-HEADER>
+"#,
+        id = xml_id("H-", &doc.metadata.document_id),
+        title = escape_xml(&doc.metadata.title),
+    );
+}
+
+/// Write the string and XHTML datatype definitions
+fn write_datatypes(out: &mut String, timestamp: &str) {
+    let _ = write!(
+        out,
+        r#"      <DATATYPES>
+        <DATATYPE-DEFINITION-STRING IDENTIFIER="{DATATYPE_STRING}" LAST-CHANGE="{timestamp}" LONG-NAME="String" MAX-LENGTH="32000"/>
+        <DATATYPE-DEFINITION-XHTML IDENTIFIER="{DATATYPE_XHTML}" LAST-CHANGE="{timestamp}" LONG-NAME="XHTML"/>
+      </DATATYPES>
+"#
+    );
+}
+
+/// Write the SpecObject, SpecRelation and Specification type definitions
+fn write_spec_types(out: &mut String, timestamp: &str) {
+    out.push_str("      <SPEC-TYPES>\n");
+
+    let _ = writeln!(
+        out,
+        r#"        <SPEC-OBJECT-TYPE IDENTIFIER="{TYPE_SECTION}" LAST-CHANGE="{timestamp}" LONG-NAME="Section">"#
+    );
+    out.push_str("          <SPEC-ATTRIBUTES>\n");
+    for (suffix, long_name) in STRING_ATTRIBUTES {
+        write_string_attribute_definition(out, TYPE_SECTION, suffix, long_name, timestamp);
+    }
+    write_text_attribute_definition(out, TYPE_SECTION, timestamp);
+    out.push_str("          </SPEC-ATTRIBUTES>\n        </SPEC-OBJECT-TYPE>\n");
+
+    let _ = writeln!(
+        out,
+        r#"        <SPEC-OBJECT-TYPE IDENTIFIER="{TYPE_HEADING}" LAST-CHANGE="{timestamp}" LONG-NAME="{HEADING_TYPE}">"#
+    );
+    out.push_str("          <SPEC-ATTRIBUTES>\n");
+    for (suffix, long_name) in HEADING_ATTRIBUTES {
+        write_string_attribute_definition(out, TYPE_HEADING, suffix, long_name, timestamp);
+    }
+    write_text_attribute_definition(out, TYPE_HEADING, timestamp);
+    out.push_str("          </SPEC-ATTRIBUTES>\n        </SPEC-OBJECT-TYPE>\n");
+
+    let _ = writeln!(
+        out,
+        r#"        <SPEC-OBJECT-TYPE IDENTIFIER="{TYPE_EXTERNAL}" LAST-CHANGE="{timestamp}" LONG-NAME="External Requirement">"#
+    );
+    out.push_str("          <SPEC-ATTRIBUTES>\n");
+    write_string_attribute_definition(
+        out,
+        TYPE_EXTERNAL,
+        "ForeignID",
+        "ReqIF.ForeignID",
+        timestamp,
+    );
+    out.push_str("          </SPEC-ATTRIBUTES>\n        </SPEC-OBJECT-TYPE>\n");
+
+    let _ = write!(
+        out,
+        r#"        <SPEC-RELATION-TYPE IDENTIFIER="{TYPE_TRACES_TO}" LAST-CHANGE="{timestamp}" LONG-NAME="Traces To"/>
+        <SPECIFICATION-TYPE IDENTIFIER="{TYPE_DOCUMENT}" LAST-CHANGE="{timestamp}" LONG-NAME="Document"/>
+      </SPEC-TYPES>
+"#
+    );
+}
+
+/// Write a string attribute definition of a SpecObject type
+fn write_string_attribute_definition(
+    out: &mut String,
+    type_id: &str,
+    suffix: &str,
+    long_name: &str,
+    timestamp: &str,
+) {
+    let _ = write!(
+        out,
+        r#"            <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="{type_id}-{suffix}" LAST-CHANGE="{timestamp}" LONG-NAME="{long_name}">
+              <TYPE><DATATYPE-DEFINITION-STRING-REF>{DATATYPE_STRING}</DATATYPE-DEFINITION-STRING-REF></TYPE>
+            </ATTRIBUTE-DEFINITION-STRING>
+"#
+    );
+}
+
+/// Write the XHTML text attribute definition of a SpecObject type
+fn write_text_attribute_definition(out: &mut String, type_id: &str, timestamp: &str) {
+    let _ = write!(
+        out,
+        r#"            <ATTRIBUTE-DEFINITION-XHTML IDENTIFIER="{type_id}-Text" LAST-CHANGE="{timestamp}" LONG-NAME="ReqIF.Text">
+              <TYPE><DATATYPE-DEFINITION-XHTML-REF>{DATATYPE_XHTML}</DATATYPE-DEFINITION-XHTML-REF></TYPE>
+            </ATTRIBUTE-DEFINITION-XHTML>
+"#
+    );
+}
+
+/// Write the SpecObject of a section with a `section_id`
+fn write_section_object(
+    out: &mut String,
+    ids: &XmlIds,
+    section_id: &str,
+    exported: &ExportedSection,
+    timestamp: &str,
+) {
+    let section = exported.section;
+    let metadata = section.metadata.as_ref();
+    let values = [
+        Some(section_id.to_string()),
+        Some(section.heading_text.clone()),
+        Some(section.section_number.to_string()),
+        metadata.and_then(|m| m.status.clone()),
+        metadata.and_then(|m| m.verification_method.clone()),
+    ];
+
+    let _ = writeln!(
+        out,
+        r#"        <SPEC-OBJECT IDENTIFIER="{id}" LAST-CHANGE="{timestamp}">
+          <TYPE><SPEC-OBJECT-TYPE-REF>{TYPE_SECTION}</SPEC-OBJECT-TYPE-REF></TYPE>
+          <VALUES>"#,
+        id = ids.get("SO-", section_id),
+    );
+    for ((suffix, _), value) in STRING_ATTRIBUTES.iter().zip(values) {
+        if let Some(value) = value {
+            write_string_value(out, TYPE_SECTION, suffix, &value);
+        }
+    }
+    write_text_value(out, TYPE_SECTION, &section.content);
+}
+
+/// Write the heading SpecObject of a section without a `section_id`
+fn write_heading_object(
+    out: &mut String,
+    ids: &XmlIds,
+    exported: &ExportedSection,
+    timestamp: &str,
+) {
+    let section = exported.section;
+    let _ = writeln!(
+        out,
+        r#"        <SPEC-OBJECT IDENTIFIER="{id}" LAST-CHANGE="{timestamp}">
+          <TYPE><SPEC-OBJECT-TYPE-REF>{TYPE_HEADING}</SPEC-OBJECT-TYPE-REF></TYPE>
+          <VALUES>"#,
+        id = ids.get("SO-", &exported.key()),
+    );
+    let values = [
+        section.heading_text.clone(),
+        section.section_number.to_string(),
+    ];
+    for ((suffix, _), value) in HEADING_ATTRIBUTES.iter().zip(values) {
+        write_string_value(out, TYPE_HEADING, suffix, &value);
+    }
+    write_text_value(out, TYPE_HEADING, &section.content);
+}
+
+/// Write the XHTML text value of a section and close its SpecObject
+fn write_text_value(out: &mut String, type_id: &str, content: &[MarkdownBlock]) {
+    let _ = write!(
+        out,
+        r#"            <ATTRIBUTE-VALUE-XHTML>
+              <DEFINITION><ATTRIBUTE-DEFINITION-XHTML-REF>{type_id}-Text</ATTRIBUTE-DEFINITION-XHTML-REF></DEFINITION>
+              <THE-VALUE><xhtml:div>{text}</xhtml:div></THE-VALUE>
+            </ATTRIBUTE-VALUE-XHTML>
+          </VALUES>
+        </SPEC-OBJECT>
+"#,
+        text = blocks_to_xhtml(content),
+    );
+}
+
+/// Write the placeholder SpecObject of a traced ID defined outside the document
+fn write_external_object(out: &mut String, ids: &XmlIds, id: &str, timestamp: &str) {
+    let _ = writeln!(
+        out,
+        r#"        <SPEC-OBJECT IDENTIFIER="{object_id}" LAST-CHANGE="{timestamp}">
+          <TYPE><SPEC-OBJECT-TYPE-REF>{TYPE_EXTERNAL}</SPEC-OBJECT-TYPE-REF></TYPE>
+          <VALUES>"#,
+        object_id = ids.get("SO-", id),
+    );
+    write_string_value(out, TYPE_EXTERNAL, "ForeignID", id);
+    out.push_str("          </VALUES>\n        </SPEC-OBJECT>\n");
+}
+
+/// Write a string attribute value
+fn write_string_value(out: &mut String, type_id: &str, suffix: &str, value: &str) {
+    let _ = writeln!(
+        out,
+        r#"            <ATTRIBUTE-VALUE-STRING THE-VALUE="{value}"><DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>{type_id}-{suffix}</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION></ATTRIBUTE-VALUE-STRING>"#,
+        value = escape_xml(value),
+    );
+}
+
+/// Write a "Traces To" SpecRelation
+fn write_relation(out: &mut String, ids: &XmlIds, source: &str, target: &str, timestamp: &str) {
+    let _ = write!(
+        out,
+        r#"        <SPEC-RELATION IDENTIFIER="SR-{source_id}--{target_id}" LAST-CHANGE="{timestamp}">
+          <TYPE><SPEC-RELATION-TYPE-REF>{TYPE_TRACES_TO}</SPEC-RELATION-TYPE-REF></TYPE>
+          <SOURCE><SPEC-OBJECT-REF>{source_ref}</SPEC-OBJECT-REF></SOURCE>
+          <TARGET><SPEC-OBJECT-REF>{target_ref}</SPEC-OBJECT-REF></TARGET>
+        </SPEC-RELATION>
+"#,
+        source_id = ids.get("", source),
+        target_id = ids.get("", target),
+        source_ref = ids.get("SO-", source),
+        target_ref = ids.get("SO-", target),
+    );
+}
+
+/// Write the Specification with the section hierarchy
+fn write_specification(
+    out: &mut String,
+    doc: &UnifiedDocument,
+    ids: &XmlIds,
+    sections: &[ExportedSection],
+    timestamp: &str,
+) {
+    let _ = writeln!(
+        out,
+        r#"      <SPECIFICATIONS>
+        <SPECIFICATION IDENTIFIER="{id}" LAST-CHANGE="{timestamp}" LONG-NAME="{title}">
+          <TYPE><SPECIFICATION-TYPE-REF>{TYPE_DOCUMENT}</SPECIFICATION-TYPE-REF></TYPE>"#,
+        id = xml_id("SP-", &doc.metadata.document_id),
+        title = escape_xml(&doc.metadata.title),
+    );
+
+    let children = hierarchy_children(sections);
+    if !children[0].is_empty() {
+        out.push_str("          <CHILDREN>\n");
+        for &child in &children[0] {
+            write_hierarchy_node(out, ids, sections, &children, child, 12, timestamp);
+        }
+        out.push_str("          </CHILDREN>\n");
+    }
+
+    out.push_str("        </SPECIFICATION>\n      </SPECIFICATIONS>\n");
+}
+
+/// Compute the children of each hierarchy node
+///
+/// Index 0 holds the top-level sections; index `i + 1` holds the children of
+/// `sections[i]`. A section's parent is the nearest preceding section whose
+/// number is a prefix of its own (sections are in section-number order).
+fn hierarchy_children(sections: &[ExportedSection]) -> Vec<Vec<usize>> {
+    let mut children = vec![Vec::new(); sections.len() + 1];
+    let mut ancestors: Vec<usize> = Vec::new();
+
+    for (index, exported) in sections.iter().enumerate() {
+        let parts = exported.section.section_number.parts();
+        while let Some(&top) = ancestors.last() {
+            let top_parts = sections[top].section.section_number.parts();
+            if top_parts.len() < parts.len() && parts.starts_with(top_parts) {
+                break;
+            }
+            ancestors.pop();
+        }
+        let parent_slot = ancestors.last().map_or(0, |&parent| parent + 1);
+        children[parent_slot].push(index);
+        ancestors.push(index);
+    }
+
+    children
+}
+
+/// Write a SpecHierarchy node and its descendants
+fn write_hierarchy_node(
+    out: &mut String,
+    ids: &XmlIds,
+    sections: &[ExportedSection],
+    children: &[Vec<usize>],
+    index: usize,
+    indent: usize,
+    timestamp: &str,
+) {
+    let pad = " ".repeat(indent);
+    let key = sections[index].key();
+    let _ = writeln!(
+        out,
+        r#"{pad}<SPEC-HIERARCHY IDENTIFIER="{id}" LAST-CHANGE="{timestamp}">
+{pad}  <OBJECT><SPEC-OBJECT-REF>{object_ref}</SPEC-OBJECT-REF></OBJECT>"#,
+        id = ids.get("SH-", &key),
+        object_ref = ids.get("SO-", &key),
+    );
+
+    let node_children = &children[index + 1];
+    if !node_children.is_empty() {
+        let _ = writeln!(out, "{pad}  <CHILDREN>");
+        for &child in node_children {
+            write_hierarchy_node(out, ids, sections, children, child, indent + 4, timestamp);
+        }
+        let _ = writeln!(out, "{pad}  </CHILDREN>");
+    }
+
+    let _ = writeln!(out, "{pad}</SPEC-HIERARCHY>");
+}
+
+/// Convert section content blocks to XHTML (ReqIF XHTML subset, `xhtml:` prefix)
+fn blocks_to_xhtml(blocks: &[MarkdownBlock]) -> String {
+    let mut out = String::new();
+    for block in blocks {
+        write_block_xhtml(&mut out, block);
+    }
+    out
+}
+
+/// Write a single block as XHTML
+fn write_block_xhtml(out: &mut String, block: &MarkdownBlock) {
+    match block {
+        MarkdownBlock::Heading { level, runs } => {
+            let level = (*level).clamp(1, 6);
+            let _ = write!(
+                out,
+                "<xhtml:h{level}>{}</xhtml:h{level}>",
+                runs_to_xhtml(runs)
+            );
+        }
+        MarkdownBlock::Paragraph(runs) => {
+            let _ = write!(out, "<xhtml:p>{}</xhtml:p>", runs_to_xhtml(runs));
+        }
+        MarkdownBlock::Image { alt_text, path, .. } => {
+            let label = if alt_text.is_empty() {
+                path.display().to_string()
+            } else {
+                alt_text.clone()
+            };
+            let _ = write!(out, "<xhtml:p>[Image: {}]</xhtml:p>", escape_xml(&label));
+        }
+        MarkdownBlock::CodeBlock { code, .. } => {
+            let _ = write!(out, "<xhtml:pre>{}</xhtml:pre>", escape_xml(code));
+        }
+        MarkdownBlock::IncludedCodeBlock { content, .. } => {
+            let code = content.as_deref().unwrap_or_default();
+            let _ = write!(out, "<xhtml:pre>{}</xhtml:pre>", escape_xml(code));
+        }
+        MarkdownBlock::BlockQuote(blocks) => {
+            let _ = write!(
+                out,
+                "<xhtml:blockquote>{}</xhtml:blockquote>",
+                blocks_to_xhtml(blocks)
+            );
+        }
+        MarkdownBlock::List { start, items } => write_list_xhtml(out, start.is_some(), items),
+        MarkdownBlock::InlineTable { headers, rows, .. } => {
+            let header_cells: Vec<String> =
+                headers.iter().map(|cell| runs_to_xhtml(cell)).collect();
+            let body: Vec<Vec<String>> = rows
+                .iter()
+                .map(|row| row.iter().map(|cell| runs_to_xhtml(cell)).collect())
+                .collect();
+            write_table_xhtml(out, &header_cells, &body);
+        }
+        MarkdownBlock::CsvTable { data, .. } => {
+            let escaped: Vec<Vec<String>> = data
+                .iter()
+                .flatten()
+                .map(|row| row.iter().map(|cell| escape_xml(cell)).collect())
+                .collect();
+            if let Some((header_cells, body)) = escaped.split_first() {
+                write_table_xhtml(out, header_cells, body);
+            }
+        }
+        MarkdownBlock::Rule => out.push_str("<xhtml:hr/>"),
+        // Raw HTML is not guaranteed to be well-formed XHTML, so keep it as text
+        MarkdownBlock::Html(html) => {
+            let _ = write!(out, "<xhtml:pre>{}</xhtml:pre>", escape_xml(html));
+        }
+    }
+}
+
+/// Write a list as XHTML
+fn write_list_xhtml(out: &mut String, ordered: bool, items: &[ListItem]) {
+    let tag = if ordered { "ol" } else { "ul" };
+    let _ = write!(out, "<xhtml:{tag}>");
+    for item in items {
+        let _ = write!(
+            out,
+            "<xhtml:li>{}</xhtml:li>",
+            blocks_to_xhtml(&item.content)
+        );
+    }
+    let _ = write!(out, "</xhtml:{tag}>");
+}
+
+/// Write a table of already-converted cell contents as XHTML
+fn write_table_xhtml(out: &mut String, headers: &[String], rows: &[Vec<String>]) {
+    out.push_str("<xhtml:table border=\"1\"><xhtml:tr>");
+    for header in headers {
+        let _ = write!(out, "<xhtml:th>{header}</xhtml:th>");
+    }
+    out.push_str("</xhtml:tr>");
+    for row in rows {
+        out.push_str("<xhtml:tr>");
+        for cell in row {
+            let _ = write!(out, "<xhtml:td>{cell}</xhtml:td>");
+        }
+        out.push_str("</xhtml:tr>");
+    }
+    out.push_str("</xhtml:table>");
+}
+
+/// Convert text runs to XHTML with formatting
+fn runs_to_xhtml(runs: &[TextRun]) -> String {
+    let mut result = String::new();
+
+    for run in runs {
+        let mut text = escape_xml(&run.text);
+
+        let formats = [
+            (run.code, "code"),
+            (run.bold, "strong"),
+            (run.italic, "em"),
+            (run.strikethrough, "del"),
+            (run.superscript, "sup"),
+            (run.subscript, "sub"),
+        ];
+        for (enabled, tag) in formats {
+            if enabled {
+                text = format!("<xhtml:{tag}>{text}</xhtml:{tag}>");
+            }
+        }
+
+        if let Some(ref url) = run.link_url {
+            text = format!("<xhtml:a href=\"{}\">{}</xhtml:a>", escape_xml(url), text);
+        }
+
+        result.push_str(&text);
+    }
+
+    result
+}
+
+/// Build a valid, deterministic XML identifier from a prefix and an ID
+///
+/// Characters not allowed in an XML ID are replaced with underscores.
+fn xml_id(prefix: &str, id: &str) -> String {
+    let sanitized: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{prefix}{sanitized}")
+}
+
+/// Unique XML identifiers for the section keys and traced IDs of a document
+///
+/// Sanitizing with [`xml_id`] can map different IDs to the same text (`SRS 1`
+/// and `SRS_1`). IDs that are already valid keep their text; the others get a
+/// `_2`, `_3`, ... suffix when their sanitized text is taken. IDs are assigned
+/// in sorted order, so the result is deterministic.
+struct XmlIds {
+    /// Unique sanitized text of each ID
+    sanitized: BTreeMap<String, String>,
+}
+
+impl XmlIds {
+    /// Assign unique identifiers to a set of IDs
+    fn new<'a>(ids: impl IntoIterator<Item = &'a str>) -> Self {
+        let ids: BTreeSet<&str> = ids.into_iter().collect();
+        let (valid, renamed): (Vec<&str>, Vec<&str>) =
+            ids.into_iter().partition(|id| xml_id("", id) == *id);
+
+        let mut taken: HashSet<String> = valid.iter().map(|id| id.to_string()).collect();
+        let mut sanitized: BTreeMap<String, String> = valid
+            .iter()
+            .map(|id| (id.to_string(), id.to_string()))
+            .collect();
+        for id in renamed {
+            let base = xml_id("", id);
+            let unique = std::iter::once(base.clone())
+                .chain((2..).map(|n| format!("{base}_{n}")))
+                .find(|candidate| !taken.contains(candidate))
+                .unwrap_or(base);
+            taken.insert(unique.clone());
+            sanitized.insert(id.to_string(), unique);
+        }
+        Self { sanitized }
+    }
+
+    /// The XML identifier of an ID with a prefix
+    fn get(&self, prefix: &str, id: &str) -> String {
+        match self.sanitized.get(id) {
+            Some(unique) => format!("{prefix}{unique}"),
+            None => xml_id(prefix, id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_model::SectionNumber;
+    use crate::unified_document::{DocumentMetadata, Person};
+    use std::path::PathBuf;
+
+    fn section(number: &str, heading: &str, sysdoc: &str) -> MarkdownSection {
+        MarkdownSection {
+            heading_level: 1,
+            heading_text: heading.to_string(),
+            section_number: SectionNumber::parse(number).unwrap(),
+            line_number: 1,
+            source_file: PathBuf::from("src/test.md"),
+            content: vec![MarkdownBlock::Paragraph(vec![TextRun::new(
+                "Shall be <fast> & small".to_string(),
+            )])],
+            metadata: Some(toml::from_str(sysdoc).unwrap()),
+        }
+    }
+
+    fn test_document() -> UnifiedDocument {
+        let person = Person {
+            name: "Owner".to_string(),
+            email: "owner@example.com".to_string(),
+        };
+        let metadata = DocumentMetadata {
+            system_id: None,
+            document_id: "SDD 001".to_string(),
+            title: "Design".to_string(),
+            subtitle: None,
+            description: None,
+            doc_type: "SDD".to_string(),
+            standard: "DI-IPSC-81435B".to_string(),
+            template: "sdd-standard-v1".to_string(),
+            owner: person.clone(),
+            approver: person,
+            version: None,
+            modified: Some("2026-01-02T03:04:05+00:00".to_string()),
+            revision_history: Vec::new(),
+            protection_mark: None,
+            title_page_background: None,
+            heading_color: "#2B579A".to_string(),
+        };
+        let mut doc = UnifiedDocument::new(metadata, PathBuf::from("/test"));
+        doc.sections = vec![
+            section("03", "Design", "status = \"Draft\""),
+            section("03.01", "Lexer", r#"section_id = "SDD-001""#),
+            section(
+                "03.02",
+                "Parser",
+                r#"section_id = "SDD-002"
+traced_ids = ["SRS-001", "SDD-001"]
+status = "Approved""#,
+            ),
+            section("04", "Notes", "status = \"Draft\""),
+        ];
+        doc
+    }
+
+    #[test]
+    fn test_reqif_objects_relations_and_hierarchy() {
+        let xml = generate_reqif(&test_document());
+
+        assert!(xml.contains(
+            r#"<SPEC-OBJECT IDENTIFIER="SO-SDD-002" LAST-CHANGE="2026-01-02T03:04:05+00:00">"#
+        ));
+        assert!(xml.contains(r#"THE-VALUE="Approved""#));
+        assert!(xml.contains("<xhtml:p>Shall be &lt;fast&gt; &amp; small</xhtml:p>"));
+        // Sections without a section_id are exported as headings
+        assert!(xml.contains(r#"<SPEC-OBJECT IDENTIFIER="SO-H-4" LAST-CHANGE"#));
+        assert!(xml.contains(r#"THE-VALUE="Notes""#));
+        // External traced IDs become placeholder objects
+        assert!(xml.contains(r#"<SPEC-OBJECT IDENTIFIER="SO-SRS-001""#));
+        assert!(xml.contains("<SPEC-RELATION IDENTIFIER=\"SR-SDD-002--SRS-001\""));
+        assert!(xml.contains("<SPEC-RELATION IDENTIFIER=\"SR-SDD-002--SDD-001\""));
+        // Identifiers are sanitized
+        assert!(xml.contains(r#"<SPECIFICATION IDENTIFIER="SP-SDD_001""#));
+
+        // SDD-001 (3.1) and SDD-002 (3.2) are nested under the chapter heading (3)
+        let chapter = xml
+            .find("            <SPEC-HIERARCHY IDENTIFIER=\"SH-H-3\"")
+            .unwrap();
+        let first = xml
+            .find("                <SPEC-HIERARCHY IDENTIFIER=\"SH-SDD-001\"")
+            .unwrap();
+        let second = xml
+            .find("                <SPEC-HIERARCHY IDENTIFIER=\"SH-SDD-002\"")
+            .unwrap();
+        let notes = xml
+            .find("            <SPEC-HIERARCHY IDENTIFIER=\"SH-H-4\"")
+            .unwrap();
+        assert!(chapter < first && first < second && second < notes);
+        assert_eq!(xml.matches("<CHILDREN>").count(), 2);
+    }
+
+    #[test]
+    fn test_xml_ids_are_unique() {
+        let ids = XmlIds::new(["SRS 1", "SRS_1", "SRS/1", "SRS_1_2"]);
+        assert_eq!(ids.get("SO-", "SRS_1"), "SO-SRS_1");
+        assert_eq!(ids.get("SO-", "SRS_1_2"), "SO-SRS_1_2");
+        assert_eq!(ids.get("SO-", "SRS 1"), "SO-SRS_1_3");
+        assert_eq!(ids.get("SO-", "SRS/1"), "SO-SRS_1_4");
+    }
+
+    #[test]
+    fn test_reqif_round_trip_keeps_chapters() {
+        let xml = generate_reqif(&test_document());
+        let reqif = crate::source_model::ReqifDocument::parse(&xml).unwrap();
+        // The chapters (Design, Notes) are headings, not requirements
+        assert_eq!(reqif.objects().count(), 5);
+        assert_eq!(reqif.requirements().count(), 3);
+        let files = crate::reqif_importer::generate_files(&reqif);
+
+        let paths: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("src/01_design.md"),
+                PathBuf::from("src/02_notes.md")
+            ]
+        );
+        let design = &files[0].content;
+        assert!(design.starts_with("# Design\n\nShall be"));
+        assert!(design.contains("## Lexer\n\n```sysdoc\nsection_id = \"SDD-001\"\n```"));
+        assert!(design.contains("## Parser\n\n```sysdoc\nsection_id = \"SDD-002\""));
+        assert_eq!(design.matches("section_id").count(), 2);
+    }
+
+    #[test]
+    fn test_reqif_output_is_deterministic() {
+        let doc = test_document();
+        assert_eq!(generate_reqif(&doc), generate_reqif(&doc));
+    }
+}
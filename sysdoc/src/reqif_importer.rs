@@ -0,0 +1,458 @@
+//! ReqIF importer
+//!
+//! Seeds a sysdoc source tree from a ReqIF file, or registers the file as a
+//! trace catalog of an existing document. In tree mode each top-level node of
+//! the spec hierarchy becomes a numbered markdown file; nested nodes become
+//! headings within that file, each carrying a sysdoc block with its `section_id`.
+
+use crate::paths::{relative_path, SOURCE_DIR};
+use crate::source_model::{ReqifDocument, ReqifError, ReqifNode};
+use crate::text::slug;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Deepest heading level generated; deeper hierarchy nodes are flattened to it
+const MAX_HEADING_LEVEL: usize = 6;
+
+/// Attribute names copied into the `status` metadata field
+const STATUS_ATTRIBUTES: [&str; 2] = ["Status", "ReqIF.Status"];
+
+/// Attribute names copied into the `verification_method` metadata field
+const VERIFICATION_ATTRIBUTES: [&str; 2] = ["VerificationMethod", "Verification Method"];
+
+/// Errors that can occur during ReqIF import
+#[derive(Error, Debug)]
+pub enum ReqifImportError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("ReqIF error: {0}")]
+    Reqif(#[from] ReqifError),
+
+    #[error("File already exists: {path} (use --force to overwrite)", path = .0.display())]
+    FileExists(PathBuf),
+
+    #[error("No sysdoc.toml found in {path}; catalogs can only be registered with an existing document", path = .0.display())]
+    MissingConfig(PathBuf),
+}
+
+/// A markdown file generated from a ReqIF file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedFile {
+    /// Path relative to the document root (e.g., "src/01_introduction.md")
+    pub path: PathBuf,
+    /// Markdown content
+    pub content: String,
+}
+
+/// Read and parse a ReqIF file
+pub fn read_reqif(path: &Path) -> Result<ReqifDocument, ReqifImportError> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(ReqifDocument::parse(&content)?)
+}
+
+/// Generate the markdown files for a ReqIF document
+///
+/// Top-level hierarchy nodes of all specifications are numbered consecutively.
+/// Spec objects that no specification references are appended as extra files,
+/// so a file without hierarchies still produces one file per object. Objects
+/// that only appear as relation targets are treated as external references
+/// and get no file.
+///
+/// # Parameters
+/// * `reqif` - The parsed ReqIF document
+///
+/// # Returns
+/// * Files to write, in section order
+pub fn generate_files(reqif: &ReqifDocument) -> Vec<GeneratedFile> {
+    let mut top_level: Vec<&ReqifNode> = reqif
+        .specifications
+        .iter()
+        .flat_map(|spec| spec.children.iter())
+        .collect();
+
+    let mut referenced = std::collections::HashSet::new();
+    collect_referenced(top_level.iter().copied(), &mut referenced);
+    referenced.extend(reqif.relations.iter().map(|(_, target)| target.as_str()));
+    let unplaced: Vec<ReqifNode> = reqif
+        .object_order
+        .iter()
+        .filter(|identifier| !referenced.contains(identifier.as_str()))
+        .map(|identifier| ReqifNode {
+            object: identifier.clone(),
+            children: Vec::new(),
+        })
+        .collect();
+    top_level.extend(unplaced.iter());
+
+    top_level
+        .into_iter()
+        .enumerate()
+        .map(|(index, node)| {
+            let title = node_title(reqif, node);
+            let mut content = String::new();
+            write_node(reqif, node, 1, &mut content);
+            GeneratedFile {
+                path: PathBuf::from(SOURCE_DIR).join(format!(
+                    "{:02}_{}.md",
+                    index + 1,
+                    slug(&title)
+                )),
+                content,
+            }
+        })
+        .collect()
+}
+
+/// Write the generated source tree into a document directory
+///
+/// A placeholder sysdoc.toml is created when the directory does not have one.
+///
+/// # Parameters
+/// * `reqif` - The parsed ReqIF document
+/// * `root` - Document root directory (created if missing)
+/// * `force` - Overwrite existing markdown files
+///
+/// # Returns
+/// * `Ok(usize)` - Number of markdown files written
+/// * `Err(ReqifImportError)` - A file exists (without `force`) or could not be written
+pub fn write_tree(
+    reqif: &ReqifDocument,
+    root: &Path,
+    force: bool,
+) -> Result<usize, ReqifImportError> {
+    let files = generate_files(reqif);
+
+    if !force {
+        if let Some(existing) = files
+            .iter()
+            .map(|f| root.join(&f.path))
+            .find(|p| p.exists())
+        {
+            return Err(ReqifImportError::FileExists(existing));
+        }
+    }
+
+    std::fs::create_dir_all(root.join(SOURCE_DIR))?;
+    for file in &files {
+        std::fs::write(root.join(&file.path), &file.content)?;
+    }
+
+    let config_path = root.join("sysdoc.toml");
+    if !config_path.exists() {
+        std::fs::write(&config_path, placeholder_config(reqif))?;
+    }
+
+    Ok(files.len())
+}
+
+/// Register a ReqIF file as a trace catalog in a document's sysdoc.toml
+///
+/// The `[[catalogs]]` entry is appended to the existing file so comments and
+/// formatting are preserved. The path is stored relative to the document root
+/// when the file lives inside it.
+///
+/// # Parameters
+/// * `root` - Document root directory containing sysdoc.toml
+/// * `file` - The ReqIF file to register
+///
+/// # Returns
+/// * `Ok(true)` - The catalog was added
+/// * `Ok(false)` - The file was already registered
+/// * `Err(ReqifImportError)` - The file does not parse or sysdoc.toml is missing
+pub fn register_catalog(root: &Path, file: &Path) -> Result<bool, ReqifImportError> {
+    read_reqif(file)?;
+
+    let config_path = root.join("sysdoc.toml");
+    if !config_path.exists() {
+        return Err(ReqifImportError::MissingConfig(root.to_path_buf()));
+    }
+
+    let catalog_path = catalog_path(root, file);
+    let mut config = std::fs::read_to_string(&config_path)?;
+    let already_registered = toml::from_str::<crate::document_config::DocumentConfig>(&config)
+        .map(|c| {
+            c.catalogs
+                .iter()
+                .any(|catalog| catalog.path == catalog_path)
+        })
+        .unwrap_or(false);
+    if already_registered {
+        return Ok(false);
+    }
+
+    if !config.is_empty() && !config.ends_with('\n') {
+        config.push('\n');
+    }
+    config.push_str(&format!(
+        "\n[[catalogs]]\npath = {}\n",
+        toml::Value::String(catalog_path)
+    ));
+    std::fs::write(&config_path, config)?;
+    Ok(true)
+}
+
+/// Path under which a catalog file is recorded in sysdoc.toml
+///
+/// The path is relative to the document root (e.g., `../customer.reqif` for a
+/// file beside it), so that the project can be moved or checked out elsewhere.
+/// Only a file on another drive, which no relative path reaches, is recorded
+/// absolute.
+fn catalog_path(root: &Path, file: &Path) -> String {
+    let absolute = |path: &Path| {
+        path.canonicalize()
+            .or_else(|_| std::path::absolute(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+    let (root, file) = (absolute(root), absolute(file));
+    if root.components().next() == file.components().next() {
+        relative_path(&root, &file)
+    } else {
+        file.to_string_lossy().replace('\\', "/")
+    }
+}
+
+/// Collect the identifiers of all objects referenced by a hierarchy
+fn collect_referenced<'a>(
+    nodes: impl Iterator<Item = &'a ReqifNode>,
+    referenced: &mut std::collections::HashSet<&'a str>,
+) {
+    for node in nodes {
+        referenced.insert(node.object.as_str());
+        collect_referenced(node.children.iter(), referenced);
+    }
+}
+
+/// Title used for a hierarchy node's heading
+fn node_title(reqif: &ReqifDocument, node: &ReqifNode) -> String {
+    reqif
+        .objects
+        .get(&node.object)
+        .map(|object| {
+            if object.title.is_empty() {
+                object.id.clone()
+            } else {
+                object.title.clone()
+            }
+        })
+        .unwrap_or_else(|| node.object.clone())
+}
+
+/// Append a node (heading, sysdoc block and text) and its children as markdown
+fn write_node(reqif: &ReqifDocument, node: &ReqifNode, level: usize, out: &mut String) {
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out.push_str(&format!(
+        "{} {}\n",
+        "#".repeat(level.min(MAX_HEADING_LEVEL)),
+        node_title(reqif, node)
+    ));
+
+    if let Some(object) = reqif.objects.get(&node.object) {
+        // Chapter headings exported without a section_id get none back
+        let mut sysdoc = String::new();
+        if !object.is_heading() {
+            sysdoc.push_str(&format!(
+                "section_id = {}\n",
+                toml::Value::String(object.id.clone())
+            ));
+        }
+        let traced_ids = reqif.related_ids(&object.identifier);
+        if !traced_ids.is_empty() {
+            let traced_ids =
+                toml::Value::Array(traced_ids.into_iter().map(toml::Value::String).collect());
+            sysdoc.push_str(&format!("traced_ids = {traced_ids}\n"));
+        }
+        let attribute = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| object.attributes.get(*name).filter(|v| !v.is_empty()))
+        };
+        if let Some(status) = attribute(&STATUS_ATTRIBUTES) {
+            sysdoc.push_str(&format!(
+                "status = {}\n",
+                toml::Value::String(status.clone())
+            ));
+        }
+        if let Some(method) = attribute(&VERIFICATION_ATTRIBUTES) {
+            sysdoc.push_str(&format!(
+                "verification_method = {}\n",
+                toml::Value::String(method.clone())
+            ));
+        }
+        if !sysdoc.is_empty() {
+            out.push_str(&format!("\n```sysdoc\n{sysdoc}```\n"));
+        }
+
+        if !object.text.is_empty() {
+            out.push('\n');
+            out.push_str(&object.text);
+            out.push('\n');
+        }
+    }
+
+    for child in &node.children {
+        write_node(reqif, child, level + 1, out);
+    }
+}
+
+/// sysdoc.toml written alongside an imported tree that has none
+fn placeholder_config(reqif: &ReqifDocument) -> String {
+    let title = if reqif.title.is_empty() {
+        "Imported Requirements".to_string()
+    } else {
+        reqif.title.clone()
+    };
+    format!(
+        r#"# sysdoc.toml - generated by `sysdoc import reqif`; review the placeholder fields
+
+document_id = "DOC-001"
+document_title = {}
+document_type = "SRS"
+document_standard = ""
+
+[document_owner]
+name = "Document Owner"
+email = "owner@example.com"
+
+[document_approver]
+name = "Document Approver"
+email = "approver@example.com"
+"#,
+        toml::Value::String(title)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    /// Build a string attribute value for the sample ReqIF
+    fn value(definition: &str, value: &str) -> String {
+        format!(
+            "<ATTRIBUTE-VALUE-STRING THE-VALUE=\"{value}\"><DEFINITION>\
+             <ATTRIBUTE-DEFINITION-STRING-REF>{definition}</ATTRIBUTE-DEFINITION-STRING-REF>\
+             </DEFINITION></ATTRIBUTE-VALUE-STRING>"
+        )
+    }
+
+    fn sample() -> ReqifDocument {
+        let object = |identifier: &str, values: &[String]| {
+            format!(
+                "<SPEC-OBJECT IDENTIFIER=\"{identifier}\"><VALUES>{}</VALUES></SPEC-OBJECT>",
+                values.concat()
+            )
+        };
+        let objects = [
+            object(
+                "o1",
+                &[
+                    value("id", "SRS-1"),
+                    value("name", "Power Supply"),
+                    value("text", "Power text."),
+                    value("status", "Approved"),
+                ],
+            ),
+            object(
+                "o2",
+                &[
+                    value("id", "SRS-1.1"),
+                    value("name", "Voltage"),
+                    value("text", "The unit shall accept 28V."),
+                    value("status", "Approved"),
+                ],
+            ),
+            object("o3", &[value("id", "SRS-9")]),
+            object("o4", &[value("id", "SYS-4")]),
+        ];
+        let xml = format!(
+            r#"<REQ-IF><THE-HEADER><REQ-IF-HEADER><TITLE>Customer SRS</TITLE></REQ-IF-HEADER></THE-HEADER>
+            <CORE-CONTENT><REQ-IF-CONTENT>
+              <SPEC-TYPES><SPEC-OBJECT-TYPE><SPEC-ATTRIBUTES>
+                <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="id" LONG-NAME="ReqIF.ForeignID"/>
+                <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="name" LONG-NAME="ReqIF.ChapterName"/>
+                <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="text" LONG-NAME="ReqIF.Text"/>
+                <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="status" LONG-NAME="Status"/>
+              </SPEC-ATTRIBUTES></SPEC-OBJECT-TYPE></SPEC-TYPES>
+              <SPEC-OBJECTS>{}</SPEC-OBJECTS>
+              <SPECIFICATIONS><SPECIFICATION LONG-NAME="Requirements"><CHILDREN>
+                <SPEC-HIERARCHY><OBJECT><SPEC-OBJECT-REF>o1</SPEC-OBJECT-REF></OBJECT><CHILDREN>
+                  <SPEC-HIERARCHY><OBJECT><SPEC-OBJECT-REF>o2</SPEC-OBJECT-REF></OBJECT></SPEC-HIERARCHY>
+                </CHILDREN></SPEC-HIERARCHY>
+              </CHILDREN></SPECIFICATION></SPECIFICATIONS>
+              <SPEC-RELATIONS><SPEC-RELATION>
+                <SOURCE><SPEC-OBJECT-REF>o2</SPEC-OBJECT-REF></SOURCE>
+                <TARGET><SPEC-OBJECT-REF>o4</SPEC-OBJECT-REF></TARGET>
+              </SPEC-RELATION></SPEC-RELATIONS>
+            </REQ-IF-CONTENT></CORE-CONTENT></REQ-IF>"#,
+            objects.concat()
+        );
+        ReqifDocument::parse(&xml).unwrap()
+    }
+
+    #[test]
+    fn test_generate_files() {
+        let files = generate_files(&sample());
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, PathBuf::from("src/01_power-supply.md"));
+        assert_eq!(
+            files[0].content,
+            "# Power Supply\n\n```sysdoc\nsection_id = \"SRS-1\"\nstatus = \"Approved\"\n```\n\nPower text.\n\n\
+             ## Voltage\n\n```sysdoc\nsection_id = \"SRS-1.1\"\ntraced_ids = [\"SYS-4\"]\nstatus = \"Approved\"\n```\n\nThe unit shall accept 28V.\n"
+        );
+        // Objects outside any hierarchy still get a file, titled by their ID,
+        // unless they are only relation targets (SYS-4)
+        assert_eq!(files[1].path, PathBuf::from("src/02_srs-9.md"));
+        assert!(files[1].content.starts_with("# SRS-9\n"));
+    }
+
+    #[test]
+    fn test_write_tree_and_register_catalog() {
+        let dir = std::env::temp_dir().join(format!("sysdoc-reqif-import-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(write_tree(&sample(), &dir, false).unwrap(), 2);
+        let config = std::fs::read_to_string(dir.join("sysdoc.toml")).unwrap();
+        assert!(config.contains("document_title = \"Customer SRS\""));
+        assert!(matches!(
+            write_tree(&sample(), &dir, false),
+            Err(ReqifImportError::FileExists(_))
+        ));
+        assert_eq!(write_tree(&sample(), &dir, true).unwrap(), 2);
+
+        let reqif_path = dir.join("customer.reqif");
+        std::fs::write(
+            &reqif_path,
+            "<REQ-IF><CORE-CONTENT><REQ-IF-CONTENT/></CORE-CONTENT></REQ-IF>",
+        )
+        .unwrap();
+        assert!(register_catalog(&dir, &reqif_path).unwrap());
+        assert!(!register_catalog(&dir, &reqif_path).unwrap());
+        let config = std::fs::read_to_string(dir.join("sysdoc.toml")).unwrap();
+        assert!(config.ends_with("\n[[catalogs]]\npath = \"customer.reqif\"\n"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_register_catalog_outside_root_is_relative() {
+        let dir = std::env::temp_dir().join(format!("sysdoc-reqif-outside-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let root = dir.join("doc");
+        write_tree(&sample(), &root, false).unwrap();
+        let reqif_path = dir.join("baselines").join("customer.reqif");
+        std::fs::create_dir_all(reqif_path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &reqif_path,
+            "<REQ-IF><CORE-CONTENT><REQ-IF-CONTENT/></CORE-CONTENT></REQ-IF>",
+        )
+        .unwrap();
+
+        assert!(register_catalog(&root, &reqif_path).unwrap());
+        let config = std::fs::read_to_string(root.join("sysdoc.toml")).unwrap();
+        assert!(config.ends_with("\n[[catalogs]]\npath = \"../baselines/customer.reqif\"\n"));
+        assert!(!register_catalog(&root, &reqif_path).unwrap());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -3,31 +3,73 @@
 //! This module defines the structures used during Stage 1 (Parsing)
 //! where markdown files, images, and CSV files are loaded and validated.
 
+use crate::diagnostics::Diagnostic;
 use crate::document_config::DocumentConfig;
+use crate::template_config::TemplateConfig;
 use std::path::{Path, PathBuf};
 
 // Submodules
+mod assets;
+mod attributes;
+mod baseline;
 mod blocks;
+mod catalog;
+mod code_trace;
+mod coverage;
 mod error;
+mod id_scheme;
 mod image;
+mod impact;
+mod lint;
 mod markdown_source;
+mod open_items;
+mod outline;
 mod parser;
+mod query;
+mod reqif;
 mod section_metadata;
 mod section_number;
+mod spelling;
+mod suspect;
 mod table;
+mod template_conformance;
+mod test_results;
 mod text_run;
+mod trace_graph;
+mod traceability;
 mod types;
+mod upstream;
 mod validation;
 
 // Re-export public types
+pub use assets::{remove_assets, AssetError, OrphanedAsset};
+pub use attributes::AttributeValue;
+pub use baseline::{Baseline, BaselineError, BaselineItem};
 pub use blocks::{ListItem, MarkdownBlock};
+pub use catalog::{CatalogError, TraceCatalog};
+pub use code_trace::{scan_code_references, CodeReference, CodeTraceError};
+pub use coverage::{CoverageOptions, CoverageReport};
 pub use error::SourceModelError;
+pub use id_scheme::{
+    IdAssignment, IdRegistry, IdRegistryError, IdScheme, IdSchemeError, ID_REGISTRY_FILE,
+};
 pub use image::ImageFormat;
+pub use impact::{changed_section_ids, TraceNetwork};
+pub use lint::{lint, LINT_RULES};
 pub use markdown_source::{MarkdownSection, MarkdownSource};
-pub use section_number::SectionNumber;
+pub use open_items::OpenItem;
+pub use outline::{Outline, OutlineEntry, OutlineError, UnlistedFile, SUMMARY_FILE};
+pub use reqif::{ReqifDocument, ReqifError, ReqifNode, HEADING_TYPE};
+pub use section_number::{SectionNumber, MAX_SECTION_DEPTH};
+pub use spelling::{add_words, check_spelling, Dictionary, SpellingError};
+pub use suspect::{SuspectLink, SuspectReason, TraceLock, TraceLockError, TRACE_LOCK_FILE};
 pub use table::TableSource;
+pub use test_results::{TestCaseResult, TestResults, TestResultsError};
 pub use text_run::TextRun;
-pub use types::Alignment;
+pub use trace_graph::{EmbeddedTraceGraph, TraceGraph, TraceGraphSpec, TRACE_GRAPH_DIR};
+pub use traceability::{SectionTrace, TraceTarget, TraceTargets};
+pub use types::{Alignment, SourceSpan};
+pub use upstream::UpstreamDocument;
 pub use validation::ValidationError;
 
 /// Collection of all source files discovered and parsed
@@ -42,6 +84,39 @@ pub struct SourceModel {
     /// All markdown source files, ordered by discovery (not sorted yet)
     /// CSV tables are embedded as CsvTable blocks within the markdown sections
     pub markdown_files: Vec<MarkdownSource>,
+
+    /// Upstream requirement catalogs declared in sysdoc.toml
+    pub catalog: TraceCatalog,
+
+    /// Upstream sysdoc projects declared in sysdoc.toml
+    pub upstreams: Vec<UpstreamDocument>,
+
+    /// Reviewed trace fingerprints from trace.lock (None if the document has no lock file)
+    pub trace_lock: Option<TraceLock>,
+
+    /// Section IDs referenced from source code annotations (`[code_trace]`)
+    pub code_references: Vec<CodeReference>,
+
+    /// Results from the test result files declared in sysdoc.toml
+    pub test_results: TestResults,
+
+    /// Section ID scheme for this document's type (`[id_schemes]` in sysdoc.toml)
+    pub id_scheme: Option<IdScheme>,
+
+    /// Issued and retired section IDs from id-registry.toml (None if the document has no registry)
+    pub id_registry: Option<IdRegistry>,
+
+    /// Baselines referenced by `changes_since_baseline`, keyed by name
+    pub baselines: std::collections::BTreeMap<String, Baseline>,
+
+    /// TBD/TBR items found in the document content (see [`Self::generate_open_items_tables`])
+    pub open_items: Vec<OpenItem>,
+
+    /// Markdown files under `src/` that are neither numbered nor listed in the outline
+    pub unlisted_files: Vec<UnlistedFile>,
+
+    /// Trace graphs embedded by `[trace_graph]`, not yet written (see [`Self::write_trace_graphs`])
+    pub trace_graphs: Vec<EmbeddedTraceGraph>,
 }
 
 impl SourceModel {
@@ -58,20 +133,35 @@ impl SourceModel {
             root,
             config,
             markdown_files: Vec::new(),
+            catalog: TraceCatalog::default(),
+            upstreams: Vec::new(),
+            trace_lock: None,
+            code_references: Vec::new(),
+            test_results: TestResults::default(),
+            id_scheme: None,
+            id_registry: None,
+            baselines: std::collections::BTreeMap::new(),
+            open_items: Vec::new(),
+            unlisted_files: Vec::new(),
+            trace_graphs: Vec::new(),
         }
     }
 
     /// Validate that all referenced resources exist
     ///
     /// # Returns
-    /// * `Ok(())` - All referenced images, tables, include files, and internal links are valid, and all section_ids are unique
-    /// * `Err(ValidationError)` - One or more referenced resources are missing, links are broken, or duplicate section_ids found
+    /// * `Ok(())` - All referenced images, tables, include files, internal links, and traced IDs are valid, and all section_ids are unique
+    /// * `Err(ValidationError)` - One or more referenced resources are missing, links are broken, traced IDs are unknown, or duplicate section_ids found
     pub fn validate(&self) -> Result<(), ValidationError> {
         let image_errors = self.validate_image_references();
         let table_errors = self.validate_table_references();
         let include_errors = self.validate_include_references();
         let link_errors = self.validate_internal_links();
         let section_id_errors = self.validate_unique_section_ids();
+        let traced_id_errors = self.validate_traced_ids();
+        let code_reference_errors = self.validate_code_references();
+        let id_scheme_errors = self.validate_section_id_scheme();
+        let attribute_errors = self.validate_section_attributes();
 
         let errors: Vec<ValidationError> = image_errors
             .into_iter()
@@ -79,6 +169,76 @@ impl SourceModel {
             .chain(include_errors)
             .chain(link_errors)
             .chain(section_id_errors)
+            .chain(traced_id_errors)
+            .chain(code_reference_errors)
+            .chain(id_scheme_errors)
+            .chain(attribute_errors)
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError::Multiple(errors))
+        }
+    }
+
+    /// Load the spell checking dictionary for this document
+    ///
+    /// # Returns
+    /// * `Ok(Dictionary)` - The bundled word list for the `[spelling]` language, the
+    ///   project dictionary and the glossary terms
+    /// * `Err(SpellingError)` - The project dictionary cannot be read
+    pub fn spelling_dictionary(&self) -> Result<Dictionary, SpellingError> {
+        let config = self.config.spelling.clone().unwrap_or_default();
+        Dictionary::load(&self.root, &config, &self.markdown_files)
+    }
+
+    /// Find the files under `src/` that nothing in the document refers to
+    ///
+    /// # Returns
+    /// * `Ok(Vec<OrphanedAsset>)` - Assets not referenced by any block, link or
+    ///   sysdoc.toml setting and not matched by `[assets] ignore`, sorted by path
+    /// * `Err(AssetError)` - An `[assets] ignore` pattern is invalid
+    pub fn orphaned_assets(&self) -> Result<Vec<OrphanedAsset>, AssetError> {
+        assets::find_orphaned_assets(&self.root, &self.config, &self.markdown_files)
+    }
+
+    /// Check the document against its DID template
+    ///
+    /// # Parameters
+    /// * `template` - The template named by `document_template` in sysdoc.toml
+    ///
+    /// # Returns
+    /// * `Ok(())` - Every templated section is present, written, and has its required metadata
+    /// * `Err(ValidationError)` - Sections are missing, renamed, renumbered, still hold only
+    ///   guidance, or lack required metadata
+    pub fn validate_template(&self, template: &TemplateConfig) -> Result<(), ValidationError> {
+        let errors =
+            template_conformance::check_template_conformance(&self.markdown_files, template);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError::Multiple(errors))
+        }
+    }
+
+    /// Check that the document is ready for release
+    ///
+    /// Run after [`Self::generate_open_items_tables`], which collects the open items.
+    ///
+    /// # Returns
+    /// * `Ok(())` - No TBD/TBR items remain
+    /// * `Err(ValidationError)` - One error per remaining TBD/TBR item
+    pub fn validate_release(&self) -> Result<(), ValidationError> {
+        let errors: Vec<ValidationError> = self
+            .open_items
+            .iter()
+            .map(|item| ValidationError::OpenItem {
+                item: item.marker.clone(),
+                referenced_in: item.source_file.clone(),
+                line: item.line,
+            })
             .collect();
 
         if errors.is_empty() {
@@ -88,6 +248,30 @@ impl SourceModel {
         }
     }
 
+    /// Collect the TBD/TBR items and generate the "TBD/TBR List" tables
+    ///
+    /// This must run before any other tables are generated so that generated
+    /// content (including the open items tables themselves) is not scanned.
+    pub fn generate_open_items_tables(&mut self) {
+        self.open_items = open_items::collect_open_items(&self.markdown_files);
+        let items = &self.open_items;
+        for section in self
+            .markdown_files
+            .iter_mut()
+            .flat_map(|md_file| md_file.sections.iter_mut())
+        {
+            if section
+                .metadata
+                .as_ref()
+                .is_some_and(|m| m.generate_tbd_table)
+            {
+                section
+                    .content
+                    .push(open_items::build_open_items_table(items));
+            }
+        }
+    }
+
     /// Generate traceability tables across all files
     ///
     /// This method should be called after validation and after all files are parsed.
@@ -95,24 +279,42 @@ impl SourceModel {
     /// the requested traceability tables.
     pub fn generate_traceability_tables(&mut self) {
         // Collect all traceability data from all sections across all files
-        let section_to_traced = self.collect_all_section_traceability();
+        let section_traces = self.collect_all_section_traceability();
 
         // Build reverse mapping: traced_id -> [section_ids]
-        let traced_to_sections = self.build_reverse_traceability(&section_to_traced);
+        let traced_to_sections = self.build_reverse_traceability(&section_traces);
+
+        // Details of known IDs, used by trace tables that show traced titles and text
+        let targets = self.collect_trace_targets(&section_traces);
+
+        // Results of test case sections, used by test result and verification tables
+        let test_cases = self.collect_test_case_results();
 
         // Generate tables for sections that request them
         for md_file in self.markdown_files.iter_mut() {
             for section in md_file.sections.iter_mut() {
-                Self::generate_tables_for_section(section, &section_to_traced, &traced_to_sections);
+                Self::generate_tables_for_section(
+                    section,
+                    &section_traces,
+                    &traced_to_sections,
+                    &targets,
+                    &test_cases,
+                );
             }
         }
+
+        self.generate_trace_graphs(&section_traces, &targets);
+        self.generate_baseline_change_tables();
+        self.generate_implemented_by_tables();
     }
 
     /// Generate traceability tables for a single section if requested
     fn generate_tables_for_section(
         section: &mut MarkdownSection,
-        section_to_traced: &[(String, Vec<String>)],
+        section_traces: &[SectionTrace],
         traced_to_sections: &std::collections::BTreeMap<String, Vec<String>>,
+        targets: &TraceTargets,
+        test_cases: &[TestCaseResult],
     ) {
         let Some(ref metadata) = section.metadata else {
             return;
@@ -123,7 +325,7 @@ impl SourceModel {
             .generate_section_id_to_traced_ids_table
             .get_headers()
         {
-            let table = Self::create_section_to_traced_table(section_to_traced, &col1, &col2);
+            let table = Self::create_section_to_traced_table(section_traces, &col1, &col2);
             section.content.push(table);
         }
 
@@ -135,11 +337,294 @@ impl SourceModel {
             let table = Self::create_traced_to_sections_table(traced_to_sections, &col1, &col2);
             section.content.push(table);
         }
+
+        // Generate configurable multi-column trace tables
+        let tables: Vec<MarkdownBlock> = metadata
+            .trace_tables
+            .iter()
+            .map(|spec| spec.build_table(section_traces, targets))
+            .collect();
+        section.content.extend(tables);
+
+        if metadata.generate_test_results_table {
+            let table = test_results::build_test_results_table(test_cases);
+            section.content.push(table);
+        }
+
+        if metadata.generate_verification_status_table {
+            let table = test_results::build_verification_status_table(test_cases);
+            section.content.push(table);
+        }
+    }
+
+    /// Resolve the result of every test case section
+    ///
+    /// A test case section is a section with a `section_id` that either lists
+    /// `test_cases` or whose `section_id` names a test in the results files.
+    ///
+    /// # Returns
+    /// * One result per test case section, sorted by `section_id`
+    pub fn collect_test_case_results(&self) -> Vec<TestCaseResult> {
+        let mut cases: Vec<TestCaseResult> = self
+            .markdown_files
+            .iter()
+            .flat_map(|md_file| md_file.sections.iter())
+            .filter_map(|section| {
+                let metadata = section.metadata.as_ref()?;
+                let section_id = metadata.section_id.clone()?;
+                let tests = match &metadata.test_cases {
+                    Some(tests) => tests.clone(),
+                    None if self.test_results.get(&section_id).is_some() => {
+                        vec![section_id.clone()]
+                    }
+                    None => return None,
+                };
+                Some(TestCaseResult {
+                    status: self
+                        .test_results
+                        .status_of(tests.iter().map(String::as_str)),
+                    section_id,
+                    heading: section.heading_text.clone(),
+                    tests,
+                    traced_ids: metadata.traced_ids.clone().unwrap_or_default(),
+                })
+            })
+            .collect();
+        cases.sort_by(|a, b| a.section_id.cmp(&b.section_id));
+        cases
+    }
+
+    /// Generate the `[[query_table]]` tables requested by sections
+    ///
+    /// Like [`Self::generate_traceability_tables`], this must run after all files
+    /// are parsed: each query is evaluated over every section of the document.
+    pub fn generate_query_tables(&mut self) {
+        let sections: Vec<&MarkdownSection> = self
+            .markdown_files
+            .iter()
+            .flat_map(|md_file| md_file.sections.iter())
+            .collect();
+        let tables: Vec<Vec<MarkdownBlock>> = sections
+            .iter()
+            .map(|section| {
+                section
+                    .metadata
+                    .iter()
+                    .flat_map(|metadata| metadata.query_tables.iter())
+                    .map(|spec| spec.build_table(&sections))
+                    .collect()
+            })
+            .collect();
+
+        let sections = self
+            .markdown_files
+            .iter_mut()
+            .flat_map(|md_file| md_file.sections.iter_mut());
+        for (section, tables) in sections.zip(tables) {
+            section.content.extend(tables);
+        }
+    }
+
+    /// Append a "Changes since baseline" table to each section requesting one
+    fn generate_baseline_change_tables(&mut self) {
+        let current = self.snapshot("current");
+        let baselines = &self.baselines;
+        for section in self
+            .markdown_files
+            .iter_mut()
+            .flat_map(|md_file| md_file.sections.iter_mut())
+        {
+            let Some(baseline) = section
+                .metadata
+                .as_ref()
+                .and_then(|m| m.changes_since_baseline.as_ref())
+                .and_then(|name| baselines.get(name))
+            else {
+                continue;
+            };
+            section.content.push(baseline.diff(&current).to_table());
+        }
+    }
+
+    /// Snapshot every section with a `section_id` as a baseline
+    ///
+    /// # Parameters
+    /// * `name` - Name of the baseline
+    ///
+    /// # Returns
+    /// * The baseline, with items sorted by `section_id`
+    pub fn snapshot(&self, name: &str) -> Baseline {
+        let contents: std::collections::HashMap<String, String> =
+            suspect::section_contents(&self.markdown_files)
+                .into_iter()
+                .collect();
+        let items = self
+            .collect_all_section_traceability()
+            .into_iter()
+            .map(|trace| BaselineItem {
+                // The first line of a section's content is its heading
+                text: contents
+                    .get(&trace.section_id)
+                    .map(|content| content.lines().skip(1).collect::<Vec<_>>().join("\n"))
+                    .unwrap_or_default(),
+                section_id: trace.section_id,
+                section_number: trace.section_number.to_string(),
+                heading: trace.heading,
+                traced_ids: trace.traced_ids,
+            })
+            .collect();
+        Baseline {
+            name: name.to_string(),
+            document_id: self.config.document_id.clone(),
+            items,
+        }
+    }
+
+    /// Build the trace graphs requested by `[trace_graph]` and embed them as images
+    ///
+    /// Each graph is appended to its section as an SVG image block under
+    /// `.sysdoc/trace-graphs/` in the document root, so exporters handle it like
+    /// any other SVG figure. Nothing is written here: checking the sources must
+    /// not touch the document tree, so the files are only written by
+    /// [`Self::write_trace_graphs`] before export.
+    fn generate_trace_graphs(&mut self, section_traces: &[SectionTrace], targets: &TraceTargets) {
+        let root = self.root.clone();
+        let document_id = self.config.document_id.clone();
+        for section in self
+            .markdown_files
+            .iter_mut()
+            .flat_map(|md_file| md_file.sections.iter_mut())
+        {
+            let Some(spec) = section
+                .metadata
+                .as_ref()
+                .and_then(|m| m.trace_graph.clone())
+            else {
+                continue;
+            };
+            let graph = TraceGraph::build(&spec, section_traces, targets, &document_id);
+            let path = Path::new(TRACE_GRAPH_DIR)
+                .join(format!("trace-graph-{}.svg", section.section_number));
+            let absolute_path = root.join(&path);
+            self.trace_graphs.push(EmbeddedTraceGraph {
+                source_file: section.source_file.clone(),
+                line: section.line_number,
+                absolute_path: absolute_path.clone(),
+                graph,
+            });
+            section.content.push(MarkdownBlock::Image {
+                path,
+                absolute_path,
+                alt_text: spec
+                    .caption
+                    .unwrap_or_else(|| "Traceability graph".to_string()),
+                title: String::new(),
+                format: ImageFormat::Svg,
+                exists: true,
+                span: SourceSpan::default(),
+            });
+        }
+    }
+
+    /// Write the embedded trace graphs as SVG (plus a DOT file alongside each)
+    ///
+    /// Called by the build before export, so that the exporters can load the
+    /// images. A graph that cannot be written is marked as a missing image.
+    ///
+    /// # Returns
+    /// * A "trace-graph-failed" error for each graph that could not be written
+    pub fn write_trace_graphs(&mut self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut failed = Vec::new();
+        for embedded in &self.trace_graphs {
+            let Err(e) = embedded.graph.write(&embedded.absolute_path) else {
+                continue;
+            };
+            diagnostics.push(
+                Diagnostic::error(
+                    "trace-graph-failed",
+                    format!(
+                        "failed to write trace graph {}: {}",
+                        embedded.absolute_path.display(),
+                        e
+                    ),
+                )
+                .at_line(&embedded.source_file, embedded.line),
+            );
+            failed.push(&embedded.absolute_path);
+        }
+
+        for block in self
+            .markdown_files
+            .iter_mut()
+            .flat_map(|md_file| md_file.sections.iter_mut())
+            .flat_map(|section| section.content.iter_mut())
+        {
+            if let MarkdownBlock::Image {
+                absolute_path,
+                exists,
+                ..
+            } = block
+            {
+                *exists &= !failed.contains(&&*absolute_path);
+            }
+        }
+        diagnostics
+    }
+
+    /// Append an "Implemented By" table to each section referenced from source code
+    fn generate_implemented_by_tables(&mut self) {
+        let enabled = self
+            .config
+            .code_trace
+            .as_ref()
+            .is_some_and(|code_trace| code_trace.implemented_by_table);
+        if !enabled || self.code_references.is_empty() {
+            return;
+        }
+
+        let references = &self.code_references;
+        for section in self
+            .markdown_files
+            .iter_mut()
+            .flat_map(|md_file| md_file.sections.iter_mut())
+        {
+            let Some(section_id) = section.metadata.as_ref().and_then(|m| m.section_id.clone())
+            else {
+                continue;
+            };
+            let rows: Vec<Vec<Vec<TextRun>>> = references
+                .iter()
+                .filter(|reference| reference.id == section_id)
+                .map(|reference| {
+                    vec![
+                        vec![TextRun::new(
+                            reference.file.to_string_lossy().replace('\\', "/"),
+                        )],
+                        vec![TextRun::new(reference.line.to_string())],
+                    ]
+                })
+                .collect();
+            if rows.is_empty() {
+                continue;
+            }
+            section.content.push(MarkdownBlock::InlineTable {
+                alignments: vec![Alignment::None, Alignment::Right],
+                headers: vec![
+                    vec![TextRun::new("Implemented By".to_string())],
+                    vec![TextRun::new("Line".to_string())],
+                ],
+                rows,
+            });
+        }
     }
 
     /// Collect traceability data from ALL sections across ALL files
-    fn collect_all_section_traceability(&self) -> Vec<(String, Vec<String>)> {
-        let mut section_to_traced: Vec<(String, Vec<String>)> = self
+    ///
+    /// # Returns
+    /// * `Vec<SectionTrace>` - One record per section with a `section_id`, sorted by `section_id`
+    pub fn collect_all_section_traceability(&self) -> Vec<SectionTrace> {
+        let mut section_traces: Vec<SectionTrace> = self
             .markdown_files
             .iter()
             .flat_map(|md_file| md_file.sections.iter())
@@ -147,32 +632,275 @@ impl SourceModel {
             .collect();
 
         // Sort by section_id
-        section_to_traced.sort_by(|a, b| a.0.cmp(&b.0));
-        section_to_traced
+        section_traces.sort_by(|a, b| a.section_id.cmp(&b.section_id));
+        section_traces
     }
 
     /// Extract traceability data from a section if it has both section_id and metadata
-    fn extract_section_traceability(section: &MarkdownSection) -> Option<(String, Vec<String>)> {
+    fn extract_section_traceability(section: &MarkdownSection) -> Option<SectionTrace> {
         let metadata = section.metadata.as_ref()?;
         let section_id = metadata.section_id.as_ref()?;
-        let traced = metadata.traced_ids.clone().unwrap_or_default();
-        Some((section_id.clone(), traced))
+        Some(SectionTrace {
+            section_id: section_id.clone(),
+            section_number: section.section_number.clone(),
+            heading: section.heading_text.clone(),
+            source_file: section.source_file.clone(),
+            line_number: section.line_number,
+            status: metadata.status.clone(),
+            verification_method: metadata.verification_method.clone(),
+            traced_ids: metadata.traced_ids.clone().unwrap_or_default(),
+            attributes: metadata.attributes.clone(),
+        })
+    }
+
+    /// Collect all known trace targets: local sections, catalog entries, and upstream sections
+    ///
+    /// Later sources take precedence over earlier ones with the same ID, so upstream
+    /// sections override catalog entries, which override local sections.
+    pub fn collect_trace_targets(&self, section_traces: &[SectionTrace]) -> TraceTargets {
+        let local = section_traces.iter().map(|trace| {
+            let target = TraceTarget {
+                title: trace.heading.clone(),
+                text: String::new(),
+                document_id: self.config.document_id.clone(),
+                section_number: Some(trace.section_number.clone()),
+            };
+            (trace.section_id.clone(), target)
+        });
+
+        let catalog = self.catalog.entries().map(|entry| {
+            let target = TraceTarget {
+                title: entry.title.clone(),
+                text: entry.text.clone(),
+                ..TraceTarget::default()
+            };
+            (entry.id.clone(), target)
+        });
+
+        let upstream = self.upstreams.iter().flat_map(|upstream| {
+            upstream
+                .sections()
+                .filter_map(Self::extract_section_traceability)
+                .map(|trace| {
+                    let target = TraceTarget {
+                        title: trace.heading,
+                        text: String::new(),
+                        document_id: upstream.config.document_id.clone(),
+                        section_number: Some(trace.section_number),
+                    };
+                    (trace.section_id, target)
+                })
+        });
+
+        local.chain(catalog).chain(upstream).collect()
+    }
+
+    /// Fingerprint the current content of every known trace target
+    ///
+    /// Uses the same precedence as [`Self::collect_trace_targets`]: upstream sections
+    /// override catalog entries, which override local sections.
+    pub fn collect_target_fingerprints(&self) -> std::collections::BTreeMap<String, String> {
+        let local = suspect::section_fingerprints(&self.markdown_files);
+
+        let catalog = self.catalog.entries().map(|entry| {
+            let content = format!("{}\n{}", entry.title, entry.text);
+            (entry.id.clone(), suspect::fingerprint(&content))
+        });
+
+        let upstream = self
+            .upstreams
+            .iter()
+            .flat_map(|upstream| suspect::section_fingerprints(&upstream.markdown_files));
+
+        local.into_iter().chain(catalog).chain(upstream).collect()
+    }
+
+    /// Find trace links whose targets changed since they were accepted
+    ///
+    /// # Returns
+    /// * Suspect links sorted by section ID, then traced ID. Empty when the document
+    ///   has no trace.lock. Links to unknown targets are not reported here; they are
+    ///   covered by traced ID validation.
+    pub fn suspect_links(&self) -> Vec<SuspectLink> {
+        let Some(lock) = &self.trace_lock else {
+            return Vec::new();
+        };
+        let fingerprints = self.collect_target_fingerprints();
+
+        self.collect_all_section_traceability()
+            .into_iter()
+            .flat_map(|trace| {
+                let links: Vec<(String, &String)> = trace
+                    .traced_ids
+                    .iter()
+                    .filter_map(|id| Some((id.clone(), fingerprints.get(id)?)))
+                    .collect();
+                links.into_iter().filter_map(move |(traced_id, current)| {
+                    let reason = match lock.get(&trace.section_id, &traced_id) {
+                        Some(accepted) if accepted == current => return None,
+                        Some(_) => SuspectReason::Changed,
+                        None => SuspectReason::Unreviewed,
+                    };
+                    Some(SuspectLink {
+                        section_id: trace.section_id.clone(),
+                        source_file: trace.source_file.clone(),
+                        line: trace.line_number,
+                        traced_id,
+                        reason,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Build a trace lock that accepts the current content of traced targets
+    ///
+    /// # Parameters
+    /// * `section_id` - Only accept links from this section (all sections if None)
+    /// * `traced_id` - Only accept links to this target (all targets if None)
+    ///
+    /// # Returns
+    /// * The updated lock and the number of links accepted. Without filters the
+    ///   lock is rebuilt from scratch, dropping links that no longer exist;
+    ///   with filters, other entries of the existing lock are kept.
+    pub fn accept_trace_links(
+        &self,
+        section_id: Option<&str>,
+        traced_id: Option<&str>,
+    ) -> (TraceLock, usize) {
+        let filtered = section_id.is_some() || traced_id.is_some();
+        let mut lock = match &self.trace_lock {
+            Some(lock) if filtered => lock.clone(),
+            _ => TraceLock::default(),
+        };
+        let fingerprints = self.collect_target_fingerprints();
+
+        let links: Vec<(String, String)> = self
+            .collect_all_section_traceability()
+            .into_iter()
+            .filter(|trace| section_id.is_none_or(|id| id == trace.section_id))
+            .flat_map(|trace| {
+                trace
+                    .traced_ids
+                    .into_iter()
+                    .map(move |id| (trace.section_id.clone(), id))
+            })
+            .filter(|(_, id)| traced_id.is_none_or(|wanted| wanted == id))
+            .collect();
+
+        let mut accepted = 0;
+        for (section, target) in links {
+            if let Some(current) = fingerprints.get(&target) {
+                lock.insert(&section, &target, current.clone());
+                accepted += 1;
+            }
+        }
+        (lock, accepted)
+    }
+
+    /// Choose new section_ids for sections that lack one
+    ///
+    /// Numbers continue after the highest number issued so far, counting the IDs
+    /// in the document and every ID in the registry, so retired IDs are never
+    /// issued again. Sections are numbered in document order.
+    ///
+    /// # Parameters
+    /// * `scheme` - ID scheme used to format the new IDs
+    /// * `all` - Number every section; otherwise only sections whose sysdoc block
+    ///   has traced IDs, a status, a verification method or test cases
+    ///
+    /// # Returns
+    /// * The assignments, and the registry updated with the current and new IDs
+    pub fn plan_section_ids(
+        &self,
+        scheme: &IdScheme,
+        all: bool,
+    ) -> (Vec<IdAssignment>, IdRegistry) {
+        let mut registry = self.id_registry.clone().unwrap_or_default();
+        let mut current: std::collections::BTreeSet<String> = self
+            .markdown_files
+            .iter()
+            .flat_map(|md_file| md_file.sections.iter())
+            .filter_map(|section| section.metadata.as_ref()?.section_id.clone())
+            .collect();
+        let mut next = registry
+            .issued
+            .iter()
+            .chain(&registry.retired)
+            .chain(&current)
+            .filter_map(|id| scheme.sequence_number(id))
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        let mut files: Vec<&MarkdownSource> = self.markdown_files.iter().collect();
+        files.sort_by(|a, b| a.section_number.cmp(&b.section_number));
+
+        let mut assignments = Vec::new();
+        for md_file in files {
+            let line_count = md_file.raw_content.lines().count();
+            let unnumbered = md_file
+                .sections
+                .iter()
+                .enumerate()
+                .filter(|(_, section)| needs_section_id(section, all));
+            for (index, section) in unnumbered {
+                let section_id = scheme.format_id(next);
+                next += 1;
+                current.insert(section_id.clone());
+                assignments.push(IdAssignment {
+                    section_id,
+                    heading: section.heading_text.clone(),
+                    source_file: md_file.path.clone(),
+                    line: section.line_number,
+                    end_line: md_file
+                        .sections
+                        .get(index + 1)
+                        .map_or(line_count + 1, |next| next.line_number),
+                });
+            }
+        }
+
+        registry.update(&current);
+        (assignments, registry)
+    }
+
+    /// Write planned section_ids into the markdown source files
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - Number of files rewritten
+    /// * `Err(std::io::Error)` - A file could not be written
+    pub fn write_section_ids(&self, assignments: &[IdAssignment]) -> std::io::Result<usize> {
+        let mut written = 0;
+        for md_file in &self.markdown_files {
+            let in_file: Vec<&IdAssignment> = assignments
+                .iter()
+                .filter(|assignment| assignment.source_file == md_file.path)
+                .collect();
+            if in_file.is_empty() {
+                continue;
+            }
+            let content = id_scheme::insert_section_ids(&md_file.raw_content, &in_file);
+            std::fs::write(&md_file.absolute_path, content)?;
+            written += 1;
+        }
+        Ok(written)
     }
 
     /// Build reverse mapping from traced_id to section_ids
     fn build_reverse_traceability(
         &self,
-        section_to_traced: &[(String, Vec<String>)],
+        section_traces: &[SectionTrace],
     ) -> std::collections::BTreeMap<String, Vec<String>> {
         let mut traced_to_sections: std::collections::BTreeMap<String, Vec<String>> =
             std::collections::BTreeMap::new();
 
-        for (section_id, traced_ids) in section_to_traced {
-            for traced_id in traced_ids {
+        for trace in section_traces {
+            for traced_id in &trace.traced_ids {
                 traced_to_sections
                     .entry(traced_id.clone())
                     .or_default()
-                    .push(section_id.clone());
+                    .push(trace.section_id.clone());
             }
         }
 
@@ -186,7 +914,7 @@ impl SourceModel {
 
     /// Create a table mapping section_ids to their traced_ids
     fn create_section_to_traced_table(
-        section_to_traced: &[(String, Vec<String>)],
+        section_traces: &[SectionTrace],
         col1_header: &str,
         col2_header: &str,
     ) -> MarkdownBlock {
@@ -195,13 +923,13 @@ impl SourceModel {
             vec![TextRun::new(col2_header.to_string())],
         ];
 
-        let rows: Vec<Vec<Vec<TextRun>>> = section_to_traced
+        let rows: Vec<Vec<Vec<TextRun>>> = section_traces
             .iter()
-            .map(|(section_id, traced_ids)| {
-                let mut sorted_traced = traced_ids.clone();
+            .map(|trace| {
+                let mut sorted_traced = trace.traced_ids.clone();
                 sorted_traced.sort();
                 vec![
-                    vec![TextRun::new(section_id.clone())],
+                    vec![TextRun::new(trace.section_id.clone())],
                     vec![TextRun::new(sorted_traced.join(", "))],
                 ]
             })
@@ -265,12 +993,14 @@ impl SourceModel {
             .content
             .iter()
             .filter_map(|block| match block {
-                MarkdownBlock::Image { path, exists, .. } if !exists => {
-                    Some(ValidationError::MissingImage {
-                        referenced_in: md_file.path.clone(),
-                        image_path: path.clone(),
-                    })
-                }
+                MarkdownBlock::Image {
+                    path, exists, span, ..
+                } if !exists => Some(ValidationError::MissingImage {
+                    referenced_in: md_file.path.clone(),
+                    image_path: path.clone(),
+                    line: span.line,
+                    column: span.column,
+                }),
                 _ => None,
             })
             .collect()
@@ -299,12 +1029,14 @@ impl SourceModel {
             .content
             .iter()
             .filter_map(|block| match block {
-                MarkdownBlock::CsvTable { path, exists, .. } if !exists => {
-                    Some(ValidationError::MissingTable {
-                        referenced_in: md_file.path.clone(),
-                        table_path: path.clone(),
-                    })
-                }
+                MarkdownBlock::CsvTable {
+                    path, exists, span, ..
+                } if !exists => Some(ValidationError::MissingTable {
+                    referenced_in: md_file.path.clone(),
+                    table_path: path.clone(),
+                    line: span.line,
+                    column: span.column,
+                }),
                 _ => None,
             })
             .collect()
@@ -333,12 +1065,14 @@ impl SourceModel {
             .content
             .iter()
             .filter_map(|block| match block {
-                MarkdownBlock::IncludedCodeBlock { path, exists, .. } if !exists => {
-                    Some(ValidationError::MissingIncludeFile {
-                        referenced_in: md_file.path.clone(),
-                        include_path: path.clone(),
-                    })
-                }
+                MarkdownBlock::IncludedCodeBlock {
+                    path, exists, span, ..
+                } if !exists => Some(ValidationError::MissingIncludeFile {
+                    referenced_in: md_file.path.clone(),
+                    include_path: path.clone(),
+                    line: span.line,
+                    column: span.column,
+                }),
                 _ => None,
             })
             .collect()
@@ -351,8 +1085,8 @@ impl SourceModel {
     /// - Links to other markdown files (e.g., `[text](other-file.md)`)
     /// - Links to files with anchors (e.g., `[text](file.md#section)`)
     ///
-    /// Note: Anchor-only links (`#section`) within the same file are not validated
-    /// as they require parsing heading IDs which is not currently implemented.
+    /// Anchors in links to upstream documents must name an upstream section (by
+    /// `section_id` or heading anchor). Other anchors are not validated.
     fn validate_internal_links(&self) -> Vec<ValidationError> {
         self.markdown_files
             .iter()
@@ -477,14 +1211,211 @@ impl SourceModel {
         // Resolve the path relative to the document root
         let target_path = self.root.join(file_path);
 
-        if !target_path.exists() {
+        if !target_path.exists() || !self.upstream_anchor_resolves(&target_path, url) {
+            let span = run.link_span.clone().unwrap_or_default();
             errors.push(ValidationError::BrokenLink {
                 referenced_in: md_file.path.clone(),
                 link_target: url.clone(),
+                line: span.line,
+                column: span.column,
             });
         }
     }
 
+    /// Check that a link into an upstream document names an existing section
+    ///
+    /// Links without an anchor, and links to files that are not upstream source
+    /// files, always resolve. Anchors into upstream files must match a section's
+    /// `section_id` or heading anchor.
+    fn upstream_anchor_resolves(&self, target_path: &Path, url: &str) -> bool {
+        let Some((_, anchor)) = url.split_once('#') else {
+            return true;
+        };
+        let Ok(canonical_path) = target_path.canonicalize() else {
+            return true;
+        };
+
+        self.upstreams
+            .iter()
+            .find_map(|upstream| upstream.find_file(&canonical_path))
+            .is_none_or(|md_file| {
+                md_file
+                    .sections
+                    .iter()
+                    .any(|section| upstream::section_has_anchor(section, anchor))
+            })
+    }
+
+    /// Validate that every traced ID resolves to a known trace target
+    ///
+    /// Traced IDs are only checked when at least one catalog or upstream project is
+    /// configured, since otherwise there is nothing authoritative to check them
+    /// against. A traced ID is valid if it is defined in a catalog or is the
+    /// `section_id` of a section in this document or an upstream document.
+    fn validate_traced_ids(&self) -> Vec<ValidationError> {
+        if self.config.catalogs.is_empty() && self.upstreams.is_empty() {
+            return Vec::new();
+        }
+
+        let known_ids: Vec<&str> = self
+            .markdown_files
+            .iter()
+            .flat_map(|md_file| md_file.sections.iter())
+            .filter_map(|section| section.metadata.as_ref()?.section_id.as_deref())
+            .chain(
+                self.upstreams
+                    .iter()
+                    .flat_map(UpstreamDocument::section_ids),
+            )
+            .collect();
+
+        self.markdown_files
+            .iter()
+            .flat_map(|md_file| {
+                md_file.sections.iter().flat_map(|section| {
+                    self.validate_section_traced_ids(md_file, section, &known_ids)
+                })
+            })
+            .collect()
+    }
+
+    /// Validate traced IDs in a single section
+    fn validate_section_traced_ids(
+        &self,
+        md_file: &MarkdownSource,
+        section: &MarkdownSection,
+        known_ids: &[&str],
+    ) -> Vec<ValidationError> {
+        let Some(traced_ids) = section
+            .metadata
+            .as_ref()
+            .and_then(|m| m.traced_ids.as_ref())
+        else {
+            return Vec::new();
+        };
+
+        traced_ids
+            .iter()
+            .filter(|id| !self.catalog.contains(id) && !known_ids.contains(&id.as_str()))
+            .map(|id| ValidationError::UnknownTracedId {
+                traced_id: id.clone(),
+                referenced_in: md_file.path.clone(),
+                line: section
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.value_span(&md_file.raw_content, "traced_ids", id))
+                    .map_or(section.line_number, |span| span.line),
+                suggestion: self.catalog.closest_id(id, known_ids).map(str::to_string),
+            })
+            .collect()
+    }
+
+    /// Validate that every ID referenced from source code is a local `section_id`
+    fn validate_code_references(&self) -> Vec<ValidationError> {
+        if self.code_references.is_empty() {
+            return Vec::new();
+        }
+
+        let section_ids: std::collections::HashSet<&str> = self
+            .markdown_files
+            .iter()
+            .flat_map(|md_file| md_file.sections.iter())
+            .filter_map(|section| section.metadata.as_ref()?.section_id.as_deref())
+            .collect();
+
+        self.code_references
+            .iter()
+            .filter(|reference| !section_ids.contains(reference.id.as_str()))
+            .map(|reference| ValidationError::UnknownCodeReference {
+                section_id: reference.id.clone(),
+                referenced_in: reference.file.clone(),
+                line: reference.line,
+            })
+            .collect()
+    }
+
+    /// Validate section_ids against the ID scheme and the retired IDs in the registry
+    fn validate_section_id_scheme(&self) -> Vec<ValidationError> {
+        let retired = self.id_registry.as_ref().map(|registry| &registry.retired);
+        self.markdown_files
+            .iter()
+            .flat_map(|md_file| md_file.sections.iter())
+            .filter_map(|section| {
+                let section_id = section.metadata.as_ref()?.section_id.as_ref()?;
+                if retired.is_some_and(|retired| retired.contains(section_id)) {
+                    return Some(ValidationError::RetiredSectionId {
+                        section_id: section_id.clone(),
+                        referenced_in: section.source_file.clone(),
+                        line: section.metadata_line("section_id"),
+                    });
+                }
+                let scheme = self.id_scheme.as_ref()?;
+                (!scheme.matches(section_id)).then(|| ValidationError::SectionIdPattern {
+                    section_id: section_id.clone(),
+                    pattern: scheme.pattern().to_string(),
+                    referenced_in: section.source_file.clone(),
+                    line: section.metadata_line("section_id"),
+                })
+            })
+            .collect()
+    }
+
+    /// Validate section attributes against their declarations in sysdoc.toml
+    ///
+    /// Declared attributes must have the declared type and one of the allowed
+    /// values. Sections with a `section_id` must set every attribute required
+    /// at their depth. Undeclared attributes are not checked.
+    fn validate_section_attributes(&self) -> Vec<ValidationError> {
+        let declarations = &self.config.attributes;
+        if declarations.is_empty() {
+            return Vec::new();
+        }
+
+        let mut errors = Vec::new();
+        let sections = self
+            .markdown_files
+            .iter()
+            .flat_map(|md_file| md_file.sections.iter());
+        for section in sections {
+            let metadata = section.metadata.as_ref();
+            let attributes = metadata.map(|metadata| &metadata.attributes);
+            let invalid = attributes
+                .into_iter()
+                .flatten()
+                .filter_map(|(name, value)| {
+                    let problem = value.check(declarations.get(name)?).err()?;
+                    Some(ValidationError::InvalidAttribute {
+                        attribute: name.clone(),
+                        heading: section.heading_text.clone(),
+                        problem,
+                        referenced_in: section.source_file.clone(),
+                        line: section.metadata_line(&format!("attributes.{}", name)),
+                    })
+                });
+            errors.extend(invalid);
+
+            let Some(section_id) = metadata.and_then(|metadata| metadata.section_id.as_ref())
+            else {
+                continue;
+            };
+            let depth = section.section_number.depth() + 1;
+            let missing = declarations
+                .iter()
+                .filter(|(name, config)| {
+                    config.is_required_at(depth)
+                        && attributes.is_none_or(|attributes| !attributes.contains_key(*name))
+                })
+                .map(|(name, _)| ValidationError::MissingAttribute {
+                    attribute: name.clone(),
+                    section_id: section_id.clone(),
+                    referenced_in: section.source_file.clone(),
+                    line: section.line_number,
+                });
+            errors.extend(missing);
+        }
+        errors
+    }
+
     /// Validate that all section_ids are unique across all sections
     fn validate_unique_section_ids(&self) -> Vec<ValidationError> {
         use std::collections::HashMap;
@@ -507,6 +1438,15 @@ impl SourceModel {
     }
 }
 
+/// Check whether `ids assign` should give a section a new section_id
+fn needs_section_id(section: &MarkdownSection, all: bool) -> bool {
+    match &section.metadata {
+        Some(metadata) if metadata.section_id.is_some() => false,
+        Some(metadata) => all || metadata.describes_item(),
+        None => all,
+    }
+}
+
 /// Helper function to check if a section_id is unique and record or report duplicates
 fn check_section_id_uniqueness(
     section: &MarkdownSection,
@@ -531,14 +1471,14 @@ fn check_section_id_uniqueness(
                 first_location: first_location.clone(),
                 first_line: *first_line,
                 second_location: file_path.to_path_buf(),
-                second_line: section.line_number,
+                second_line: section.metadata_line("section_id"),
             });
         }
         None => {
             // First time seeing this section_id, record its location and line number
             section_id_locations.insert(
                 section_id.clone(),
-                (file_path.to_path_buf(), section.line_number),
+                (file_path.to_path_buf(), section.metadata_line("section_id")),
             );
         }
     }
@@ -549,6 +1489,7 @@ mod tests {
     use super::*;
     use crate::document_config::Person;
     use crate::source_model::section_metadata::SectionMetadata;
+    use crate::source_model::test_results::TestStatus;
 
     /// Helper to create a minimal DocumentConfig for testing
     fn test_config() -> DocumentConfig {
@@ -574,6 +1515,18 @@ mod tests {
             title_page_background: None,
             revision_tag_pattern: r"^v[1-9]\d*\.\d+\.\d+$".to_string(),
             heading_color: "#2B579A".to_string(),
+            catalogs: Vec::new(),
+            upstreams: Vec::new(),
+            downstreams: Vec::new(),
+            code_trace: None,
+            test_results: Vec::new(),
+            id_schemes: std::collections::BTreeMap::new(),
+            attributes: std::collections::BTreeMap::new(),
+            lint: None,
+            spelling: None,
+            diagnostics: std::collections::BTreeMap::new(),
+            assets: None,
+            outline: None,
         }
     }
 
@@ -763,9 +1716,686 @@ mod tests {
         let error_message = format!("{}", result.unwrap_err());
         println!("Error message:\n{}", error_message);
 
-        // Verify the error message includes both file paths with line numbers
-        assert!(error_message.contains("src/requirements/01.01_functional.md:3"));
-        assert!(error_message.contains("src/requirements/01.02_nonfunctional.md:15"));
-        assert!(error_message.contains("REQ-001"));
+        assert!(error_message.contains("Duplicate section_id 'REQ-001'"));
+
+        // The diagnostic points at the second occurrence and names the first
+        let diagnostics = model.validate().unwrap_err().diagnostics();
+        let diagnostic = &diagnostics[0];
+        assert_eq!(
+            diagnostic.file.as_deref(),
+            Some(Path::new("src/requirements/01.02_nonfunctional.md"))
+        );
+        assert_eq!(diagnostic.span.as_ref().map(|span| span.line), Some(15));
+        assert!(diagnostic
+            .suggestion
+            .as_deref()
+            .is_some_and(|s| s.contains("src/requirements/01.01_functional.md:3")));
+    }
+
+    /// Helper to parse a markdown string into a source file
+    fn parse_test_file(path: &str, number: &str, content: &str) -> MarkdownSource {
+        let mut source = MarkdownSource {
+            path: PathBuf::from(path),
+            absolute_path: PathBuf::from("/test").join(path),
+            section_number: SectionNumber::parse(number).unwrap(),
+            title: String::new(),
+            raw_content: content.to_string(),
+            sections: Vec::new(),
+        };
+        source.parse(Path::new("/test")).unwrap();
+        source
+    }
+
+    #[test]
+    fn test_trace_table_spans_all_files() {
+        let mut model = SourceModel::new(PathBuf::from("/test"), test_config());
+
+        model.markdown_files.push(parse_test_file(
+            "src/01_requirements.md",
+            "01",
+            r#"# Requirements
+
+## Parse Input
+
+```sysdoc
+section_id = "SRS-001"
+```
+
+## Report Errors
+
+```sysdoc
+section_id = "SRS-002"
+```
+"#,
+        ));
+        model.markdown_files.push(parse_test_file(
+            "src/02_design.md",
+            "02",
+            r#"# Design
+
+```sysdoc
+[[trace_table]]
+columns = ["traced_id", "traced_title", "section_id", "section_number"]
+headers = ["Requirement", "Title", "Design Element", "Section"]
+group_by = "traced_id"
+traced_id_prefix = "SRS-"
+```
+
+## Parser
+
+```sysdoc
+section_id = "SDD-001"
+traced_ids = ["SRS-001", "SRS-002"]
+```
+"#,
+        ));
+
+        model.generate_traceability_tables();
+
+        let design = &model.markdown_files[1].sections[0];
+        let Some(MarkdownBlock::InlineTable { headers, rows, .. }) = design.content.last() else {
+            panic!("Expected a generated trace table");
+        };
+        assert_eq!(headers.len(), 4);
+        assert_eq!(headers[2][0].text, "Design Element");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0][0].text, "SRS-001");
+        assert_eq!(rows[0][1][0].text, "Parse Input");
+        assert_eq!(rows[0][2][0].text, "SDD-001");
+        assert_eq!(rows[0][3][0].text, "2.1");
+        assert_eq!(rows[1][1][0].text, "Report Errors");
+    }
+
+    #[test]
+    fn test_trace_graphs_written_only_on_request() {
+        let root = std::env::temp_dir().join(format!("sysdoc-trace-graph-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let mut model = SourceModel::new(root.clone(), test_config());
+        model.markdown_files.push(parse_test_file(
+            "src/01_design.md",
+            "01",
+            r#"# Design
+
+```sysdoc
+section_id = "SDD-001"
+traced_ids = ["SRS-001"]
+
+[trace_graph]
+caption = "Design traceability"
+```
+"#,
+        ));
+
+        model.generate_traceability_tables();
+        let graph_dir = root.join(TRACE_GRAPH_DIR);
+        assert!(
+            !graph_dir.exists(),
+            "checking the sources must not write files"
+        );
+        assert_eq!(model.trace_graphs.len(), 1);
+        assert_eq!(model.trace_graphs[0].line, 1);
+
+        // A file in the way of the output directory makes the write fail
+        std::fs::write(root.join(".sysdoc"), "").unwrap();
+        let diagnostics = model.write_trace_graphs();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "trace-graph-failed");
+        assert_eq!(
+            diagnostics[0].file.as_deref(),
+            Some(Path::new("src/01_design.md"))
+        );
+        let Some(MarkdownBlock::Image { exists, .. }) =
+            model.markdown_files[0].sections[0].content.last()
+        else {
+            panic!("Expected an embedded trace graph");
+        };
+        assert!(!exists);
+
+        std::fs::remove_file(root.join(".sysdoc")).unwrap();
+        assert!(model.write_trace_graphs().is_empty());
+        assert!(graph_dir.join("trace-graph-1.svg").exists());
+        assert!(graph_dir.join("trace-graph-1.dot").exists());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_traced_id_with_catalog() {
+        let mut config = test_config();
+        config.catalogs = vec![toml::from_str(r#"path = "srs.csv""#).unwrap()];
+        let mut model = SourceModel::new(PathBuf::from("/test"), config);
+        model
+            .catalog
+            .insert(catalog::CatalogEntry {
+                id: "SRS-REQ-001".to_string(),
+                title: "Parse input".to_string(),
+                text: "The system shall parse input.".to_string(),
+                catalog: PathBuf::from("srs.csv"),
+            })
+            .unwrap();
+
+        model.markdown_files.push(parse_test_file(
+            "src/01_design.md",
+            "01",
+            r#"# Design
+
+```sysdoc
+section_id = "SDD-001"
+traced_ids = ["SRS-REQ-001", "SRS-REQ-01", "SDD-001"]
+```
+"#,
+        ));
+
+        let Err(ValidationError::Multiple(errors)) = model.validate() else {
+            panic!("Expected validation to fail");
+        };
+        assert_eq!(errors.len(), 1);
+        let message = errors[0].to_string();
+        assert!(
+            message.contains("Unknown traced ID 'SRS-REQ-01'"),
+            "{}",
+            message
+        );
+        let diagnostic = &errors[0].diagnostics()[0];
+        assert_eq!(
+            diagnostic.suggestion.as_deref(),
+            Some("did you mean 'SRS-REQ-001'?")
+        );
+    }
+
+    #[test]
+    fn test_suspect_links() {
+        let requirement = |text: &str| {
+            parse_test_file(
+                "src/01_requirements.md",
+                "01",
+                &format!("# Power\n\n```sysdoc\nsection_id = \"SRS-1\"\n```\n\n{text}\n"),
+            )
+        };
+        let design = |traced_ids: &str| {
+            parse_test_file(
+                "src/02_design.md",
+                "02",
+                &format!(
+                    "# Design\n\n```sysdoc\nsection_id = \"SDD-1\"\ntraced_ids = {traced_ids}\n```\n"
+                ),
+            )
+        };
+
+        let mut model = SourceModel::new(PathBuf::from("/test"), test_config());
+        model.markdown_files = vec![
+            requirement("The unit shall accept 28V."),
+            design(r#"["SRS-1", "EXT-9"]"#),
+        ];
+        assert!(model.suspect_links().is_empty(), "no lock, no checks");
+
+        // Unknown targets (EXT-9) cannot be fingerprinted and are skipped
+        let (lock, accepted) = model.accept_trace_links(None, None);
+        assert_eq!(accepted, 1);
+        model.trace_lock = Some(lock);
+        assert!(model.suspect_links().is_empty());
+
+        // Reformatting whitespace or editing the target's metadata does not matter
+        model.markdown_files[0] = requirement("The unit shall  accept 28V.\n");
+        assert!(model.suspect_links().is_empty());
+
+        model.markdown_files = vec![
+            requirement("The unit shall accept 24V."),
+            design(r#"["SRS-1", "SDD-1"]"#),
+        ];
+        let suspects = model.suspect_links();
+        assert_eq!(suspects.len(), 2);
+        assert_eq!(suspects[0].traced_id, "SRS-1");
+        assert_eq!(suspects[0].reason, SuspectReason::Changed);
+        assert_eq!(suspects[1].traced_id, "SDD-1");
+        assert_eq!(suspects[1].reason, SuspectReason::Unreviewed);
+        assert!(suspects[0]
+            .to_string()
+            .starts_with("src/02_design.md:1: Suspect link SDD-1 -> SRS-1"));
+
+        // Accepting a single target keeps the other link suspect
+        let (lock, accepted) = model.accept_trace_links(None, Some("SRS-1"));
+        assert_eq!(accepted, 1);
+        model.trace_lock = Some(lock);
+        let suspects = model.suspect_links();
+        assert_eq!(suspects.len(), 1);
+        assert_eq!(suspects[0].traced_id, "SDD-1");
+    }
+
+    #[test]
+    fn test_section_id_scheme() {
+        let mut config = test_config();
+        config.document_type = "SDD".to_string();
+        config.id_schemes.insert(
+            "SDD".to_string(),
+            toml::from_str(r#"format = "SDD-{n:03}""#).unwrap(),
+        );
+        let scheme = IdScheme::new(config.id_scheme().unwrap()).unwrap();
+        let mut model = SourceModel::new(PathBuf::from("/test"), config);
+        model.id_scheme = Some(scheme.clone());
+        model.markdown_files.push(parse_test_file(
+            "src/01_design.md",
+            "01",
+            "# Lexer\n\n```sysdoc\nsection_id = \"SDD-002\"\n```\n\n## Parser\n\n```sysdoc\ntraced_ids = [\"SDD-002\"]\n```\n\n## Notes\n\nText.\n",
+        ));
+        model.id_registry = Some(IdRegistry {
+            issued: ["SDD-002", "SDD-005"].map(String::from).into(),
+            retired: ["SDD-005"].map(String::from).into(),
+        });
+        assert!(model.validate().is_ok());
+
+        // Retired numbers are never issued again; Notes has no item metadata
+        let (assignments, registry) = model.plan_section_ids(&scheme, false);
+        let ids: Vec<(&str, usize)> = assignments
+            .iter()
+            .map(|a| (a.section_id.as_str(), a.line))
+            .collect();
+        assert_eq!(ids, vec![("SDD-006", 7)]);
+        assert!(registry.issued.contains("SDD-006"));
+        let (assignments, _) = model.plan_section_ids(&scheme, true);
+        assert_eq!(assignments.len(), 2);
+
+        model.markdown_files[0] = parse_test_file(
+            "src/01_design.md",
+            "01",
+            "# Lexer\n\n```sysdoc\nsection_id = \"SDD-005\"\n```\n\n## Parser\n\n```sysdoc\nsection_id = \"SDD-3.1\"\n```\n",
+        );
+        let Err(ValidationError::Multiple(errors)) = model.validate() else {
+            panic!("Expected validation to fail");
+        };
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Section ID 'SDD-005' was retired and must not be reused (see id-registry.toml)",
+                "Section ID 'SDD-3.1' does not match the ID scheme '^SDD\\-\\d{3,}$'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_section_attributes() {
+        let mut config = test_config();
+        config.attributes = toml::from_str(
+            r#"
+[safety_level]
+values = ["A", "B", "C"]
+required_depths = [2]
+
+[priority]
+type = "integer"
+"#,
+        )
+        .unwrap();
+        let mut model = SourceModel::new(PathBuf::from("/test"), config);
+        model.markdown_files.push(parse_test_file(
+            "src/01_reqs.md",
+            "01",
+            "# Requirements\n\n```sysdoc\nsection_id = \"SRS-1\"\n```\n\n## Parse\n\n```sysdoc\nsection_id = \"SRS-1.1\"\n[attributes]\nsafety_level = \"B\"\nowner = \"J. Smith\"\n```\n",
+        ));
+        assert!(model.validate().is_ok());
+
+        let traces = model.collect_all_section_traceability();
+        assert_eq!(traces[1].attributes.len(), 2);
+
+        model.markdown_files[0] = parse_test_file(
+            "src/01_reqs.md",
+            "01",
+            "# Requirements\n\n```sysdoc\n[attributes]\npriority = \"high\"\n```\n\n## Parse\n\n```sysdoc\nsection_id = \"SRS-1.1\"\n[attributes]\nsafety_level = \"E\"\n```\n\n## Report\n\n```sysdoc\nsection_id = \"SRS-1.2\"\n```\n",
+        );
+        let Err(ValidationError::Multiple(errors)) = model.validate() else {
+            panic!("Expected validation to fail");
+        };
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Attribute 'priority' of section 'Requirements': expected integer, found string",
+                "Attribute 'safety_level' of section 'Parse': 'E' is not one of: A, B, C",
+                "Section 'SRS-1.2' is missing the required attribute 'safety_level'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_query_tables() {
+        let mut model = SourceModel::new(PathBuf::from("/test"), test_config());
+        model.markdown_files.push(parse_test_file(
+            "src/01_interfaces.md",
+            "01",
+            r#"# Interfaces
+
+```sysdoc
+[[query_table]]
+query = "select section_id, heading, attributes.protocol where attributes.protocol = CAN order by section_id desc"
+
+[[query_table]]
+query = "select status, count, section_id group by status order by section_id"
+headers = ["Status", "Sections", "IDs"]
+
+[[query_table]]
+query = "select heading where attributes.protocol != CAN"
+
+[[query_table]]
+query = "select heading where not attributes.protocol = CAN or attributes.protocol is not set"
+```
+
+## Engine Bus
+
+```sysdoc
+section_id = "ICD-2"
+status = "TBD"
+[attributes]
+protocol = "CAN"
+```
+
+## Cockpit Bus
+
+```sysdoc
+section_id = "ICD-1"
+status = "Approved"
+[attributes]
+protocol = "ARINC 429"
+```
+
+## Sensor Bus
+
+```sysdoc
+section_id = "ICD-3"
+status = "TBD"
+[attributes]
+protocol = "CAN"
+```
+"#,
+        ));
+        model.generate_query_tables();
+
+        let tables: Vec<Vec<Vec<String>>> = model.markdown_files[0].sections[0]
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                MarkdownBlock::InlineTable { rows, .. } => Some(
+                    rows.iter()
+                        .map(|row| row.iter().map(|cell| cell[0].text.clone()).collect())
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            tables,
+            vec![
+                vec![
+                    vec!["ICD-3", "Sensor Bus", "CAN"],
+                    vec!["ICD-2", "Engine Bus", "CAN"],
+                ],
+                vec![
+                    vec!["Approved", "1", "ICD-1"],
+                    vec!["TBD", "2", "ICD-2, ICD-3"],
+                ],
+                // A comparison with an unset field is unknown, so "Interfaces"
+                // only matches through `is not set`
+                vec![vec!["Cockpit Bus"]],
+                vec![vec!["Interfaces"], vec!["Cockpit Bus"]],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_open_items() {
+        let mut model = SourceModel::new(PathBuf::from("/test"), test_config());
+        model.markdown_files.push(parse_test_file(
+            "src/01_timing.md",
+            "01",
+            "# Timing\n\n```sysdoc\nstatus = \"TBD\"\n```\n\nThe timeout shall be TBD-003 (load analysis) ms.\n\n## Open Items\n\n```sysdoc\ngenerate_tbd_table = true\n```\n",
+        ));
+        assert!(model.validate_release().is_ok());
+        model.generate_open_items_tables();
+
+        let MarkdownBlock::InlineTable { rows, .. } =
+            &model.markdown_files[0].sections[1].content[0]
+        else {
+            panic!("expected the TBD/TBR List table");
+        };
+        let row: Vec<&str> = rows[0].iter().map(|cell| cell[0].text.as_str()).collect();
+        assert_eq!(
+            row,
+            vec![
+                "TBD-003",
+                "1",
+                "Timing",
+                "src/01_timing.md:7",
+                "load analysis"
+            ]
+        );
+        assert_eq!(rows.len(), 1);
+
+        let Err(ValidationError::Multiple(errors)) = model.validate_release() else {
+            panic!("expected release validation to fail");
+        };
+        assert_eq!(
+            errors[0].to_string(),
+            "Open item 'TBD-003' must be resolved before release"
+        );
+    }
+
+    #[test]
+    fn test_code_references() {
+        let mut config = test_config();
+        config.code_trace = Some(toml::from_str(r#"paths = ["../code"]"#).unwrap());
+        let mut model = SourceModel::new(PathBuf::from("/test"), config);
+        model.markdown_files.push(parse_test_file(
+            "src/01_design.md",
+            "01",
+            "# Lexer\n\n```sysdoc\nsection_id = \"SDD-1\"\n```\n\n## Parser\n",
+        ));
+        let reference = |id: &str, line: usize| CodeReference {
+            id: id.to_string(),
+            file: PathBuf::from("../code/lexer.rs"),
+            line,
+        };
+        model.code_references = vec![reference("SDD-1", 3), reference("SDD-2", 9)];
+
+        let Err(ValidationError::Multiple(errors)) = model.validate() else {
+            panic!("Expected validation to fail");
+        };
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "Unknown section ID 'SDD-2' referenced from code"
+        );
+
+        model.code_references.pop();
+        model.generate_traceability_tables();
+        let sections = &model.markdown_files[0].sections;
+        let Some(MarkdownBlock::InlineTable { headers, rows, .. }) = sections[0].content.last()
+        else {
+            panic!("Expected an Implemented By table");
+        };
+        assert_eq!(headers[0][0].text, "Implemented By");
+        assert_eq!(rows[0][0][0].text, "../code/lexer.rs");
+        assert_eq!(rows[0][1][0].text, "3");
+        assert!(!matches!(
+            sections[1].content.last(),
+            Some(MarkdownBlock::InlineTable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_test_case_results_propagate_to_requirements() {
+        let mut model = SourceModel::new(PathBuf::from("/test"), test_config());
+        model.markdown_files.push(parse_test_file(
+            "src/01_tests.md",
+            "01",
+            r#"# Test Cases
+
+```sysdoc
+generate_test_results_table = true
+generate_verification_status_table = true
+```
+
+## Parse Input
+
+```sysdoc
+section_id = "STD-1"
+test_cases = ["parser.parses_input", "parser.parses_empty"]
+traced_ids = ["SRS-1", "SRS-2"]
+```
+
+## Report
+
+```sysdoc
+section_id = "STD-2"
+traced_ids = ["SRS-2"]
+```
+
+## Export
+
+```sysdoc
+section_id = "STD-3"
+traced_ids = ["SRS-3"]
+```
+"#,
+        ));
+        model
+            .test_results
+            .insert("parser.parses_input".to_string(), TestStatus::Pass);
+        model
+            .test_results
+            .insert("parser.parses_empty".to_string(), TestStatus::Pass);
+        model
+            .test_results
+            .insert("STD-2".to_string(), TestStatus::Fail);
+
+        // STD-3 has neither test_cases nor a result named after it
+        let cases = model.collect_test_case_results();
+        let statuses: Vec<(&str, TestStatus)> = cases
+            .iter()
+            .map(|case| (case.section_id.as_str(), case.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![("STD-1", TestStatus::Pass), ("STD-2", TestStatus::Fail)]
+        );
+
+        model.generate_traceability_tables();
+        let content = &model.markdown_files[0].sections[0].content;
+        let [.., MarkdownBlock::InlineTable { rows: results, .. }, MarkdownBlock::InlineTable { rows: matrix, .. }] =
+            content.as_slice()
+        else {
+            panic!("Expected test results and verification tables");
+        };
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1][3][0].text, "Fail");
+
+        let cell = |row: &Vec<Vec<TextRun>>, column: usize| row[column][0].text.clone();
+        let matrix: Vec<(String, String, String)> = matrix
+            .iter()
+            .map(|row| (cell(row, 0), cell(row, 1), cell(row, 2)))
+            .collect();
+        assert_eq!(
+            matrix,
+            vec![
+                ("SRS-1".to_string(), "STD-1".to_string(), "Pass".to_string()),
+                (
+                    "SRS-2".to_string(),
+                    "STD-1, STD-2".to_string(),
+                    "Fail".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_traced_ids_unchecked_without_catalogs() {
+        let mut model = SourceModel::new(PathBuf::from("/test"), test_config());
+        model.markdown_files.push(parse_test_file(
+            "src/01_design.md",
+            "01",
+            r#"# Design
+
+```sysdoc
+section_id = "SDD-001"
+traced_ids = ["ANYTHING-1"]
+```
+"#,
+        ));
+
+        assert!(model.validate().is_ok());
+    }
+
+    #[test]
+    fn test_upstream_traced_ids_and_links() {
+        let dir = std::env::temp_dir().join(format!("sysdoc-upstream-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sdd")).unwrap();
+        std::fs::create_dir_all(dir.join("srs/src")).unwrap();
+        let srs_content = r#"# Requirements
+
+## Parse Input
+
+```sysdoc
+section_id = "SRS-001"
+```
+"#;
+        std::fs::write(dir.join("srs/src/03_requirements.md"), srs_content).unwrap();
+
+        let mut upstream_config = test_config();
+        upstream_config.document_id = "SRS-2024-001".to_string();
+        let mut config = test_config();
+        config.upstreams = vec![toml::from_str(r#"path = "../srs""#).unwrap()];
+
+        let mut model = SourceModel::new(dir.join("sdd"), config);
+        model.upstreams.push(UpstreamDocument {
+            path: PathBuf::from("../srs"),
+            root: dir.join("srs").canonicalize().unwrap(),
+            config: upstream_config,
+            markdown_files: vec![parse_test_file("src/03_requirements.md", "03", srs_content)],
+        });
+        model.markdown_files.push(parse_test_file(
+            "src/01_design.md",
+            "01",
+            r#"# Design
+
+See [parsing](../srs/src/03_requirements.md#SRS-001),
+[input](../srs/src/03_requirements.md#parse-input) and
+[missing](../srs/src/03_requirements.md#SRS-999).
+
+```sysdoc
+section_id = "SDD-001"
+traced_ids = ["SRS-001", "SRS-01"]
+
+[[trace_table]]
+columns = ["traced_id", "traced_document", "traced_section_number"]
+```
+"#,
+        ));
+
+        let Err(ValidationError::Multiple(errors)) = model.validate() else {
+            panic!("Expected validation to fail");
+        };
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages.len(), 2, "{:?}", messages);
+        assert!(messages[0].contains("#SRS-999"), "{}", messages[0]);
+        assert!(
+            messages[1].contains("Unknown traced ID 'SRS-01'"),
+            "{}",
+            messages[1]
+        );
+        assert_eq!(
+            errors[1].diagnostics()[0].suggestion.as_deref(),
+            Some("did you mean 'SRS-001'?")
+        );
+
+        model.generate_traceability_tables();
+        let Some(MarkdownBlock::InlineTable { rows, .. }) =
+            model.markdown_files[0].sections[0].content.last()
+        else {
+            panic!("Expected a generated trace table");
+        };
+        assert_eq!(rows[0][0][0].text, "SRS-001");
+        assert_eq!(rows[0][1][0].text, "SRS-2024-001");
+        assert_eq!(rows[0][2][0].text, "3.1");
+
+        std::fs::remove_dir_all(&dir).ok();
     }
 }
@@ -0,0 +1,398 @@
+//! Orphaned asset detection
+//!
+//! An asset is any file under `src/` other than a markdown file: images, CSV
+//! tables, included files and diagram sources. An asset is orphaned when no
+//! image, CSV table (a `.csv` link or a `<!-- TABLE: path.csv -->` comment),
+//! `include_file`, link or sysdoc.toml setting refers to it, and it does not
+//! match an `[assets] ignore` pattern. A `.drawio` file is in use while an
+//! export of it (such as `diagram.drawio.svg`) is referenced.
+
+use super::blocks::MarkdownBlock;
+use super::markdown_source::MarkdownSource;
+use super::text_run::TextRun;
+use crate::diagnostics::Diagnostic;
+use crate::document_config::DocumentConfig;
+use crate::paths::SOURCE_DIR;
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use thiserror::Error;
+use walkdir::WalkDir;
+
+/// A `<!-- TABLE: path.csv -->` comment, with the path relative to the markdown file
+static TABLE_COMMENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<!--\s*TABLE:\s*(\S+?)\s*-->").expect("valid table comment regex")
+});
+
+/// Errors finding or removing orphaned assets
+#[derive(Error, Debug)]
+pub enum AssetError {
+    #[error("Invalid pattern '{0}' in [assets] ignore: {1}")]
+    InvalidIgnorePattern(String, #[source] regex::Error),
+
+    #[error("Failed to remove '{0}': {1}")]
+    Remove(PathBuf, #[source] std::io::Error),
+}
+
+/// A file under `src/` that nothing in the document refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanedAsset {
+    /// Path of the file (relative to document root)
+    pub path: PathBuf,
+}
+
+impl From<&OrphanedAsset> for Diagnostic {
+    fn from(asset: &OrphanedAsset) -> Self {
+        Diagnostic::warning(
+            "orphaned-asset",
+            format!(
+                "Asset '{}' is not referenced by the document",
+                asset.path.display()
+            ),
+        )
+        .in_file(&asset.path)
+        .with_suggestion(
+            "reference the file, delete it with 'sysdoc clean-assets --apply', \
+             or add it to [assets] ignore in sysdoc.toml",
+        )
+    }
+}
+
+/// Find the files under `src/` that nothing in the document refers to
+///
+/// # Parameters
+/// * `root` - Root directory of the document
+/// * `config` - Document configuration (for referenced files and `[assets]`)
+/// * `files` - Parsed markdown sources of the document
+///
+/// # Returns
+/// * `Ok(Vec<OrphanedAsset>)` - Orphaned assets, sorted by path
+/// * `Err(AssetError)` - An `[assets] ignore` pattern is invalid
+pub fn find_orphaned_assets(
+    root: &Path,
+    config: &DocumentConfig,
+    files: &[MarkdownSource],
+) -> Result<Vec<OrphanedAsset>, AssetError> {
+    let ignore = config
+        .assets
+        .iter()
+        .flat_map(|assets| &assets.ignore)
+        .map(|pattern| IgnorePattern::new(pattern))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut references = References::new(root);
+    for path in config_references(config) {
+        references.insert(root.join(path));
+    }
+    for md_file in files {
+        let dir = md_file.absolute_path.parent().unwrap_or(root);
+        for section in &md_file.sections {
+            references.insert_blocks(&section.content, dir);
+        }
+    }
+
+    let mut orphaned: Vec<OrphanedAsset> = WalkDir::new(root.join(SOURCE_DIR))
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file() && is_asset(entry.path()))
+        .filter(|entry| !references.contains(entry.path()))
+        .filter_map(|entry| {
+            let path = entry.path().strip_prefix(root).ok()?.to_path_buf();
+            (!ignore.iter().any(|pattern| pattern.matches(&path))).then_some(OrphanedAsset { path })
+        })
+        .collect();
+    orphaned.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(orphaned)
+}
+
+/// Delete orphaned assets, and the directories under `src/` they leave empty
+///
+/// # Parameters
+/// * `root` - Root directory of the document
+/// * `assets` - Assets returned by [`find_orphaned_assets`]
+///
+/// # Returns
+/// * `Ok(())` - Every asset was deleted
+/// * `Err(AssetError)` - An asset could not be deleted
+pub fn remove_assets(root: &Path, assets: &[OrphanedAsset]) -> Result<(), AssetError> {
+    let source_dir = root.join(SOURCE_DIR);
+    for asset in assets {
+        let path = root.join(&asset.path);
+        std::fs::remove_file(&path).map_err(|e| AssetError::Remove(asset.path.clone(), e))?;
+
+        // Removing a directory fails once it is not empty, which ends the walk
+        let parents = path.ancestors().skip(1);
+        for dir in parents.take_while(|dir| dir.starts_with(&source_dir) && *dir != source_dir) {
+            if std::fs::remove_dir(dir).is_err() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether a file under `src/` is an asset (not markdown and not hidden)
+fn is_asset(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_none_or(|name| name.starts_with('.'));
+    let markdown = path.extension().and_then(|ext| ext.to_str()) == Some("md");
+    !hidden && !markdown
+}
+
+/// Files named in sysdoc.toml, relative to the document root
+fn config_references(config: &DocumentConfig) -> Vec<&str> {
+    let mut paths: Vec<&str> = config
+        .catalogs
+        .iter()
+        .map(|catalog| catalog.path.as_str())
+        .chain(
+            config
+                .test_results
+                .iter()
+                .map(|results| results.path.as_str()),
+        )
+        .collect();
+    paths.extend(config.docx_template_path.as_deref());
+    paths.extend(config.title_page_background.as_deref());
+    if let Some(spelling) = &config.spelling {
+        paths.push(spelling.dictionary.as_str());
+        paths.extend(spelling.word_list.as_deref());
+    }
+    paths
+}
+
+/// Canonical paths of the files the document refers to
+struct References<'a> {
+    root: &'a Path,
+    paths: HashSet<PathBuf>,
+}
+
+impl<'a> References<'a> {
+    fn new(root: &'a Path) -> Self {
+        Self {
+            root,
+            paths: HashSet::new(),
+        }
+    }
+
+    /// Record a referenced file (files that do not exist are skipped)
+    fn insert(&mut self, path: PathBuf) {
+        if let Ok(path) = path.canonicalize() {
+            self.paths.insert(path);
+        }
+    }
+
+    /// Whether a file is referenced, directly or through an export of a `.drawio` source
+    fn contains(&self, path: &Path) -> bool {
+        let Ok(path) = path.canonicalize() else {
+            return false;
+        };
+        if self.paths.contains(&path) {
+            return true;
+        }
+        let is_drawio = path.extension().and_then(|ext| ext.to_str()) == Some("drawio");
+        is_drawio
+            && self.paths.iter().any(|referenced| {
+                referenced.parent() == path.parent()
+                    && referenced
+                        .file_stem()
+                        .is_some_and(|stem| Some(stem) == path.file_name())
+            })
+    }
+
+    /// Record the files referenced by blocks, including nested blocks
+    ///
+    /// `dir` is the directory of the markdown file the blocks come from.
+    fn insert_blocks(&mut self, blocks: &[MarkdownBlock], dir: &Path) {
+        for block in blocks {
+            self.insert_block(block, dir);
+        }
+    }
+
+    /// Record the files referenced by a single block
+    fn insert_block(&mut self, block: &MarkdownBlock, dir: &Path) {
+        match block {
+            MarkdownBlock::Image { absolute_path, .. }
+            | MarkdownBlock::CsvTable { absolute_path, .. }
+            | MarkdownBlock::IncludedCodeBlock { absolute_path, .. } => {
+                self.insert(absolute_path.clone());
+            }
+            MarkdownBlock::Paragraph(runs) | MarkdownBlock::Heading { runs, .. } => {
+                self.insert_links(runs);
+            }
+            MarkdownBlock::Html(html) => {
+                for comment in TABLE_COMMENT.captures_iter(html) {
+                    self.insert(dir.join(&comment[1]));
+                }
+            }
+            MarkdownBlock::List { items, .. } => {
+                for item in items {
+                    self.insert_blocks(&item.content, dir);
+                }
+            }
+            MarkdownBlock::BlockQuote(blocks) => self.insert_blocks(blocks, dir),
+            MarkdownBlock::InlineTable { headers, rows, .. } => {
+                for cell in headers.iter().chain(rows.iter().flatten()) {
+                    self.insert_links(cell);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Record the files targeted by links (resolved like internal link validation)
+    fn insert_links(&mut self, runs: &[TextRun]) {
+        for url in runs.iter().filter_map(|run| run.link_url.as_deref()) {
+            let file_path = url.split('#').next().unwrap_or(url);
+            if !file_path.is_empty() && !file_path.contains("://") && !url.starts_with("mailto:") {
+                self.insert(self.root.join(file_path));
+            }
+        }
+    }
+}
+
+/// A compiled `[assets] ignore` glob pattern
+struct IgnorePattern {
+    regex: Regex,
+    /// Whether the pattern matches the whole relative path rather than the file name
+    full_path: bool,
+}
+
+impl IgnorePattern {
+    fn new(pattern: &str) -> Result<Self, AssetError> {
+        let regex = glob_regex(pattern)
+            .map_err(|e| AssetError::InvalidIgnorePattern(pattern.to_string(), e))?;
+        Ok(Self {
+            regex,
+            full_path: pattern.contains('/'),
+        })
+    }
+
+    /// Whether the pattern matches a path relative to the document root
+    fn matches(&self, path: &Path) -> bool {
+        let text = if self.full_path {
+            path.components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        } else {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        self.regex.is_match(&text)
+    }
+}
+
+/// Translate a glob pattern into an anchored regular expression
+///
+/// `*` and `?` match within a path component, `**/` matches any number of
+/// directories and a trailing `**` matches everything below a directory.
+fn glob_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::section_number::SectionNumber;
+    use super::*;
+    use crate::document_config::AssetsConfig;
+
+    #[test]
+    fn test_ignore_patterns() {
+        let nested = IgnorePattern::new("src/**/*.psd").unwrap();
+        assert!(nested.matches(Path::new("src/logo.psd")));
+        assert!(nested.matches(Path::new("src/art/old/logo.psd")));
+        assert!(!nested.matches(Path::new("src/logo.png")));
+
+        let name = IgnorePattern::new("*.bak").unwrap();
+        assert!(name.matches(Path::new("src/tables/power.csv.bak")));
+
+        let dir = IgnorePattern::new("src/logos/*").unwrap();
+        assert!(dir.matches(Path::new("src/logos/a.png")));
+        assert!(!dir.matches(Path::new("src/logos/old/a.png")));
+    }
+
+    #[test]
+    fn test_find_and_remove_orphaned_assets() {
+        let root = std::env::temp_dir().join(format!("sysdoc-assets-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["src/diagrams", "src/tables", "src/old", "src/art"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        let markdown = "# Power\n\n![Pump](src/diagrams/pump.drawio.svg)\n\n\
+                        See [the data](src/tables/data.xlsx).\n\n\
+                        [Power](src/tables/power.csv)\n\n\
+                        <!-- TABLE: tables/limits.csv -->\n";
+        for file in [
+            "src/diagrams/pump.drawio.svg",
+            "src/diagrams/pump.drawio",
+            "src/tables/power.csv",
+            "src/tables/limits.csv",
+            "src/tables/data.xlsx",
+            "src/old/unused.png",
+            "src/art/logo.psd",
+            "src/.gitkeep",
+        ] {
+            std::fs::write(root.join(file), "x").unwrap();
+        }
+        std::fs::write(root.join("src/01_power.md"), markdown).unwrap();
+
+        let mut md_file = MarkdownSource {
+            path: PathBuf::from("src/01_power.md"),
+            absolute_path: root.join("src/01_power.md"),
+            section_number: SectionNumber::parse("01").unwrap(),
+            title: "Power".to_string(),
+            raw_content: markdown.to_string(),
+            sections: Vec::new(),
+        };
+        md_file.parse(&root).unwrap();
+        let mut config: DocumentConfig = toml::from_str(
+            "document_id = \"D\"\ndocument_title = \"T\"\ndocument_type = \"SRS\"\n\
+             document_standard = \"S\"\ndocument_template = \"\"\n\
+             [document_owner]\nname = \"A\"\nemail = \"a@b\"\n\
+             [document_approver]\nname = \"A\"\nemail = \"a@b\"\n",
+        )
+        .unwrap();
+        config.assets = Some(AssetsConfig {
+            ignore: vec!["*.psd".to_string()],
+        });
+
+        let orphaned = find_orphaned_assets(&root, &config, &[md_file]).unwrap();
+        assert_eq!(
+            orphaned,
+            vec![OrphanedAsset {
+                path: PathBuf::from("src/old/unused.png")
+            }]
+        );
+
+        remove_assets(&root, &orphaned).unwrap();
+        assert!(!root.join("src/old").exists());
+        assert!(root.join("src/tables/power.csv").exists());
+        assert!(root.join("src/tables/limits.csv").exists());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
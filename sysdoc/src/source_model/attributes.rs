@@ -0,0 +1,175 @@
+//! Custom section attributes
+//!
+//! Sections carry free-form attributes in the `[attributes]` table of their
+//! sysdoc block (e.g., `owner`, `safety_level`). Attributes declared in
+//! sysdoc.toml (`[attributes.<name>]`) have their values checked here.
+
+use crate::document_config::{AttributeConfig, AttributeKind};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use toml::value::Datetime;
+
+/// Value of a custom section attribute
+///
+/// Variants are tried in order, so a datetime is not read as a table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AttributeValue {
+    /// A boolean value
+    Boolean(bool),
+    /// An integer value
+    Integer(i64),
+    /// A floating point value
+    Float(f64),
+    /// A date, time or date-time value
+    Datetime(Datetime),
+    /// A string value
+    Text(String),
+    /// An array of strings
+    List(Vec<String>),
+    /// An inline table
+    Table(toml::Table),
+}
+
+impl AttributeValue {
+    /// The type of this value
+    pub fn kind(&self) -> AttributeKind {
+        match self {
+            Self::Boolean(_) => AttributeKind::Boolean,
+            Self::Integer(_) => AttributeKind::Integer,
+            Self::Float(_) => AttributeKind::Float,
+            Self::Datetime(_) => AttributeKind::Datetime,
+            Self::Text(_) => AttributeKind::String,
+            Self::List(_) => AttributeKind::List,
+            Self::Table(_) => AttributeKind::Table,
+        }
+    }
+
+    /// Check the value against an attribute declaration
+    ///
+    /// # Returns
+    /// * `Ok(())` - The value has the declared type and only allowed values
+    /// * `Err(String)` - Description of the problem
+    pub fn check(&self, config: &AttributeConfig) -> Result<(), String> {
+        if self.kind() != config.kind {
+            return Err(format!("expected {}, found {}", config.kind, self.kind()));
+        }
+        if config.values.is_empty() {
+            return Ok(());
+        }
+        let disallowed = match self {
+            Self::List(items) => items
+                .iter()
+                .find(|item| !config.values.contains(item))
+                .cloned(),
+            value => Some(value.to_string()).filter(|text| !config.values.contains(text)),
+        };
+        match disallowed {
+            Some(value) => Err(format!(
+                "'{}' is not one of: {}",
+                value,
+                config.values.join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Boolean(value) => write!(f, "{value}"),
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Datetime(value) => write!(f, "{value}"),
+            Self::Text(value) => f.write_str(value),
+            Self::List(items) => f.write_str(&items.join(", ")),
+            Self::Table(table) => {
+                let entries: Vec<String> = table
+                    .iter()
+                    .map(|(key, value)| format!("{key} = {value}"))
+                    .collect();
+                write!(f, "{{ {} }}", entries.join(", "))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn declaration(kind: AttributeKind, values: &[&str]) -> AttributeConfig {
+        AttributeConfig {
+            kind,
+            values: values.iter().map(|s| s.to_string()).collect(),
+            ..AttributeConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_check_attribute_values() {
+        let level = declaration(AttributeKind::String, &["A", "B", "C"]);
+        assert!(AttributeValue::Text("B".to_string()).check(&level).is_ok());
+        let error = AttributeValue::Text("E".to_string())
+            .check(&level)
+            .unwrap_err();
+        assert_eq!(error, "'E' is not one of: A, B, C");
+        let error = AttributeValue::Integer(2).check(&level).unwrap_err();
+        assert_eq!(error, "expected string, found integer");
+
+        let platforms = declaration(AttributeKind::List, &["linux", "windows"]);
+        let list =
+            |items: &[&str]| AttributeValue::List(items.iter().map(|s| s.to_string()).collect());
+        assert!(list(&["linux"]).check(&platforms).is_ok());
+        assert!(list(&["linux", "macos"]).check(&platforms).is_err());
+
+        let priority = declaration(AttributeKind::Integer, &[]);
+        assert!(AttributeValue::Integer(7).check(&priority).is_ok());
+    }
+
+    #[test]
+    fn test_parse_attribute_values() {
+        #[derive(Deserialize)]
+        struct Table {
+            attributes: std::collections::BTreeMap<String, AttributeValue>,
+        }
+        let table: Table = toml::from_str(
+            "[attributes]\nowner = \"J. Smith\"\npriority = 2\ncritical = true\ntags = [\"a\", \"b\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            table.attributes["owner"],
+            AttributeValue::Text("J. Smith".to_string())
+        );
+        assert_eq!(table.attributes["priority"], AttributeValue::Integer(2));
+        assert_eq!(table.attributes["critical"], AttributeValue::Boolean(true));
+        assert_eq!(table.attributes["tags"].to_string(), "a, b");
+    }
+
+    #[test]
+    fn test_parse_float_datetime_and_table_values() {
+        #[derive(Deserialize)]
+        struct Table {
+            attributes: std::collections::BTreeMap<String, AttributeValue>,
+        }
+        let table: Table = toml::from_str(
+            "[attributes]\nmass_kg = 2.5\nreviewed = 2024-03-01\nlimits = { min = 0, max = 10 }\n",
+        )
+        .unwrap();
+        assert_eq!(table.attributes["mass_kg"], AttributeValue::Float(2.5));
+        assert_eq!(table.attributes["mass_kg"].kind(), AttributeKind::Float);
+        assert_eq!(table.attributes["reviewed"].kind(), AttributeKind::Datetime);
+        assert_eq!(table.attributes["reviewed"].to_string(), "2024-03-01");
+        assert_eq!(table.attributes["limits"].kind(), AttributeKind::Table);
+        assert_eq!(
+            table.attributes["limits"].to_string(),
+            "{ max = 10, min = 0 }"
+        );
+
+        let mass = declaration(AttributeKind::Float, &[]);
+        assert!(table.attributes["mass_kg"].check(&mass).is_ok());
+        let error = AttributeValue::Integer(2).check(&mass).unwrap_err();
+        assert_eq!(error, "expected float, found integer");
+    }
+}
@@ -0,0 +1,460 @@
+//! Requirements baselines
+//!
+//! A baseline is a named snapshot of every section with a `section_id`: its
+//! number, heading, normalized text and traced IDs. Baselines are written by
+//! `sysdoc baseline create` as JSON files under `baselines/` in the document
+//! root, meant to be committed, and compared with `sysdoc baseline diff` or with
+//! a generated "Changes since baseline" table.
+
+use super::blocks::MarkdownBlock;
+use super::text_run::TextRun;
+use super::types::Alignment;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Directory holding baselines, relative to the document root
+pub const BASELINE_DIR: &str = "baselines";
+
+/// Errors that can occur when reading or writing baselines
+#[derive(Error, Debug)]
+pub enum BaselineError {
+    #[error("Invalid baseline name '{0}': use letters, digits, '.', '-' and '_'")]
+    InvalidName(String),
+
+    #[error("Baseline '{0}' not found")]
+    NotFound(String),
+
+    #[error("Baseline '{0}' already exists")]
+    AlreadyExists(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// One section recorded in a baseline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineItem {
+    /// The section's unique identifier
+    pub section_id: String,
+    /// Section number at the time of the baseline (e.g., "3.2.1")
+    pub section_number: String,
+    /// Heading text
+    pub heading: String,
+    /// Body text with whitespace normalized and sysdoc blocks removed
+    pub text: String,
+    /// IDs the section traces to
+    pub traced_ids: Vec<String>,
+}
+
+/// A named snapshot of a document's identified sections
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    /// Baseline name (e.g., "v1.0" or "PDR")
+    pub name: String,
+    /// `document_id` of the document
+    pub document_id: String,
+    /// Identified sections, sorted by `section_id`
+    pub items: Vec<BaselineItem>,
+}
+
+impl Baseline {
+    /// Path of a baseline file
+    ///
+    /// # Returns
+    /// * `Ok(PathBuf)` - `<root>/baselines/<name>.json`
+    /// * `Err(BaselineError::InvalidName)` - The name could escape the baselines directory
+    pub fn path(root: &Path, name: &str) -> Result<PathBuf, BaselineError> {
+        let valid = !name.is_empty()
+            && !name.starts_with('.')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+        if !valid {
+            return Err(BaselineError::InvalidName(name.to_string()));
+        }
+        Ok(root.join(BASELINE_DIR).join(format!("{name}.json")))
+    }
+
+    /// Load a baseline by name
+    pub fn load(root: &Path, name: &str) -> Result<Self, BaselineError> {
+        let path = Self::path(root, name)?;
+        if !path.exists() {
+            return Err(BaselineError::NotFound(name.to_string()));
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Write the baseline under `baselines/`
+    ///
+    /// # Parameters
+    /// * `root` - Document root directory
+    /// * `force` - Overwrite an existing baseline with the same name
+    ///
+    /// # Returns
+    /// * `Ok(PathBuf)` - Path of the written file
+    /// * `Err(BaselineError)` - The name is invalid, the baseline exists, or writing failed
+    pub fn save(&self, root: &Path, force: bool) -> Result<PathBuf, BaselineError> {
+        let path = Self::path(root, &self.name)?;
+        if path.exists() && !force {
+            return Err(BaselineError::AlreadyExists(self.name.clone()));
+        }
+        std::fs::create_dir_all(root.join(BASELINE_DIR))?;
+        let mut content = serde_json::to_string_pretty(self)?;
+        content.push('\n');
+        std::fs::write(&path, content)?;
+        Ok(path)
+    }
+
+    /// Compare this baseline with a later one
+    ///
+    /// # Parameters
+    /// * `later` - The baseline (or current snapshot) to compare against
+    ///
+    /// # Returns
+    /// * The changes from this baseline to `later`, sorted by `section_id`
+    pub fn diff(&self, later: &Baseline) -> BaselineDiff {
+        let old: BTreeMap<&str, &BaselineItem> = self
+            .items
+            .iter()
+            .map(|item| (item.section_id.as_str(), item))
+            .collect();
+        let new: BTreeMap<&str, &BaselineItem> = later
+            .items
+            .iter()
+            .map(|item| (item.section_id.as_str(), item))
+            .collect();
+
+        let mut ids: Vec<&str> = old.keys().chain(new.keys()).copied().collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let changes = ids
+            .into_iter()
+            .filter_map(|id| BaselineChange::between(old.get(id).copied(), new.get(id).copied()))
+            .collect();
+
+        BaselineDiff {
+            from: self.name.clone(),
+            to: later.name.clone(),
+            changes,
+        }
+    }
+}
+
+/// How an item differs between two baselines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// The section_id is new
+    Added,
+    /// The section_id no longer exists
+    Removed,
+    /// The section was renumbered (it may also be modified)
+    Moved,
+    /// The heading, text or traces changed in place
+    Modified,
+}
+
+impl ChangeKind {
+    /// Display label of the change kind
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Added => "Added",
+            Self::Removed => "Removed",
+            Self::Moved => "Moved",
+            Self::Modified => "Modified",
+        }
+    }
+}
+
+/// A part of an item that changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangedField {
+    Heading,
+    Text,
+    Traces,
+}
+
+impl ChangedField {
+    /// Display label of the field
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Heading => "heading",
+            Self::Text => "text",
+            Self::Traces => "traces",
+        }
+    }
+}
+
+/// One changed item
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BaselineChange {
+    /// The section's unique identifier
+    pub section_id: String,
+    /// Kind of change
+    pub kind: ChangeKind,
+    /// Section number in the earlier baseline
+    pub old_number: Option<String>,
+    /// Section number in the later baseline
+    pub new_number: Option<String>,
+    /// Heading in the later baseline (or the earlier one if removed)
+    pub heading: String,
+    /// Parts of the item that changed (empty for added, removed and pure moves)
+    pub modified: Vec<ChangedField>,
+}
+
+impl BaselineChange {
+    /// Compare one item across two baselines; None if it is unchanged
+    fn between(old: Option<&BaselineItem>, new: Option<&BaselineItem>) -> Option<Self> {
+        let change = |kind, item: &BaselineItem, modified| Self {
+            section_id: item.section_id.clone(),
+            kind,
+            old_number: old.map(|item| item.section_number.clone()),
+            new_number: new.map(|item| item.section_number.clone()),
+            heading: item.heading.clone(),
+            modified,
+        };
+        match (old, new) {
+            (None, Some(new)) => Some(change(ChangeKind::Added, new, Vec::new())),
+            (Some(old), None) => Some(change(ChangeKind::Removed, old, Vec::new())),
+            (Some(old), Some(new)) => {
+                let modified: Vec<ChangedField> = [
+                    (ChangedField::Heading, old.heading != new.heading),
+                    (ChangedField::Text, old.text != new.text),
+                    (ChangedField::Traces, old.traced_ids != new.traced_ids),
+                ]
+                .into_iter()
+                .filter_map(|(field, changed)| changed.then_some(field))
+                .collect();
+                if old.section_number != new.section_number {
+                    Some(change(ChangeKind::Moved, new, modified))
+                } else if !modified.is_empty() {
+                    Some(change(ChangeKind::Modified, new, modified))
+                } else {
+                    None
+                }
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// Human-readable description, e.g. "Moved 1.2 -> 1.3, text modified"
+    pub fn description(&self) -> String {
+        let mut description = self.kind.label().to_string();
+        if self.kind == ChangeKind::Moved {
+            description.push_str(&format!(
+                " {} -> {}",
+                self.old_number.as_deref().unwrap_or_default(),
+                self.new_number.as_deref().unwrap_or_default()
+            ));
+        }
+        if !self.modified.is_empty() {
+            let fields: Vec<&str> = self.modified.iter().map(|field| field.label()).collect();
+            let separator = if self.kind == ChangeKind::Moved {
+                ", modified "
+            } else {
+                " "
+            };
+            description.push_str(separator);
+            description.push_str(&fields.join(", "));
+        }
+        description
+    }
+}
+
+/// Changes between two baselines
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BaselineDiff {
+    /// Name of the earlier baseline
+    pub from: String,
+    /// Name of the later baseline (or "current" for the working document)
+    pub to: String,
+    /// Changed items, sorted by `section_id`
+    pub changes: Vec<BaselineChange>,
+}
+
+impl BaselineDiff {
+    /// Number of changes of a kind
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.kind == kind)
+            .count()
+    }
+
+    /// Plain-text report, one line per change followed by a summary
+    pub fn to_text(&self) -> String {
+        let mut report = format!("Changes from '{}' to '{}':\n", self.from, self.to);
+        for change in &self.changes {
+            let number = change
+                .new_number
+                .as_ref()
+                .or(change.old_number.as_ref())
+                .map(String::as_str)
+                .unwrap_or_default();
+            report.push_str(&format!(
+                "  {:<9} {} ({} {}): {}\n",
+                change.kind.label(),
+                change.section_id,
+                number,
+                change.heading,
+                change.description()
+            ));
+        }
+        report.push_str(&format!(
+            "{} added, {} removed, {} moved, {} modified\n",
+            self.count(ChangeKind::Added),
+            self.count(ChangeKind::Removed),
+            self.count(ChangeKind::Moved),
+            self.count(ChangeKind::Modified)
+        ));
+        report
+    }
+
+    /// JSON report of the changes
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Generated "Changes since baseline" table
+    ///
+    /// Columns are ID, Section, Heading and Change. A document without
+    /// changes gets a single "No changes" row.
+    pub fn to_table(&self) -> MarkdownBlock {
+        let cell = |text: &str| vec![TextRun::new(text.to_string())];
+        let mut rows: Vec<Vec<Vec<TextRun>>> = self
+            .changes
+            .iter()
+            .map(|change| {
+                let number = change
+                    .new_number
+                    .as_ref()
+                    .or(change.old_number.as_ref())
+                    .map(String::as_str)
+                    .unwrap_or_default();
+                vec![
+                    cell(&change.section_id),
+                    cell(number),
+                    cell(&change.heading),
+                    cell(&change.description()),
+                ]
+            })
+            .collect();
+        if rows.is_empty() {
+            rows.push(vec![cell("No changes"), cell(""), cell(""), cell("")]);
+        }
+
+        MarkdownBlock::InlineTable {
+            alignments: vec![Alignment::None; 4],
+            headers: vec![cell("ID"), cell("Section"), cell("Heading"), cell("Change")],
+            rows,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, number: &str, text: &str, traced: &[&str]) -> BaselineItem {
+        BaselineItem {
+            section_id: id.to_string(),
+            section_number: number.to_string(),
+            heading: format!("{id} heading"),
+            text: text.to_string(),
+            traced_ids: traced.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn baseline(name: &str, items: Vec<BaselineItem>) -> Baseline {
+        Baseline {
+            name: name.to_string(),
+            document_id: "SDD-001".to_string(),
+            items,
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = baseline(
+            "v1.0",
+            vec![
+                item("SDD-1", "1.1", "Same.", &[]),
+                item("SDD-2", "1.2", "Old text.", &["SRS-1"]),
+                item("SDD-3", "1.3", "Moves.", &[]),
+                item("SDD-4", "1.4", "Gone.", &[]),
+            ],
+        );
+        let new = baseline(
+            "current",
+            vec![
+                item("SDD-1", "1.1", "Same.", &[]),
+                item("SDD-2", "1.2", "New text.", &["SRS-1", "SRS-2"]),
+                item("SDD-3", "2.1", "Moves.", &[]),
+                item("SDD-5", "1.4", "Fresh.", &[]),
+            ],
+        );
+
+        let diff = old.diff(&new);
+        let summary: Vec<(&str, ChangeKind, String)> = diff
+            .changes
+            .iter()
+            .map(|c| (c.section_id.as_str(), c.kind, c.description()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "SDD-2",
+                    ChangeKind::Modified,
+                    "Modified text, traces".to_string()
+                ),
+                ("SDD-3", ChangeKind::Moved, "Moved 1.3 -> 2.1".to_string()),
+                ("SDD-4", ChangeKind::Removed, "Removed".to_string()),
+                ("SDD-5", ChangeKind::Added, "Added".to_string()),
+            ]
+        );
+        assert!(diff
+            .to_text()
+            .ends_with("1 added, 1 removed, 1 moved, 1 modified\n"));
+
+        let json: serde_json::Value = serde_json::from_str(&diff.to_json().unwrap()).unwrap();
+        assert_eq!(json["changes"][0]["kind"], "modified");
+        assert_eq!(json["changes"][0]["modified"][1], "traces");
+
+        let MarkdownBlock::InlineTable { rows, .. } = diff.to_table() else {
+            panic!("Expected a table");
+        };
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1][1][0].text, "2.1");
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let root = std::env::temp_dir().join(format!("sysdoc-baseline-{}", std::process::id()));
+        let snapshot = baseline("v1.0", vec![item("SDD-1", "1", "Text.", &["SRS-1"])]);
+        let path = snapshot.save(&root, false).unwrap();
+        assert!(path.ends_with("baselines/v1.0.json"));
+        assert_eq!(Baseline::load(&root, "v1.0").unwrap(), snapshot);
+        assert!(matches!(
+            snapshot.save(&root, false),
+            Err(BaselineError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            Baseline::load(&root, "v2.0"),
+            Err(BaselineError::NotFound(_))
+        ));
+        assert!(matches!(
+            Baseline::load(&root, "../v1.0"),
+            Err(BaselineError::InvalidName(_))
+        ));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -4,7 +4,7 @@
 //! at the block level (paragraphs, headings, lists, code blocks, etc.)
 
 use super::text_run::TextRun;
-use super::types::Alignment;
+use super::types::{Alignment, SourceSpan};
 use std::path::PathBuf;
 
 /// Block-level markdown element
@@ -37,6 +37,8 @@ pub enum MarkdownBlock {
         format: super::image::ImageFormat,
         /// Whether the image file exists on disk
         exists: bool,
+        /// Location of the image reference (`![alt](path)`) in the source file
+        span: SourceSpan,
     },
 
     /// A code block
@@ -86,6 +88,8 @@ pub enum MarkdownBlock {
         exists: bool,
         /// Parsed CSV data (headers + rows) if loaded successfully
         data: Option<Vec<Vec<String>>>,
+        /// Location of the table reference (`[text](path.csv)`) in the source file
+        span: SourceSpan,
     },
 
     /// A horizontal rule (thematic break)
@@ -113,9 +117,49 @@ pub enum MarkdownBlock {
         content: Option<String>,
         /// Whether the file exists on disk
         exists: bool,
+        /// Location of the `include_file` metadata field in the source file
+        span: SourceSpan,
     },
 }
 
+impl MarkdownBlock {
+    /// Append the prose of the block (and the blocks it contains) to `texts`
+    ///
+    /// Each heading, paragraph and table cell (including CSV cells) becomes
+    /// one entry. Runs rejected by `include` are replaced by a space so the
+    /// words around them stay apart. Code blocks, included files, images and
+    /// HTML are left out.
+    ///
+    /// # Parameters
+    /// * `include` - Whether a text run belongs to the prose (e.g. `!run.code`)
+    /// * `texts` - Collected text, appended to
+    pub fn collect_text(&self, include: &impl Fn(&TextRun) -> bool, texts: &mut Vec<String>) {
+        let runs_text = |runs: &[TextRun]| {
+            runs.iter()
+                .map(|run| if include(run) { run.text.as_str() } else { " " })
+                .collect::<String>()
+        };
+        match self {
+            Self::Heading { runs, .. } | Self::Paragraph(runs) => texts.push(runs_text(runs)),
+            Self::BlockQuote(blocks) => blocks
+                .iter()
+                .for_each(|block| block.collect_text(include, texts)),
+            Self::List { items, .. } => items
+                .iter()
+                .flat_map(|item| item.content.iter())
+                .for_each(|block| block.collect_text(include, texts)),
+            Self::InlineTable { headers, rows, .. } => {
+                let cells = headers.iter().chain(rows.iter().flatten());
+                texts.extend(cells.map(|cell| runs_text(cell)));
+            }
+            Self::CsvTable {
+                data: Some(data), ..
+            } => texts.extend(data.iter().flatten().cloned()),
+            _ => {}
+        }
+    }
+}
+
 /// A list item, which may contain multiple blocks
 #[derive(Debug, Clone)]
 pub struct ListItem {
@@ -0,0 +1,332 @@
+//! Upstream requirement catalogs
+//!
+//! Catalogs are CSV, JSON or ReqIF files declared in sysdoc.toml that list the IDs a
+//! document may trace to, together with their titles and text. They are loaded
+//! once during parsing and used to validate `traced_ids` and to populate trace
+//! tables with upstream requirement details.
+
+use super::reqif::{ReqifDocument, ReqifError};
+use crate::document_config::{CatalogConfig, CatalogFormat};
+use crate::text::edit_distance;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// A single requirement (or other trace target) listed in a catalog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogEntry {
+    /// Requirement identifier
+    pub id: String,
+    /// Requirement title (may be empty)
+    pub title: String,
+    /// Requirement text (may be empty)
+    pub text: String,
+    /// Catalog file the entry was loaded from (relative to document root)
+    pub catalog: PathBuf,
+}
+
+/// All catalog entries available as trace targets, keyed by ID
+#[derive(Debug, Clone, Default)]
+pub struct TraceCatalog {
+    /// Entries keyed by requirement ID
+    entries: BTreeMap<String, CatalogEntry>,
+}
+
+/// Errors that can occur when loading a catalog
+#[derive(Error, Debug)]
+pub enum CatalogError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("ReqIF error: {0}")]
+    Reqif(#[from] ReqifError),
+
+    #[error(
+        "Cannot infer catalog format from extension; set `format` to \"csv\", \"json\" or \"reqif\""
+    )]
+    UnknownFormat,
+
+    #[error("Column '{0}' not found in catalog")]
+    MissingColumn(String),
+
+    #[error("Expected a JSON array of objects")]
+    InvalidJsonStructure,
+
+    #[error("Duplicate ID '{id}' (also defined in '{first_catalog}')", first_catalog = first_catalog.display())]
+    DuplicateId { id: String, first_catalog: PathBuf },
+}
+
+impl TraceCatalog {
+    /// Load all catalogs declared in the document configuration
+    ///
+    /// # Parameters
+    /// * `root` - Document root directory (catalog paths are relative to it)
+    /// * `configs` - Catalog declarations from sysdoc.toml
+    ///
+    /// # Returns
+    /// * `Ok(TraceCatalog)` - All entries from all catalogs
+    /// * `Err((PathBuf, CatalogError))` - The catalog that failed to load and why
+    pub fn load(root: &Path, configs: &[CatalogConfig]) -> Result<Self, (PathBuf, CatalogError)> {
+        let mut catalog = Self::default();
+        for config in configs {
+            let path = PathBuf::from(&config.path);
+            let entries = load_catalog_entries(root, config).map_err(|e| (path.clone(), e))?;
+            for entry in entries {
+                catalog.insert(entry).map_err(|e| (path.clone(), e))?;
+            }
+        }
+        Ok(catalog)
+    }
+
+    /// Add an entry, rejecting IDs already defined by another entry
+    pub fn insert(&mut self, entry: CatalogEntry) -> Result<(), CatalogError> {
+        if let Some(existing) = self.entries.get(&entry.id) {
+            return Err(CatalogError::DuplicateId {
+                id: entry.id,
+                first_catalog: existing.catalog.clone(),
+            });
+        }
+        self.entries.insert(entry.id.clone(), entry);
+        Ok(())
+    }
+
+    /// Look up an entry by ID
+    pub fn get(&self, id: &str) -> Option<&CatalogEntry> {
+        self.entries.get(id)
+    }
+
+    /// Check whether an ID is defined in any catalog
+    pub fn contains(&self, id: &str) -> bool {
+        self.entries.contains_key(id)
+    }
+
+    /// Iterate over all entries in ID order
+    pub fn entries(&self) -> impl Iterator<Item = &CatalogEntry> {
+        self.entries.values()
+    }
+
+    /// Check whether the catalog has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Find the catalog ID closest to `id`, for "did you mean" suggestions
+    ///
+    /// Only IDs within an edit distance of a third of the ID's length are considered.
+    pub fn closest_id<'a>(&'a self, id: &str, extra: &[&'a str]) -> Option<&'a str> {
+        let max_distance = (id.chars().count() / 3).max(1);
+        self.entries
+            .keys()
+            .map(String::as_str)
+            .chain(extra.iter().copied())
+            .filter_map(|candidate| Some((edit_distance(id, candidate, max_distance)?, candidate)))
+            .min()
+            .map(|(_, candidate)| candidate)
+    }
+}
+
+/// Load the entries of a single catalog file
+fn load_catalog_entries(
+    root: &Path,
+    config: &CatalogConfig,
+) -> Result<Vec<CatalogEntry>, CatalogError> {
+    let relative_path = PathBuf::from(&config.path);
+    let absolute_path = root.join(&relative_path);
+
+    let format = match config.format {
+        Some(format) => format,
+        None => CatalogFormat::from_path(&relative_path).ok_or(CatalogError::UnknownFormat)?,
+    };
+
+    let rows = match format {
+        CatalogFormat::Csv => read_csv_rows(&absolute_path, config)?,
+        CatalogFormat::Json => read_json_rows(&absolute_path, config)?,
+        CatalogFormat::Reqif => read_reqif_rows(&absolute_path)?,
+    };
+
+    Ok(rows
+        .into_iter()
+        .filter(|(id, _, _)| !id.is_empty())
+        .map(|(id, title, text)| CatalogEntry {
+            id,
+            title,
+            text,
+            catalog: relative_path.clone(),
+        })
+        .collect())
+}
+
+/// Read (id, title, text) rows from a CSV catalog
+///
+/// The ID column is required; missing title or text columns produce empty values.
+fn read_csv_rows(
+    path: &Path,
+    config: &CatalogConfig,
+) -> Result<Vec<(String, String, String)>, CatalogError> {
+    let mut reader = csv::Reader::from_path(path)?;
+    let headers = reader.headers()?.clone();
+    let column_index = |name: &str| headers.iter().position(|h| h.trim() == name);
+
+    let id_index = column_index(&config.id_column)
+        .ok_or_else(|| CatalogError::MissingColumn(config.id_column.clone()))?;
+    let title_index = column_index(&config.title_column);
+    let text_index = column_index(&config.text_column);
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|i| record.get(i))
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        rows.push((field(Some(id_index)), field(title_index), field(text_index)));
+    }
+    Ok(rows)
+}
+
+/// Read (id, title, text) rows from a JSON catalog (an array of objects)
+fn read_json_rows(
+    path: &Path,
+    config: &CatalogConfig,
+) -> Result<Vec<(String, String, String)>, CatalogError> {
+    let content = std::fs::read_to_string(path)?;
+    let value: serde_json::Value = serde_json::from_str(&content)?;
+    let items = value.as_array().ok_or(CatalogError::InvalidJsonStructure)?;
+
+    items
+        .iter()
+        .map(|item| {
+            let object = item.as_object().ok_or(CatalogError::InvalidJsonStructure)?;
+            let field = |name: &str| object.get(name).map(json_value_to_string);
+            let id = field(&config.id_column)
+                .ok_or_else(|| CatalogError::MissingColumn(config.id_column.clone()))?;
+            Ok((
+                id,
+                field(&config.title_column).unwrap_or_default(),
+                field(&config.text_column).unwrap_or_default(),
+            ))
+        })
+        .collect()
+}
+
+/// Read (id, title, text) rows from a ReqIF file (one per spec object)
+///
+/// The column settings are ignored; IDs come from `ReqIF.ForeignID` when present.
+fn read_reqif_rows(path: &Path) -> Result<Vec<(String, String, String)>, CatalogError> {
+    let content = std::fs::read_to_string(path)?;
+    let reqif = ReqifDocument::parse(&content)?;
+    Ok(reqif
+        .objects()
+        .map(|object| (object.id.clone(), object.title.clone(), object.text.clone()))
+        .collect())
+}
+
+/// Convert a scalar JSON value to its display string
+fn json_value_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.trim().to_string(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog_config(path: &str) -> CatalogConfig {
+        toml::from_str(&format!("path = \"{}\"", path)).unwrap()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("sysdoc-catalog-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_load_csv_catalog() {
+        let dir = temp_dir("csv");
+        std::fs::write(
+            dir.join("srs.csv"),
+            "id,title,text\nSRS-REQ-001,Parse input,The system shall parse input.\nSRS-REQ-002,Report,\n",
+        )
+        .unwrap();
+
+        let catalog = TraceCatalog::load(&dir, &[catalog_config("srs.csv")]).unwrap();
+        let entry = catalog.get("SRS-REQ-001").unwrap();
+        assert_eq!(entry.title, "Parse input");
+        assert_eq!(entry.text, "The system shall parse input.");
+        assert_eq!(entry.catalog, PathBuf::from("srs.csv"));
+        assert!(catalog.contains("SRS-REQ-002"));
+        assert!(!catalog.contains("SRS-REQ-01"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_load_json_catalog_with_custom_columns() {
+        let dir = temp_dir("json");
+        std::fs::write(
+            dir.join("sss.json"),
+            r#"[{"key": "SSS-1", "name": "Power", "description": "Operate on 28V."}]"#,
+        )
+        .unwrap();
+
+        let config: CatalogConfig = toml::from_str(
+            r#"
+path = "sss.json"
+id_column = "key"
+title_column = "name"
+text_column = "description"
+"#,
+        )
+        .unwrap();
+
+        let catalog = TraceCatalog::load(&dir, &[config]).unwrap();
+        let entry = catalog.get("SSS-1").unwrap();
+        assert_eq!(entry.title, "Power");
+        assert_eq!(entry.text, "Operate on 28V.");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_missing_id_column_is_an_error() {
+        let dir = temp_dir("missing-column");
+        std::fs::write(dir.join("bad.csv"), "key,title\nA,B\n").unwrap();
+
+        let result = TraceCatalog::load(&dir, &[catalog_config("bad.csv")]);
+        assert!(matches!(result, Err((_, CatalogError::MissingColumn(_)))));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_closest_id_suggestion() {
+        let mut catalog = TraceCatalog::default();
+        for id in ["SRS-REQ-001", "SRS-REQ-002", "SRS-REQ-100"] {
+            catalog
+                .insert(CatalogEntry {
+                    id: id.to_string(),
+                    title: String::new(),
+                    text: String::new(),
+                    catalog: PathBuf::from("srs.csv"),
+                })
+                .unwrap();
+        }
+
+        assert_eq!(catalog.closest_id("SRS-REQ-01", &[]), Some("SRS-REQ-001"));
+        assert_eq!(catalog.closest_id("ICD-9", &[]), None);
+    }
+}
@@ -0,0 +1,187 @@
+//! Source code trace annotations
+//!
+//! Scans the source directories declared in `[code_trace]` for comments such as
+//! `// @implements SDD-3.2.1` and records where each section ID is referenced.
+//! The references are validated against the document's `section_id`s and shown
+//! in generated "Implemented By" tables.
+
+use crate::document_config::CodeTraceConfig;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use walkdir::WalkDir;
+
+/// A section ID referenced from a source code annotation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeReference {
+    /// The referenced section ID
+    pub id: String,
+    /// Source file, as the configured path joined with the path below it
+    pub file: PathBuf,
+    /// Line number of the annotation (1-indexed)
+    pub line: usize,
+}
+
+/// Errors that can occur when scanning source code
+#[derive(Error, Debug)]
+pub enum CodeTraceError {
+    #[error("Invalid code_trace pattern '{pattern}': {source}")]
+    InvalidPattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+
+    #[error("Code trace path not found: {path}", path = .0.display())]
+    MissingPath(PathBuf),
+
+    #[error("Error scanning {path}: {source}", path = .0.display(), source = .1)]
+    Io(PathBuf, #[source] std::io::Error),
+}
+
+/// Scan the configured source paths for trace annotations
+///
+/// Files that are not valid UTF-8 are skipped. Results are ordered by file
+/// path and line number, so the output is stable across runs.
+///
+/// # Parameters
+/// * `root` - Document root directory (configured paths are relative to it)
+/// * `config` - The `[code_trace]` configuration
+///
+/// # Returns
+/// * `Ok(Vec<CodeReference>)` - One entry per (annotation, ID)
+/// * `Err(CodeTraceError)` - A pattern is invalid or a path cannot be read
+pub fn scan_code_references(
+    root: &Path,
+    config: &CodeTraceConfig,
+) -> Result<Vec<CodeReference>, CodeTraceError> {
+    let patterns = config
+        .patterns
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern).map_err(|source| CodeTraceError::InvalidPattern {
+                pattern: pattern.clone(),
+                source,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut references = Vec::new();
+    for configured in &config.paths {
+        let base = root.join(configured);
+        if !base.exists() {
+            return Err(CodeTraceError::MissingPath(PathBuf::from(configured)));
+        }
+
+        for entry in WalkDir::new(&base).sort_by_file_name() {
+            let entry = entry.map_err(|e| CodeTraceError::Io(base.clone(), e.into()))?;
+            if !entry.file_type().is_file() || !has_extension(entry.path(), &config.extensions) {
+                continue;
+            }
+            let Ok(content) = std::fs::read_to_string(entry.path()) else {
+                continue;
+            };
+            let relative = entry.path().strip_prefix(&base).unwrap_or(entry.path());
+            let file = Path::new(configured).join(relative);
+            references.extend(scan_content(&content, &file, &patterns));
+        }
+    }
+    Ok(references)
+}
+
+/// Find all annotations in a file's content
+fn scan_content(content: &str, file: &Path, patterns: &[Regex]) -> Vec<CodeReference> {
+    content
+        .lines()
+        .enumerate()
+        .flat_map(|(index, line)| {
+            patterns
+                .iter()
+                .flat_map(move |pattern| pattern.captures_iter(line))
+                .filter_map(|captures| captures.get(1))
+                .flat_map(|ids| {
+                    ids.as_str()
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .map(|id| id.trim_end_matches('.'))
+                        .filter(|id| !id.is_empty())
+                        .map(str::to_string)
+                        .collect::<Vec<_>>()
+                })
+                .map(move |id| CodeReference {
+                    id,
+                    file: file.to_path_buf(),
+                    line: index + 1,
+                })
+        })
+        .collect()
+}
+
+/// Check whether a file should be scanned given the configured extensions
+fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    extensions.is_empty()
+        || path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| extensions.iter().any(|wanted| wanted == ext))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(paths: &[&str]) -> CodeTraceConfig {
+        let paths = paths
+            .iter()
+            .map(|p| format!("\"{}\"", p))
+            .collect::<Vec<_>>()
+            .join(", ");
+        toml::from_str(&format!("paths = [{}]\nextensions = [\"rs\"]", paths)).unwrap()
+    }
+
+    #[test]
+    fn test_scan_annotations() {
+        let dir = std::env::temp_dir().join(format!("sysdoc-code-trace-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("code/parser")).unwrap();
+        std::fs::write(
+            dir.join("code/parser/lexer.rs"),
+            "// @implements SDD-3.2.1 for the lexer.\nfn lex() {}\n/* @implements SDD-3.2.2, SDD-3.2.3 */\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("code/notes.txt"), "@implements SDD-9\n").unwrap();
+
+        let references = scan_code_references(&dir, &config(&["code"])).unwrap();
+        let found: Vec<(&str, String, usize)> = references
+            .iter()
+            .map(|r| (r.id.as_str(), r.file.display().to_string(), r.line))
+            .collect();
+        let file = Path::new("code").join("parser").join("lexer.rs");
+        let file = file.display().to_string();
+        assert_eq!(
+            found,
+            vec![
+                ("SDD-3.2.1", file.clone(), 1),
+                ("SDD-3.2.2", file.clone(), 3),
+                ("SDD-3.2.3", file, 3),
+            ]
+        );
+
+        assert!(matches!(
+            scan_code_references(&dir, &config(&["missing"])),
+            Err(CodeTraceError::MissingPath(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_custom_pattern() {
+        let pattern = Regex::new(r"Satisfies:\s*\[([^\]]*)\]").unwrap();
+        let references = scan_content(
+            "# Satisfies: [REQ-1 REQ-2]\n",
+            Path::new("build.py"),
+            &[pattern],
+        );
+        assert_eq!(references.len(), 2);
+        assert_eq!(references[1].id, "REQ-2");
+    }
+}
@@ -0,0 +1,445 @@
+//! Traceability coverage analysis
+//!
+//! Computes forward coverage (upstream requirements traced by at least one
+//! section) and backward coverage (sections tracing to at least one ID) from
+//! section metadata, requirement catalogs, and upstream projects.
+
+use super::SourceModel;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+/// Options controlling which IDs are analysed
+#[derive(Debug, Clone, Default)]
+pub struct CoverageOptions {
+    /// Treat local sections whose `section_id` starts with this prefix as requirements
+    pub requirement_prefix: Option<String>,
+    /// Only analyse local sections whose `section_id` starts with this prefix
+    pub section_prefix: Option<String>,
+    /// Items with more links than this are reported as over-traced
+    pub over_trace_threshold: usize,
+}
+
+/// Covered/total counts for one direction of coverage
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CoverageStats {
+    /// Number of items considered
+    pub total: usize,
+    /// Number of items with at least one link
+    pub covered: usize,
+    /// Percentage of items covered (None when there are no items)
+    pub percent: Option<f64>,
+}
+
+impl CoverageStats {
+    fn new(total: usize, covered: usize) -> Self {
+        let percent = (total > 0).then(|| {
+            // Counts are far below 2^52, so the conversion is exact
+            #[allow(clippy::cast_precision_loss)]
+            let ratio = covered as f64 / total as f64;
+            ratio * 100.0
+        });
+        Self {
+            total,
+            covered,
+            percent,
+        }
+    }
+
+    /// The percentage with one decimal, or `N/A` when there are no items
+    pub fn percent_text(&self) -> String {
+        self.percent
+            .map_or_else(|| "N/A".to_string(), |percent| format!("{percent:.1}%"))
+    }
+}
+
+/// An item and the IDs linked to it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CoverageItem {
+    /// Requirement ID or section ID
+    pub id: String,
+    /// IDs linked to the item (tracing sections for requirements, traced IDs for sections)
+    pub links: Vec<String>,
+}
+
+/// Result of a traceability coverage analysis
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverageReport {
+    /// Upstream requirements traced by at least one section
+    pub forward: CoverageStats,
+    /// Sections tracing to at least one ID
+    pub backward: CoverageStats,
+    /// Requirements not traced by any section
+    pub uncovered_requirements: Vec<String>,
+    /// Sections that trace to nothing
+    pub untraced_sections: Vec<String>,
+    /// Requirements traced by more sections than the threshold
+    pub over_traced_requirements: Vec<CoverageItem>,
+    /// Sections tracing to more IDs than the threshold
+    pub over_traced_sections: Vec<CoverageItem>,
+    /// Threshold used for over-trace detection
+    pub over_trace_threshold: usize,
+    /// Every requirement with the sections tracing to it
+    #[serde(skip)]
+    requirements: Vec<CoverageItem>,
+    /// Every section with the IDs it traces to
+    #[serde(skip)]
+    sections: Vec<CoverageItem>,
+}
+
+impl CoverageReport {
+    /// Analyse the traceability of a parsed source model
+    ///
+    /// Requirements are the entries of all catalogs, the `section_id`s of all
+    /// upstream projects, and local sections matching `requirement_prefix`.
+    /// Sections are the remaining local sections with a `section_id`.
+    pub fn analyze(model: &SourceModel, options: &CoverageOptions) -> Self {
+        let has_prefix =
+            |id: &str, prefix: &Option<String>| prefix.as_ref().is_none_or(|p| id.starts_with(p));
+        let is_local_requirement = |id: &str| {
+            options
+                .requirement_prefix
+                .as_ref()
+                .is_some_and(|p| id.starts_with(p.as_str()))
+        };
+
+        let traces = model.collect_all_section_traceability();
+
+        let mut requirement_links: BTreeMap<String, Vec<String>> = model
+            .catalog
+            .entries()
+            .map(|entry| entry.id.clone())
+            .chain(
+                model
+                    .upstreams
+                    .iter()
+                    .flat_map(|upstream| upstream.section_ids().map(str::to_string)),
+            )
+            .chain(
+                traces
+                    .iter()
+                    .map(|trace| trace.section_id.clone())
+                    .filter(|id| is_local_requirement(id)),
+            )
+            .map(|id| (id, Vec::new()))
+            .collect();
+
+        let sections: Vec<CoverageItem> = traces
+            .iter()
+            .filter(|trace| !is_local_requirement(&trace.section_id))
+            .filter(|trace| has_prefix(&trace.section_id, &options.section_prefix))
+            .map(|trace| {
+                let mut links = trace.traced_ids.clone();
+                links.sort();
+                links.dedup();
+                CoverageItem {
+                    id: trace.section_id.clone(),
+                    links,
+                }
+            })
+            .collect();
+
+        let section_links = sections.iter().flat_map(|section| {
+            section
+                .links
+                .iter()
+                .map(move |traced_id| (traced_id, &section.id))
+        });
+        for (traced_id, section_id) in section_links {
+            if let Some(links) = requirement_links.get_mut(traced_id) {
+                links.push(section_id.clone());
+            }
+        }
+
+        let requirements: Vec<CoverageItem> = requirement_links
+            .into_iter()
+            .map(|(id, links)| CoverageItem { id, links })
+            .collect();
+
+        let threshold = options.over_trace_threshold;
+        let unlinked = |items: &[CoverageItem]| -> Vec<String> {
+            items
+                .iter()
+                .filter(|item| item.links.is_empty())
+                .map(|item| item.id.clone())
+                .collect()
+        };
+        let over_traced = |items: &[CoverageItem]| -> Vec<CoverageItem> {
+            items
+                .iter()
+                .filter(|item| item.links.len() > threshold)
+                .cloned()
+                .collect()
+        };
+
+        let uncovered_requirements = unlinked(&requirements);
+        let untraced_sections = unlinked(&sections);
+
+        Self {
+            forward: CoverageStats::new(
+                requirements.len(),
+                requirements.len() - uncovered_requirements.len(),
+            ),
+            backward: CoverageStats::new(sections.len(), sections.len() - untraced_sections.len()),
+            over_traced_requirements: over_traced(&requirements),
+            over_traced_sections: over_traced(&sections),
+            uncovered_requirements,
+            untraced_sections,
+            over_trace_threshold: threshold,
+            requirements,
+            sections,
+        }
+    }
+
+    /// Render the report as human-readable text
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "Forward coverage:  {} ({}/{} requirements traced)",
+            self.forward.percent_text(),
+            self.forward.covered,
+            self.forward.total
+        );
+        let _ = writeln!(
+            out,
+            "Backward coverage: {} ({}/{} sections tracing)",
+            self.backward.percent_text(),
+            self.backward.covered,
+            self.backward.total
+        );
+
+        write_id_list(
+            &mut out,
+            "Requirements not traced by any section",
+            &self.uncovered_requirements,
+        );
+        write_id_list(
+            &mut out,
+            "Sections that trace to nothing",
+            &self.untraced_sections,
+        );
+        write_over_traced(
+            &mut out,
+            &format!(
+                "Requirements traced by more than {} sections",
+                self.over_trace_threshold
+            ),
+            &self.over_traced_requirements,
+        );
+        write_over_traced(
+            &mut out,
+            &format!(
+                "Sections tracing to more than {} IDs",
+                self.over_trace_threshold
+            ),
+            &self.over_traced_sections,
+        );
+        out
+    }
+
+    /// Render the report as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Render the report as CSV with one row per requirement and section
+    ///
+    /// Columns: `kind`, `id`, `status`, `link_count`, `links`. The status is
+    /// `covered`, `uncovered` or `over_traced`; links are separated by `;`.
+    pub fn to_csv(&self) -> Result<String, csv::Error> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(["kind", "id", "status", "link_count", "links"])?;
+
+        let rows = self
+            .requirements
+            .iter()
+            .map(|item| ("requirement", item))
+            .chain(self.sections.iter().map(|item| ("section", item)));
+        for (kind, item) in rows {
+            let status = if item.links.is_empty() {
+                "uncovered"
+            } else if item.links.len() > self.over_trace_threshold {
+                "over_traced"
+            } else {
+                "covered"
+            };
+            writer.write_record([
+                kind,
+                &item.id,
+                status,
+                &item.links.len().to_string(),
+                &item.links.join(";"),
+            ])?;
+        }
+
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| csv::Error::from(e.into_error()))?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+/// Append a titled list of IDs to a text report (omitted when empty)
+fn write_id_list(out: &mut String, title: &str, ids: &[String]) {
+    if ids.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "\n{} ({}):", title, ids.len());
+    for id in ids {
+        let _ = writeln!(out, "  - {}", id);
+    }
+}
+
+/// Append a titled list of over-traced items to a text report (omitted when empty)
+fn write_over_traced(out: &mut String, title: &str, items: &[CoverageItem]) {
+    if items.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "\n{} ({}):", title, items.len());
+    for item in items {
+        let _ = writeln!(
+            out,
+            "  - {} ({}): {}",
+            item.id,
+            item.links.len(),
+            item.links.join(", ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_model::catalog::CatalogEntry;
+    use crate::source_model::{MarkdownSource, SectionNumber};
+    use std::path::{Path, PathBuf};
+
+    fn test_model() -> SourceModel {
+        let config = toml::from_str(
+            r#"
+document_id = "SDD-001"
+document_title = "Design"
+document_type = "SDD"
+document_standard = "DI-IPSC-81435B"
+document_template = "sdd-standard-v1"
+document_owner = { name = "Owner", email = "owner@example.com" }
+document_approver = { name = "Approver", email = "approver@example.com" }
+"#,
+        )
+        .unwrap();
+        let mut model = SourceModel::new(PathBuf::from("/test"), config);
+        for id in ["SRS-001", "SRS-002", "SRS-003"] {
+            model
+                .catalog
+                .insert(CatalogEntry {
+                    id: id.to_string(),
+                    title: String::new(),
+                    text: String::new(),
+                    catalog: PathBuf::from("srs.csv"),
+                })
+                .unwrap();
+        }
+
+        let mut source = MarkdownSource {
+            path: PathBuf::from("src/01_design.md"),
+            absolute_path: PathBuf::from("/test/src/01_design.md"),
+            section_number: SectionNumber::parse("01").unwrap(),
+            title: String::new(),
+            raw_content: r#"# Design
+
+```sysdoc
+section_id = "SDD-001"
+traced_ids = ["SRS-001", "SRS-002"]
+```
+
+## Parser
+
+```sysdoc
+section_id = "SDD-002"
+traced_ids = ["SRS-001"]
+```
+
+## Notes
+
+```sysdoc
+section_id = "SDD-003"
+```
+"#
+            .to_string(),
+            sections: Vec::new(),
+        };
+        source.parse(Path::new("/test")).unwrap();
+        model.markdown_files.push(source);
+        model
+    }
+
+    #[test]
+    fn test_coverage_percentages_and_orphans() {
+        let options = CoverageOptions {
+            over_trace_threshold: 1,
+            ..CoverageOptions::default()
+        };
+        let report = CoverageReport::analyze(&test_model(), &options);
+
+        assert_eq!(report.forward.total, 3);
+        assert_eq!(report.forward.covered, 2);
+        assert!(report
+            .forward
+            .percent
+            .is_some_and(|percent| (percent - 66.666).abs() < 0.01));
+        assert_eq!(report.forward.percent_text(), "66.7%");
+        assert_eq!(report.backward, CoverageStats::new(3, 2));
+        assert_eq!(report.uncovered_requirements, vec!["SRS-003"]);
+        assert_eq!(report.untraced_sections, vec!["SDD-003"]);
+        assert_eq!(report.over_traced_requirements[0].id, "SRS-001");
+        assert_eq!(
+            report.over_traced_requirements[0].links,
+            vec!["SDD-001", "SDD-002"]
+        );
+        assert_eq!(report.over_traced_sections[0].id, "SDD-001");
+    }
+
+    #[test]
+    fn test_coverage_csv_rows() {
+        let options = CoverageOptions {
+            over_trace_threshold: 5,
+            ..CoverageOptions::default()
+        };
+        let csv = CoverageReport::analyze(&test_model(), &options)
+            .to_csv()
+            .unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "kind,id,status,link_count,links");
+        assert_eq!(lines[1], "requirement,SRS-001,covered,2,SDD-001;SDD-002");
+        assert_eq!(lines[3], "requirement,SRS-003,uncovered,0,");
+        assert_eq!(lines[6], "section,SDD-003,uncovered,0,");
+    }
+
+    #[test]
+    fn test_local_requirements_by_prefix() {
+        let mut model = test_model();
+        model.catalog = Default::default();
+        let options = CoverageOptions {
+            requirement_prefix: Some("SDD-003".to_string()),
+            over_trace_threshold: 5,
+            ..CoverageOptions::default()
+        };
+        let report = CoverageReport::analyze(&model, &options);
+        assert_eq!(report.forward.total, 1);
+        assert_eq!(report.uncovered_requirements, vec!["SDD-003"]);
+        assert_eq!(report.backward.total, 2);
+    }
+
+    #[test]
+    fn test_coverage_without_requirements() {
+        let mut model = test_model();
+        model.catalog = Default::default();
+        let report = CoverageReport::analyze(&model, &CoverageOptions::default());
+        assert_eq!(report.forward, CoverageStats::new(0, 0));
+        assert_eq!(report.forward.percent, None);
+        assert!(report
+            .to_text()
+            .starts_with("Forward coverage:  N/A (0/0 requirements traced)"));
+    }
+}
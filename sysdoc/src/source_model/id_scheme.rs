@@ -0,0 +1,319 @@
+//! Section ID schemes and the ID registry
+//!
+//! An ID scheme (`[id_schemes.<document_type>]` in sysdoc.toml) fixes the form
+//! of every `section_id` in a document, such as `SDD-{n:03}`. `sysdoc ids assign`
+//! uses it to number sections that have no ID yet. Every ID ever issued is
+//! recorded in `id-registry.toml` at the document root; IDs that disappear from
+//! the document are retired there and are never issued again.
+
+use crate::document_config::IdSchemeConfig;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// File name of the ID registry, relative to the document root
+pub const ID_REGISTRY_FILE: &str = "id-registry.toml";
+
+/// Header comment written at the top of the ID registry
+const ID_REGISTRY_HEADER: &str = "# Section IDs issued in this document. Commit this file.\n\
+     # Updated by `sysdoc ids assign`; retired IDs are never issued again.\n\n";
+
+/// Errors in a configured ID scheme
+#[derive(Error, Debug)]
+pub enum IdSchemeError {
+    #[error("Invalid ID format '{0}': expected exactly one {{n}} or {{n:0W}} placeholder")]
+    InvalidFormat(String),
+
+    #[error("Invalid ID pattern '{pattern}': {source}")]
+    InvalidPattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// Errors that can occur when reading or writing the ID registry
+#[derive(Error, Debug)]
+pub enum IdRegistryError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("TOML parse error: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("TOML serialize error: {0}")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+/// A compiled section ID scheme
+#[derive(Debug, Clone)]
+pub struct IdScheme {
+    /// Text before the sequence number
+    prefix: String,
+    /// Minimum number of digits (zero padded)
+    width: usize,
+    /// Text after the sequence number
+    suffix: String,
+    /// Regex every section_id must match
+    pattern: Regex,
+}
+
+impl IdScheme {
+    /// Compile an ID scheme from its configuration
+    ///
+    /// # Parameters
+    /// * `config` - The `[id_schemes.<document_type>]` table
+    ///
+    /// # Returns
+    /// * `Ok(IdScheme)` - The compiled scheme
+    /// * `Err(IdSchemeError)` - The format has no valid placeholder or the pattern is invalid
+    pub fn new(config: &IdSchemeConfig) -> Result<Self, IdSchemeError> {
+        let invalid = || IdSchemeError::InvalidFormat(config.format.clone());
+        let start = config.format.find("{n").ok_or_else(invalid)?;
+        let end = start + config.format[start..].find('}').ok_or_else(invalid)?;
+        let width = match &config.format[start + 2..end] {
+            "" => 1,
+            spec => spec
+                .strip_prefix(':')
+                .and_then(|digits| digits.parse::<usize>().ok())
+                .ok_or_else(invalid)?,
+        };
+        let prefix = config.format[..start].to_string();
+        let suffix = config.format[end + 1..].to_string();
+        if prefix.contains('{') || suffix.contains('{') {
+            return Err(invalid());
+        }
+
+        let pattern = config.pattern.clone().unwrap_or_else(|| {
+            format!(
+                "^{}\\d{{{},}}{}$",
+                regex::escape(&prefix),
+                width,
+                regex::escape(&suffix)
+            )
+        });
+        let pattern = Regex::new(&pattern).map_err(|source| IdSchemeError::InvalidPattern {
+            pattern: pattern.clone(),
+            source,
+        })?;
+
+        Ok(Self {
+            prefix,
+            width,
+            suffix,
+            pattern,
+        })
+    }
+
+    /// The regex every section_id must match
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    /// Check whether a section_id conforms to the scheme
+    pub fn matches(&self, id: &str) -> bool {
+        self.pattern.is_match(id)
+    }
+
+    /// Format the ID with the given sequence number
+    pub fn format_id(&self, number: u64) -> String {
+        format!(
+            "{}{:0width$}{}",
+            self.prefix,
+            number,
+            self.suffix,
+            width = self.width
+        )
+    }
+
+    /// Sequence number of an ID in this scheme's format, if it has one
+    pub fn sequence_number(&self, id: &str) -> Option<u64> {
+        let digits = id.strip_prefix(&self.prefix)?.strip_suffix(&self.suffix)?;
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
+    }
+}
+
+/// Every section ID issued in a document, and those since retired
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdRegistry {
+    /// All IDs ever issued or seen in the document (including retired ones)
+    #[serde(default)]
+    pub issued: BTreeSet<String>,
+    /// IDs that were removed from the document and must not be reused
+    #[serde(default)]
+    pub retired: BTreeSet<String>,
+}
+
+impl IdRegistry {
+    /// Load the ID registry from a document root
+    ///
+    /// # Returns
+    /// * `Ok(Some(IdRegistry))` - The registry exists and was parsed
+    /// * `Ok(None)` - The document has no registry yet
+    /// * `Err(IdRegistryError)` - The registry could not be read or parsed
+    pub fn load(root: &Path) -> Result<Option<Self>, IdRegistryError> {
+        let path = root.join(ID_REGISTRY_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(Some(toml::from_str(&content)?))
+    }
+
+    /// Write the ID registry to a document root
+    pub fn save(&self, root: &Path) -> Result<(), IdRegistryError> {
+        let content = toml::to_string(self)?;
+        std::fs::write(
+            root.join(ID_REGISTRY_FILE),
+            format!("{ID_REGISTRY_HEADER}{content}"),
+        )?;
+        Ok(())
+    }
+
+    /// Record the IDs currently in the document
+    ///
+    /// IDs issued earlier but no longer present are retired.
+    pub fn update(&mut self, current: &BTreeSet<String>) {
+        let removed: Vec<String> = self.issued.difference(current).cloned().collect();
+        self.retired.extend(removed);
+        self.issued.extend(current.iter().cloned());
+    }
+}
+
+/// A section_id chosen for a section that had none
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdAssignment {
+    /// The new section_id
+    pub section_id: String,
+    /// Heading text of the section
+    pub heading: String,
+    /// Source file of the section (relative to document root)
+    pub source_file: PathBuf,
+    /// Line number of the section heading (1-indexed)
+    pub line: usize,
+    /// Line number where the next section starts (or past the end of the file)
+    pub end_line: usize,
+}
+
+/// Write new section_ids into the markdown of one file
+///
+/// The ID is added as the first line of the section's `sysdoc` block. A section
+/// without a block gets a new one directly below its heading.
+///
+/// # Parameters
+/// * `raw` - Markdown content of the file
+/// * `assignments` - Assignments for sections in this file
+///
+/// # Returns
+/// * The updated markdown content
+pub fn insert_section_ids(raw: &str, assignments: &[&IdAssignment]) -> String {
+    let mut lines: Vec<String> = raw.lines().map(str::to_string).collect();
+    let mut ordered: Vec<&IdAssignment> = assignments.to_vec();
+    ordered.sort_by_key(|assignment| std::cmp::Reverse(assignment.line));
+
+    for assignment in ordered {
+        let id_line = format!("section_id = \"{}\"", assignment.section_id);
+        let heading = assignment.line.min(lines.len());
+        let end = assignment.end_line.saturating_sub(1).min(lines.len());
+        let block = (heading..end.max(heading)).find(|&index| is_sysdoc_fence(&lines[index]));
+        match block {
+            Some(index) => lines.insert(index + 1, id_line),
+            None => {
+                let new_block = ["", "```sysdoc", id_line.as_str(), "```"];
+                for (offset, line) in new_block.iter().enumerate() {
+                    lines.insert(heading + offset, line.to_string());
+                }
+            }
+        }
+    }
+
+    let mut content = lines.join("\n");
+    if raw.ends_with('\n') {
+        content.push('\n');
+    }
+    content
+}
+
+/// Check whether a line opens a fenced `sysdoc` block
+fn is_sysdoc_fence(line: &str) -> bool {
+    let trimmed = line.trim();
+    ["```", "~~~"]
+        .into_iter()
+        .any(|fence| trimmed.strip_prefix(fence).map(str::trim) == Some("sysdoc"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheme(format: &str) -> Result<IdScheme, IdSchemeError> {
+        IdScheme::new(&IdSchemeConfig {
+            format: format.to_string(),
+            pattern: None,
+        })
+    }
+
+    #[test]
+    fn test_scheme_format_and_pattern() {
+        let scheme = scheme("SDD-{n:03}").unwrap();
+        assert_eq!(scheme.format_id(7), "SDD-007");
+        assert_eq!(scheme.format_id(1234), "SDD-1234");
+        assert_eq!(scheme.sequence_number("SDD-042"), Some(42));
+        assert_eq!(scheme.sequence_number("SRS-042"), None);
+        assert!(scheme.matches("SDD-001"));
+        assert!(!scheme.matches("SDD-01"));
+        assert!(!scheme.matches("SDD-3.2"));
+
+        let plain = self::scheme("REQ-{n}.A").unwrap();
+        assert_eq!(plain.format_id(3), "REQ-3.A");
+        assert!(plain.matches("REQ-12.A"));
+        assert!(!plain.matches("REQ-12xA"));
+
+        assert!(matches!(
+            self::scheme("SDD-001"),
+            Err(IdSchemeError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            self::scheme("SDD-{n:x}"),
+            Err(IdSchemeError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_registry_retires_removed_ids() {
+        let mut registry = IdRegistry::default();
+        let ids = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<BTreeSet<_>>();
+        registry.update(&ids(&["SDD-001", "SDD-002"]));
+        registry.update(&ids(&["SDD-002", "SDD-003"]));
+        assert_eq!(registry.issued, ids(&["SDD-001", "SDD-002", "SDD-003"]));
+        assert_eq!(registry.retired, ids(&["SDD-001"]));
+
+        let text = toml::to_string(&registry).unwrap();
+        assert_eq!(toml::from_str::<IdRegistry>(&text).unwrap(), registry);
+    }
+
+    #[test]
+    fn test_insert_section_ids() {
+        let raw =
+            "# Design\n\n```sysdoc\ntraced_ids = [\"SRS-1\"]\n```\n\nText.\n\n## Parser\n\nMore.\n";
+        let assignment = |id: &str, line: usize, end_line: usize| IdAssignment {
+            section_id: id.to_string(),
+            heading: String::new(),
+            source_file: PathBuf::from("src/01_design.md"),
+            line,
+            end_line,
+        };
+        let first = assignment("SDD-001", 1, 9);
+        let second = assignment("SDD-002", 9, 12);
+        let updated = insert_section_ids(raw, &[&first, &second]);
+        assert_eq!(
+            updated,
+            "# Design\n\n```sysdoc\nsection_id = \"SDD-001\"\ntraced_ids = [\"SRS-1\"]\n```\n\nText.\n\n## Parser\n\n```sysdoc\nsection_id = \"SDD-002\"\n```\n\nMore.\n"
+        );
+    }
+}
@@ -0,0 +1,474 @@
+//! Change impact analysis
+//!
+//! Starting from a set of changed IDs, walks the trace relation in both
+//! directions across this document and its linked upstream and downstream
+//! projects: downstream to every section that traces (directly or
+//! transitively) to a changed item, and upstream to every item a changed
+//! section traces to. Changed IDs can also be derived from a git diff of the
+//! markdown sources.
+
+use super::markdown_source::{MarkdownSection, MarkdownSource};
+use super::upstream::UpstreamDocument;
+use super::SourceModel;
+use crate::paths::SOURCE_DIR;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Errors that can occur when deriving changed IDs from git
+#[derive(Error, Debug)]
+pub enum ImpactError {
+    #[error("Failed to run git: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("git diff failed: {0}")]
+    Git(String),
+}
+
+/// Location of an identified section in one of the linked projects
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImpactLocation {
+    /// `document_id` of the project defining the section
+    pub document_id: String,
+    /// Source file, relative to this document's root
+    pub file: PathBuf,
+    /// Line number of the section heading (1-indexed)
+    pub line: usize,
+    /// Heading text
+    pub heading: String,
+}
+
+/// An item reached by the impact analysis
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImpactedItem {
+    /// Section ID or traced ID
+    pub id: String,
+    /// Number of trace links from the nearest changed item (0 for changed items)
+    pub distance: usize,
+    /// ID from which this item was reached (None for changed items)
+    pub via: Option<String>,
+    /// Where the item is defined (None for catalog entries and unknown IDs)
+    pub location: Option<ImpactLocation>,
+}
+
+impl std::fmt::Display for ImpactedItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id)?;
+        match &self.location {
+            Some(location) => write!(
+                f,
+                "  {}:{}  {} [{}]",
+                location.file.display(),
+                location.line,
+                location.heading,
+                location.document_id
+            )?,
+            None => write!(f, "  (not a section)")?,
+        }
+        if let Some(via) = &self.via {
+            write!(f, "  via {via}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of an impact analysis
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImpactReport {
+    /// The changed items the analysis started from
+    pub changed: Vec<ImpactedItem>,
+    /// Sections tracing (directly or transitively) to a changed item
+    pub downstream: Vec<ImpactedItem>,
+    /// Items a changed section traces to (directly or transitively)
+    pub upstream: Vec<ImpactedItem>,
+}
+
+impl ImpactReport {
+    /// Plain-text report listing each group of items
+    pub fn to_text(&self) -> String {
+        let groups = [
+            ("Changed", &self.changed),
+            ("Downstream (trace to a changed item)", &self.downstream),
+            ("Upstream (traced by a changed item)", &self.upstream),
+        ];
+        let mut report = String::new();
+        for (title, items) in groups {
+            report.push_str(&format!("{} ({}):\n", title, items.len()));
+            for item in items {
+                report.push_str(&format!("  {}\n", item));
+            }
+        }
+        report
+    }
+
+    /// JSON report
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// The trace relation across a document and its linked projects
+#[derive(Debug, Default)]
+pub struct TraceNetwork {
+    /// Identified sections by ID (the first project defining an ID wins)
+    locations: BTreeMap<String, ImpactLocation>,
+    /// section ID -> IDs it traces to
+    traces: BTreeMap<String, BTreeSet<String>>,
+    /// traced ID -> section IDs tracing to it
+    traced_by: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl TraceNetwork {
+    /// Build the network of a document, its upstreams and the given downstream projects
+    ///
+    /// # Parameters
+    /// * `model` - The document, with its upstream projects loaded
+    /// * `downstreams` - Downstream projects declared with `[[downstream]]`
+    pub fn new(model: &SourceModel, downstreams: &[UpstreamDocument]) -> Self {
+        let mut network = Self::default();
+        network.add_project(
+            Path::new(""),
+            &model.config.document_id,
+            &model.markdown_files,
+        );
+        for project in model.upstreams.iter().chain(downstreams) {
+            network.add_project(
+                &project.path,
+                &project.config.document_id,
+                &project.markdown_files,
+            );
+        }
+        network
+    }
+
+    /// Add the identified sections of one project
+    fn add_project(&mut self, prefix: &Path, document_id: &str, files: &[MarkdownSource]) {
+        let sections = files.iter().flat_map(|md_file| md_file.sections.iter());
+        for trace in sections.filter_map(SourceModel::extract_section_traceability) {
+            self.locations
+                .entry(trace.section_id.clone())
+                .or_insert_with(|| ImpactLocation {
+                    document_id: document_id.to_string(),
+                    file: prefix.join(&trace.source_file),
+                    line: trace.line_number,
+                    heading: trace.heading.clone(),
+                });
+            for traced_id in &trace.traced_ids {
+                self.traces
+                    .entry(trace.section_id.clone())
+                    .or_default()
+                    .insert(traced_id.clone());
+                self.traced_by
+                    .entry(traced_id.clone())
+                    .or_default()
+                    .insert(trace.section_id.clone());
+            }
+        }
+    }
+
+    /// Check whether an ID is a section or a traced item of any project in the network
+    ///
+    /// # Parameters
+    /// * `id` - Section ID or traced ID
+    pub fn contains(&self, id: &str) -> bool {
+        self.locations.contains_key(id) || self.traced_by.contains_key(id)
+    }
+
+    /// Walk the trace relation from a set of changed IDs
+    ///
+    /// # Parameters
+    /// * `changed` - IDs of the changed items
+    ///
+    /// # Returns
+    /// * The changed items, and the items reached downstream and upstream,
+    ///   each ordered by distance then ID
+    pub fn analyze(&self, changed: &BTreeSet<String>) -> ImpactReport {
+        ImpactReport {
+            changed: changed.iter().map(|id| self.item(id, 0, None)).collect(),
+            downstream: self.walk(changed, &self.traced_by),
+            upstream: self.walk(changed, &self.traces),
+        }
+    }
+
+    /// Breadth-first walk along one direction of the relation
+    fn walk(
+        &self,
+        start: &BTreeSet<String>,
+        edges: &BTreeMap<String, BTreeSet<String>>,
+    ) -> Vec<ImpactedItem> {
+        let mut visited: BTreeSet<&str> = start.iter().map(String::as_str).collect();
+        let mut queue: VecDeque<(&str, usize)> = start.iter().map(|id| (id.as_str(), 0)).collect();
+        let mut reached = Vec::new();
+
+        while let Some((id, distance)) = queue.pop_front() {
+            let unvisited: Vec<&str> = edges
+                .get(id)
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .filter(|next| visited.insert(next))
+                .collect();
+            for next in unvisited {
+                reached.push(self.item(next, distance + 1, Some(id)));
+                queue.push_back((next, distance + 1));
+            }
+        }
+        reached
+    }
+
+    fn item(&self, id: &str, distance: usize, via: Option<&str>) -> ImpactedItem {
+        ImpactedItem {
+            id: id.to_string(),
+            distance,
+            via: via.map(str::to_string),
+            location: self.locations.get(id).cloned(),
+        }
+    }
+}
+
+/// Files and lines changed according to `git diff`
+#[derive(Debug, Default, PartialEq, Eq)]
+struct GitChanges {
+    /// Changed (first, last) line ranges in the new version of each file,
+    /// keyed by path relative to the document root
+    lines: BTreeMap<PathBuf, Vec<(usize, usize)>>,
+    /// Files deleted since the revision
+    deleted: Vec<PathBuf>,
+}
+
+/// Changed line ranges per file from `git diff`
+///
+/// # Parameters
+/// * `root` - Document root; the diff is limited to `src/` below it
+/// * `revision` - Revision or range passed to `git diff` (e.g., "v1.0" or "main...HEAD")
+///
+/// # Returns
+/// * `Ok(GitChanges)` - Changed line ranges and deleted files below `src/`
+/// * `Err(ImpactError)` - git could not be run or reported an error
+fn git_changed_lines(root: &Path, revision: &str) -> Result<GitChanges, ImpactError> {
+    let output = std::process::Command::new("git")
+        .args([
+            "diff",
+            "--relative",
+            "--no-prefix",
+            "--no-color",
+            "--unified=0",
+            revision,
+            "--",
+            SOURCE_DIR,
+        ])
+        .current_dir(root)
+        .output()?;
+    if !output.status.success() {
+        return Err(ImpactError::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(parse_unified_diff(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// IDs of the local sections changed since a git revision
+///
+/// # Parameters
+/// * `model` - The parsed document
+/// * `revision` - Revision or range passed to `git diff`
+///
+/// # Returns
+/// * `Ok((ids, deleted))` - IDs of the touched sections, and deleted source
+///   files (whose IDs can no longer be determined)
+/// * `Err(ImpactError)` - git could not be run or reported an error
+pub fn changed_section_ids(
+    model: &SourceModel,
+    revision: &str,
+) -> Result<(BTreeSet<String>, Vec<PathBuf>), ImpactError> {
+    let changes = git_changed_lines(&model.root, revision)?;
+    let ids = model
+        .markdown_files
+        .iter()
+        .filter_map(|md_file| Some(sections_touched(md_file, changes.lines.get(&md_file.path)?)))
+        .flatten()
+        .collect();
+    Ok((ids, changes.deleted))
+}
+
+/// Parse the file names and hunk headers of a `--no-prefix --unified=0` diff
+fn parse_unified_diff(diff: &str) -> GitChanges {
+    let mut changes = GitChanges::default();
+    let mut old_file: Option<&str> = None;
+    let mut current: Option<PathBuf> = None;
+
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("--- ") {
+            old_file = Some(path);
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            current = None;
+            match (path, old_file) {
+                ("/dev/null", Some(old)) => changes.deleted.push(PathBuf::from(old)),
+                _ => current = Some(PathBuf::from(path)),
+            }
+        } else if let (Some(file), Some(hunk)) = (&current, line.strip_prefix("@@ ")) {
+            if let Some(range) = parse_hunk_new_range(hunk) {
+                changes.lines.entry(file.clone()).or_default().push(range);
+            }
+        }
+    }
+    changes
+}
+
+/// Parse the new-side range of a hunk header body (`-a,b +c,d @@ ...`)
+///
+/// A pure deletion (`+c,0`) is reported as the line it follows.
+fn parse_hunk_new_range(hunk: &str) -> Option<(usize, usize)> {
+    let new = hunk
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix('+'))?;
+    let (start, count) = match new.split_once(',') {
+        Some((start, count)) => (start.parse::<usize>().ok()?, count.parse::<usize>().ok()?),
+        None => (new.parse::<usize>().ok()?, 1),
+    };
+    let start = start.max(1);
+    Some((start, start + count.max(1) - 1))
+}
+
+/// IDs of the sections touched by changed line ranges of one file
+///
+/// A change in a section without a `section_id` is attributed to its nearest
+/// enclosing section that has one.
+fn sections_touched(md_file: &MarkdownSource, ranges: &[(usize, usize)]) -> BTreeSet<String> {
+    let sections = &md_file.sections;
+    let mut touched = BTreeSet::new();
+    for (index, section) in sections.iter().enumerate() {
+        let end = sections
+            .get(index + 1)
+            .map_or(usize::MAX, |next| next.line_number.saturating_sub(1));
+        let overlaps = ranges
+            .iter()
+            .any(|&(first, last)| first <= end && last >= section.line_number);
+        if !overlaps {
+            continue;
+        }
+        if let Some(id) = identified_ancestor(&sections[..=index]) {
+            touched.insert(id.to_string());
+        }
+    }
+    touched
+}
+
+/// section_id of the last section in the slice, or of its nearest identified ancestor
+fn identified_ancestor(sections: &[MarkdownSection]) -> Option<&str> {
+    let (last, earlier) = sections.split_last()?;
+    let mut level = last.heading_level + 1;
+    for section in std::iter::once(last).chain(earlier.iter().rev()) {
+        if section.heading_level >= level {
+            continue;
+        }
+        level = section.heading_level;
+        if let Some(id) = section
+            .metadata
+            .as_ref()
+            .and_then(|m| m.section_id.as_deref())
+        {
+            return Some(id);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_model::SectionNumber;
+
+    fn source(path: &str, number: &str, content: &str) -> MarkdownSource {
+        let mut source = MarkdownSource {
+            path: PathBuf::from(path),
+            absolute_path: PathBuf::from("/test").join(path),
+            section_number: SectionNumber::parse(number).unwrap(),
+            title: String::new(),
+            raw_content: content.to_string(),
+            sections: Vec::new(),
+        };
+        source.parse(Path::new("/test")).unwrap();
+        source
+    }
+
+    #[test]
+    fn test_walk_both_directions() {
+        let config = toml::from_str(
+            "document_id = \"SDD\"\ndocument_title = \"t\"\ndocument_type = \"SDD\"\n\
+             document_standard = \"s\"\ndocument_template = \"t\"\n\
+             [document_owner]\nname = \"a\"\nemail = \"a\"\n\
+             [document_approver]\nname = \"b\"\nemail = \"b\"\n",
+        )
+        .unwrap();
+        let mut model = SourceModel::new(PathBuf::from("/test"), config);
+        model.markdown_files.push(source(
+            "src/01_design.md",
+            "01",
+            "# Design\n\n```sysdoc\nsection_id = \"SDD-1\"\ntraced_ids = [\"SRS-1\"]\n```\n\n\
+             ## Detail\n\n```sysdoc\nsection_id = \"SDD-2\"\ntraced_ids = [\"SDD-1\"]\n```\n\n\
+             ### Notes\n\nText.\n\n\
+             ## Other\n\n```sysdoc\nsection_id = \"SDD-3\"\ntraced_ids = [\"SRS-2\"]\n```\n",
+        ));
+
+        let network = TraceNetwork::new(&model, &[]);
+        let report = network.analyze(&BTreeSet::from(["SRS-1".to_string()]));
+        let downstream: Vec<(&str, usize, Option<&str>)> = report
+            .downstream
+            .iter()
+            .map(|item| (item.id.as_str(), item.distance, item.via.as_deref()))
+            .collect();
+        assert_eq!(
+            downstream,
+            vec![("SDD-1", 1, Some("SRS-1")), ("SDD-2", 2, Some("SDD-1"))]
+        );
+        assert!(report.upstream.is_empty());
+        let location = report.downstream[1].location.as_ref().unwrap();
+        assert_eq!(
+            (location.file.as_path(), location.line),
+            (Path::new("src/01_design.md"), 8)
+        );
+        assert!(report.changed[0].location.is_none());
+        assert!(network.contains("SRS-1"));
+        assert!(network.contains("SDD-3"));
+        assert!(!network.contains("SRS-9"));
+
+        let report = network.analyze(&BTreeSet::from(["SDD-2".to_string()]));
+        let upstream: Vec<&str> = report
+            .upstream
+            .iter()
+            .map(|item| item.id.as_str())
+            .collect();
+        assert_eq!(upstream, vec!["SDD-1", "SRS-1"]);
+
+        // Lines 15-16 are in "Notes", which has no ID and belongs to SDD-2
+        let touched = sections_touched(&model.markdown_files[0], &[(15, 16), (20, 20)]);
+        assert_eq!(
+            touched,
+            BTreeSet::from(["SDD-2".to_string(), "SDD-3".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_parse_unified_diff() {
+        let diff = "diff --git src/01_a.md src/01_a.md\n\
+                    --- src/01_a.md\n\
+                    +++ src/01_a.md\n\
+                    @@ -3 +3 @@ heading\n\
+                    -old\n\
+                    +new\n\
+                    @@ -10,2 +9,0 @@\n\
+                    diff --git src/02_b.md src/02_b.md\n\
+                    --- src/02_b.md\n\
+                    +++ /dev/null\n\
+                    @@ -1,4 +0,0 @@\n";
+        let changes = parse_unified_diff(diff);
+        assert_eq!(
+            changes.lines[Path::new("src/01_a.md")],
+            vec![(3, 3), (9, 9)]
+        );
+        assert_eq!(changes.lines.len(), 1);
+        assert_eq!(changes.deleted, vec![PathBuf::from("src/02_b.md")]);
+    }
+}
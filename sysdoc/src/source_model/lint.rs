@@ -0,0 +1,688 @@
+//! Documentation-quality lint rules
+//!
+//! `sysdoc lint` runs a registry of rules over the markdown sources of a
+//! document. Rules work on the raw source lines of each section, so every
+//! finding points at a file and line. Each rule has a default severity that
+//! `[lint.rules]` in sysdoc.toml can override or turn off.
+
+use super::markdown_source::{MarkdownSection, MarkdownSource};
+use super::spelling::{self, Dictionary};
+use crate::diagnostics::Diagnostic;
+use crate::document_config::{LintConfig, LintSeverity};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Default maximum number of words in a paragraph
+const DEFAULT_MAX_PARAGRAPH_WORDS: usize = 150;
+
+/// Words and phrases reported by `vague-word` unless sysdoc.toml replaces them
+const DEFAULT_VAGUE_WORDS: &[&str] = &[
+    "etc.",
+    "and/or",
+    "as appropriate",
+    "as applicable",
+    "as required",
+    "if possible",
+    "if practical",
+    "user-friendly",
+    "easy to use",
+    "sufficient",
+    "adequate",
+];
+
+/// Weak words that must not appear in a requirement
+const WEAK_REQUIREMENT_WORDS: &[&str] = &["should", "may"];
+
+/// Errors in the lint configuration
+#[derive(Error, Debug)]
+pub enum LintError {
+    #[error("Unknown lint rule '{0}' in [lint.rules] (run `sysdoc lint --list-rules`)")]
+    UnknownRule(String),
+}
+
+/// A problem found by a lint rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    /// Name of the rule that reported the finding
+    pub rule: &'static str,
+    /// Severity of the rule
+    pub severity: LintSeverity,
+    /// Source file (relative to document root)
+    pub file: PathBuf,
+    /// Line number (1-indexed)
+    pub line: usize,
+    /// Column of the offending text (1-indexed, in characters), if known
+    pub column: Option<usize>,
+    /// Offending text on the line, underlined in reports
+    pub highlight: Option<String>,
+    /// Description of the problem
+    pub message: String,
+    /// How to fix the problem, if the rule knows
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}[{}]: {}",
+            self.file.display(),
+            self.line,
+            self.severity,
+            self.rule,
+            self.message
+        )
+    }
+}
+
+impl From<&LintFinding> for Diagnostic {
+    fn from(finding: &LintFinding) -> Self {
+        let diagnostic = Diagnostic::new(finding.rule, finding.severity.into(), &finding.message);
+        let mut diagnostic = match finding.column {
+            Some(column) => diagnostic.at(&finding.file, finding.line, column),
+            None => diagnostic.at_line(&finding.file, finding.line),
+        };
+        if let Some(highlight) = &finding.highlight {
+            diagnostic = diagnostic.highlighting(highlight);
+        }
+        if let Some(suggestion) = &finding.suggestion {
+            diagnostic = diagnostic.with_suggestion(suggestion);
+        }
+        diagnostic
+    }
+}
+
+/// A finding before the engine attaches the rule name and severity
+struct RawFinding {
+    file: PathBuf,
+    line: usize,
+    column: Option<usize>,
+    highlight: Option<String>,
+    message: String,
+    suggestion: Option<String>,
+}
+
+impl RawFinding {
+    /// Point the finding at text on its line
+    fn highlighting(mut self, text: impl Into<String>) -> Self {
+        self.highlight = Some(text.into());
+        self
+    }
+
+    /// Point the finding at the text at a byte offset of its source line
+    fn at_offset(self, line: &str, offset: usize, len: usize) -> Self {
+        let column = line[..offset].chars().count() + 1;
+        Self {
+            column: Some(column),
+            ..self.highlighting(&line[offset..offset + len])
+        }
+    }
+}
+
+/// A section with the prose lines of its source
+struct SectionText<'a> {
+    section: &'a MarkdownSection,
+    /// Lines between the heading and the next heading, outside fenced code
+    /// blocks, with their 1-indexed line numbers
+    lines: Vec<(usize, &'a str)>,
+}
+
+impl SectionText<'_> {
+    /// Create a finding at a line of this section
+    fn finding(&self, line: usize, message: String) -> RawFinding {
+        RawFinding {
+            file: self.section.source_file.clone(),
+            line,
+            column: None,
+            highlight: None,
+            message,
+            suggestion: None,
+        }
+    }
+
+    /// `section_id` of the section, if it is a requirement
+    fn requirement_id(&self) -> Option<&str> {
+        self.section.metadata.as_ref()?.section_id.as_deref()
+    }
+}
+
+/// Options shared by all rules
+struct LintOptions<'a> {
+    max_paragraph_words: usize,
+    vague_words: Vec<String>,
+    /// Accepted words for `spelling` (None skips the rule)
+    dictionary: Option<&'a Dictionary>,
+}
+
+/// A lint rule in the registry
+pub struct LintRule {
+    /// Name used in `[lint.rules]` and in findings
+    pub name: &'static str,
+    /// One-line description of what the rule reports
+    pub description: &'static str,
+    /// Severity unless overridden in sysdoc.toml
+    pub default_severity: LintSeverity,
+    /// The check over every section of the document, in document order
+    check: fn(&[SectionText<'_>], &LintOptions) -> Vec<RawFinding>,
+}
+
+/// All lint rules
+pub const LINT_RULES: &[LintRule] = &[
+    LintRule {
+        name: "multiple-shall",
+        description: "A requirement (a section with a section_id) contains more than one 'shall'",
+        default_severity: LintSeverity::Warning,
+        check: check_multiple_shall,
+    },
+    LintRule {
+        name: "weak-requirement-word",
+        description: "A requirement uses 'should' or 'may' instead of 'shall'",
+        default_severity: LintSeverity::Warning,
+        check: check_weak_requirement_words,
+    },
+    LintRule {
+        name: "vague-word",
+        description: "Text uses a vague word or phrase such as 'etc.' or 'as appropriate'",
+        default_severity: LintSeverity::Warning,
+        check: check_vague_words,
+    },
+    LintRule {
+        name: "heading-skip",
+        description: "A heading is more than one level deeper than the heading before it",
+        default_severity: LintSeverity::Warning,
+        check: check_heading_skips,
+    },
+    LintRule {
+        name: "empty-section",
+        description: "A section has no content and no subsections",
+        default_severity: LintSeverity::Warning,
+        check: check_empty_sections,
+    },
+    LintRule {
+        name: "long-paragraph",
+        description: "A paragraph is longer than max_paragraph_words (default 150)",
+        default_severity: LintSeverity::Warning,
+        check: check_long_paragraphs,
+    },
+    LintRule {
+        name: "duplicate-heading",
+        description: "Two sections under the same parent have the same heading",
+        default_severity: LintSeverity::Warning,
+        check: check_duplicate_headings,
+    },
+    LintRule {
+        name: "spelling",
+        description: "A word is not in the English, project or glossary dictionaries",
+        default_severity: LintSeverity::Warning,
+        check: check_spelling,
+    },
+];
+
+/// Run every enabled lint rule over the markdown sources
+///
+/// # Parameters
+/// * `files` - Parsed markdown sources of the document
+/// * `config` - The `[lint]` settings from sysdoc.toml, if any
+/// * `dictionary` - Accepted words for the `spelling` rule (None skips the rule)
+///
+/// # Returns
+/// * `Ok(Vec<LintFinding>)` - Findings sorted by file and line
+/// * `Err(LintError)` - `[lint.rules]` names a rule that does not exist
+pub fn lint(
+    files: &[MarkdownSource],
+    config: Option<&LintConfig>,
+    dictionary: Option<&Dictionary>,
+) -> Result<Vec<LintFinding>, LintError> {
+    let overrides = config.map(|config| &config.rules);
+    if let Some(unknown) = overrides
+        .into_iter()
+        .flat_map(BTreeMap::keys)
+        .find(|name| !LINT_RULES.iter().any(|rule| rule.name == name.as_str()))
+    {
+        return Err(LintError::UnknownRule(unknown.clone()));
+    }
+
+    let options = LintOptions {
+        max_paragraph_words: config
+            .and_then(|config| config.max_paragraph_words)
+            .unwrap_or(DEFAULT_MAX_PARAGRAPH_WORDS),
+        vague_words: config
+            .and_then(|config| config.vague_words.clone())
+            .unwrap_or_else(|| DEFAULT_VAGUE_WORDS.iter().map(|s| s.to_string()).collect()),
+        dictionary,
+    };
+    let sections = section_texts(files);
+
+    let mut findings = Vec::new();
+    for rule in LINT_RULES {
+        let severity = overrides
+            .and_then(|rules| rules.get(rule.name))
+            .copied()
+            .unwrap_or(rule.default_severity);
+        if severity == LintSeverity::Off {
+            continue;
+        }
+        findings.extend(
+            (rule.check)(&sections, &options)
+                .into_iter()
+                .map(|raw| LintFinding {
+                    rule: rule.name,
+                    severity,
+                    file: raw.file,
+                    line: raw.line,
+                    column: raw.column,
+                    highlight: raw.highlight,
+                    message: raw.message,
+                    suggestion: raw.suggestion,
+                }),
+        );
+    }
+    findings.sort_by(|a, b| (&a.file, a.line, a.rule).cmp(&(&b.file, b.line, b.rule)));
+    Ok(findings)
+}
+
+/// Split every file into sections with their prose lines, in document order
+fn section_texts(files: &[MarkdownSource]) -> Vec<SectionText<'_>> {
+    let mut sorted: Vec<&MarkdownSource> = files.iter().collect();
+    sorted.sort_by(|a, b| a.section_number.cmp(&b.section_number));
+
+    let mut texts = Vec::new();
+    for file in sorted {
+        for (section, lines) in file.section_lines() {
+            // Skip the heading line, and code blocks (including sysdoc blocks)
+            let mut in_fence = false;
+            let prose = lines
+                .into_iter()
+                .enumerate()
+                .skip(1)
+                .filter(|(_, line)| {
+                    let fence = is_fence(line);
+                    let outside = !in_fence && !fence;
+                    in_fence ^= fence;
+                    outside
+                })
+                .map(|(index, line)| (section.line_number + index, line))
+                .collect();
+            texts.push(SectionText {
+                section,
+                lines: prose,
+            });
+        }
+    }
+    texts
+}
+
+/// Check whether a line opens or closes a fenced code block
+fn is_fence(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+}
+
+/// Find the byte offsets of a word or phrase in a line (case-insensitive, whole words)
+fn find_phrase(line: &str, phrase: &str) -> Vec<usize> {
+    let haystack = line.to_lowercase();
+    let needle = phrase.to_lowercase();
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    haystack
+        .match_indices(&needle)
+        .map(|(offset, _)| offset)
+        .filter(|&offset| {
+            let before = haystack[..offset].chars().next_back();
+            let after = haystack[offset + needle.len()..].chars().next();
+            let bounded_start = !is_word(needle.chars().next()) || !is_word(before);
+            let bounded_end = !is_word(needle.chars().next_back()) || !is_word(after);
+            bounded_start && bounded_end
+        })
+        .collect()
+}
+
+/// `multiple-shall`: report the second and later "shall" in a requirement
+fn check_multiple_shall(sections: &[SectionText<'_>], _: &LintOptions) -> Vec<RawFinding> {
+    let mut findings = Vec::new();
+    for text in sections {
+        let Some(id) = text.requirement_id() else {
+            continue;
+        };
+        let shalls: Vec<(usize, &str, usize)> = text
+            .lines
+            .iter()
+            .flat_map(|(number, line)| {
+                find_phrase(line, "shall")
+                    .into_iter()
+                    .map(|offset| (*number, *line, offset))
+            })
+            .collect();
+        if let [_, (number, line, offset), ..] = shalls.as_slice() {
+            findings.push(
+                text.finding(
+                    *number,
+                    format!(
+                        "Requirement '{}' contains {} 'shall' statements; split it into one requirement per 'shall'",
+                        id,
+                        shalls.len()
+                    ),
+                )
+                .at_offset(line, *offset, "shall".len()),
+            );
+        }
+    }
+    findings
+}
+
+/// `weak-requirement-word`: report "should" and "may" in requirements
+fn check_weak_requirement_words(sections: &[SectionText<'_>], _: &LintOptions) -> Vec<RawFinding> {
+    let mut findings = Vec::new();
+    for text in sections {
+        let Some(id) = text.requirement_id() else {
+            continue;
+        };
+        for (number, line) in &text.lines {
+            findings.extend(WEAK_REQUIREMENT_WORDS.iter().filter_map(|word| {
+                let offset = *find_phrase(line, word).first()?;
+                Some(
+                    text.finding(
+                        *number,
+                        format!("Requirement '{id}' uses '{word}'; requirements use 'shall'"),
+                    )
+                    .at_offset(line, offset, word.len()),
+                )
+            }));
+        }
+    }
+    findings
+}
+
+/// `vague-word`: report vague words and phrases anywhere in the text
+fn check_vague_words(sections: &[SectionText<'_>], options: &LintOptions) -> Vec<RawFinding> {
+    let mut findings = Vec::new();
+    for text in sections {
+        for (number, line) in &text.lines {
+            findings.extend(options.vague_words.iter().filter_map(|word| {
+                let offset = *find_phrase(line, word).first()?;
+                Some(
+                    text.finding(*number, format!("Vague wording '{word}'"))
+                        .at_offset(line, offset, word.len()),
+                )
+            }));
+        }
+    }
+    findings
+}
+
+/// `heading-skip`: report headings more than one level below the previous heading in a file
+fn check_heading_skips(sections: &[SectionText<'_>], _: &LintOptions) -> Vec<RawFinding> {
+    sections
+        .windows(2)
+        .filter(|pair| pair[0].section.source_file == pair[1].section.source_file)
+        .filter(|pair| pair[1].section.heading_level > pair[0].section.heading_level + 1)
+        .map(|pair| {
+            pair[1]
+                .finding(
+                    pair[1].section.line_number,
+                    format!(
+                        "Heading '{}' skips from level {} to level {}",
+                        pair[1].section.heading_text,
+                        pair[0].section.heading_level,
+                        pair[1].section.heading_level
+                    ),
+                )
+                .highlighting(&pair[1].section.heading_text)
+        })
+        .collect()
+}
+
+/// `empty-section`: report sections without content or subsections
+///
+/// Lint runs before tables, graphs and included files are generated, so a
+/// section whose metadata requests them is not empty.
+fn check_empty_sections(sections: &[SectionText<'_>], _: &LintOptions) -> Vec<RawFinding> {
+    let mut numbers: Vec<&[u32]> = sections
+        .iter()
+        .map(|text| text.section.section_number.parts())
+        .collect();
+    numbers.sort();
+    let has_subsection = |parts: &[u32]| {
+        let next = numbers.partition_point(|number| *number <= parts);
+        numbers
+            .get(next)
+            .is_some_and(|number| number.len() > parts.len() && number.starts_with(parts))
+    };
+
+    sections
+        .iter()
+        .filter(|text| text.section.content.is_empty())
+        .filter(|text| {
+            !text.section.metadata.as_ref().is_some_and(|metadata| {
+                metadata.requests_table_generation() || metadata.include_file.is_some()
+            })
+        })
+        .filter(|text| !has_subsection(text.section.section_number.parts()))
+        .map(|text| {
+            text.finding(
+                text.section.line_number,
+                format!("Section '{}' is empty", text.section.heading_text),
+            )
+            .highlighting(&text.section.heading_text)
+        })
+        .collect()
+}
+
+/// `long-paragraph`: report paragraphs with more than the maximum number of words
+fn check_long_paragraphs(sections: &[SectionText<'_>], options: &LintOptions) -> Vec<RawFinding> {
+    let mut findings = Vec::new();
+    for text in sections {
+        for (start, words) in paragraphs(&text.lines) {
+            if words > options.max_paragraph_words {
+                findings.push(text.finding(
+                    start,
+                    format!(
+                        "Paragraph has {} words (maximum {})",
+                        words, options.max_paragraph_words
+                    ),
+                ));
+            }
+        }
+    }
+    findings
+}
+
+/// Split prose lines into paragraphs
+///
+/// # Returns
+/// * The first line number and word count of each paragraph; table rows,
+///   headings and list items start new paragraphs and are not counted
+fn paragraphs(lines: &[(usize, &str)]) -> Vec<(usize, usize)> {
+    let mut paragraphs: Vec<(usize, usize)> = Vec::new();
+    let mut previous: Option<usize> = None;
+    for (number, line) in lines {
+        let trimmed = line.trim();
+        let is_prose = !trimmed.is_empty()
+            && !trimmed.starts_with('|')
+            && !trimmed.starts_with('#')
+            && !is_list_item(trimmed);
+        let words = trimmed.split_whitespace().count();
+        match (is_prose, paragraphs.last_mut()) {
+            (true, Some((_, count))) if previous == Some(number - 1) => *count += words,
+            (true, _) => paragraphs.push((*number, words)),
+            (false, _) => {}
+        }
+        previous = is_prose.then_some(*number);
+    }
+    paragraphs
+}
+
+/// Check whether a line starts a list item
+fn is_list_item(line: &str) -> bool {
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    line.starts_with("- ")
+        || line.starts_with("* ")
+        || line.starts_with("+ ")
+        || (digits > 0 && line[digits..].starts_with(". "))
+}
+
+/// `spelling`: report words not found in the dictionary
+fn check_spelling(sections: &[SectionText<'_>], options: &LintOptions) -> Vec<RawFinding> {
+    let Some(dictionary) = options.dictionary else {
+        return Vec::new();
+    };
+    sections
+        .iter()
+        .flat_map(|text| spelling::section_misspellings(text.section, &text.lines, dictionary))
+        .map(|misspelling| {
+            let diagnostic = Diagnostic::from(&misspelling);
+            RawFinding {
+                file: misspelling.file,
+                line: misspelling.line,
+                column: None,
+                highlight: Some(misspelling.word),
+                message: diagnostic.message,
+                suggestion: diagnostic.suggestion,
+            }
+        })
+        .collect()
+}
+
+/// `duplicate-heading`: report sections whose heading repeats a sibling's
+fn check_duplicate_headings(sections: &[SectionText<'_>], _: &LintOptions) -> Vec<RawFinding> {
+    let mut seen: BTreeMap<(Vec<u32>, String), &MarkdownSection> = BTreeMap::new();
+    let mut findings = Vec::new();
+    for text in sections {
+        let parts = text.section.section_number.parts();
+        let parent = parts[..parts.len().saturating_sub(1)].to_vec();
+        let key = (parent, text.section.heading_text.trim().to_lowercase());
+        match seen.get(&key) {
+            Some(first) => findings.push(RawFinding {
+                suggestion: Some(format!(
+                    "rename one of the sections (also used at {}:{})",
+                    first.source_file.display(),
+                    first.line_number
+                )),
+                ..text
+                    .finding(
+                        text.section.line_number,
+                        format!("Duplicate heading '{}'", text.section.heading_text),
+                    )
+                    .highlighting(&text.section.heading_text)
+            }),
+            None => {
+                seen.insert(key, text.section);
+            }
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_model::SectionNumber;
+    use std::path::Path;
+
+    fn source(content: &str) -> MarkdownSource {
+        let mut source = MarkdownSource {
+            path: PathBuf::from("src/01_reqs.md"),
+            absolute_path: PathBuf::from("/test/src/01_reqs.md"),
+            section_number: SectionNumber::parse("01").unwrap(),
+            title: String::new(),
+            raw_content: content.to_string(),
+            sections: Vec::new(),
+        };
+        source.parse(Path::new("/test")).unwrap();
+        source
+    }
+
+    fn summary(findings: &[LintFinding]) -> Vec<(&'static str, usize)> {
+        findings.iter().map(|f| (f.rule, f.line)).collect()
+    }
+
+    #[test]
+    fn test_requirement_language() {
+        let file = source(
+            "# Requirements\n\nThe system shall log errors, etc.\n\n## Logging\n\n```sysdoc\nsection_id = \"SRS-1\"\n```\n\nThe logger shall rotate files.\nIt shall also compress them and should be fast.\n\n## Shelling\n\n```sysdoc\nsection_id = \"SRS-2\"\n```\n\nThe marshall may be Shall-compliant.\n",
+        );
+        let findings = lint(&[file], None, None).unwrap();
+        assert_eq!(
+            summary(&findings),
+            vec![
+                ("vague-word", 3),
+                ("multiple-shall", 12),
+                ("weak-requirement-word", 12),
+                ("weak-requirement-word", 20),
+            ]
+        );
+        assert_eq!(
+            findings[1].to_string(),
+            "src/01_reqs.md:12: warning[multiple-shall]: Requirement 'SRS-1' contains 2 'shall' statements; split it into one requirement per 'shall'"
+        );
+        let located: Vec<(Option<usize>, Option<&str>)> = findings
+            .iter()
+            .map(|f| (f.column, f.highlight.as_deref()))
+            .collect();
+        assert_eq!(
+            located,
+            vec![
+                (Some(30), Some("etc.")),
+                (Some(4), Some("shall")),
+                (Some(33), Some("should")),
+                (Some(14), Some("may")),
+            ]
+        );
+        let diagnostic = Diagnostic::from(&findings[1]);
+        let span = diagnostic.span.unwrap();
+        assert_eq!((span.line, span.column), (12, Some(4)));
+    }
+
+    #[test]
+    fn test_structure_rules() {
+        let file = source(
+            "# Design\n\n#### Deep\n\nText.\n\n## Empty\n\n## Notes\n\nText.\n\n## Notes\n\nMore text.\n\n## Open Items\n\n```sysdoc\ngenerate_tbd_table = true\n```\n",
+        );
+        let findings = lint(&[file], None, None).unwrap();
+        assert_eq!(
+            summary(&findings),
+            vec![
+                ("heading-skip", 3),
+                ("empty-section", 7),
+                ("duplicate-heading", 13),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rule_configuration() {
+        let words = std::iter::repeat_n("word", 30)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let file = source(&format!(
+            "# Design\n\n{words}\n{words}\n\n- {words} {words}\n\nUse as needed.\n"
+        ));
+        let config: LintConfig = toml::from_str(
+            r#"
+max_paragraph_words = 50
+vague_words = ["as needed"]
+
+[rules]
+vague-word = "error"
+"#,
+        )
+        .unwrap();
+        let findings = lint(std::slice::from_ref(&file), Some(&config), None).unwrap();
+        assert_eq!(
+            summary(&findings),
+            vec![("long-paragraph", 3), ("vague-word", 8)]
+        );
+        assert_eq!(findings[1].severity, LintSeverity::Error);
+
+        let config: LintConfig = toml::from_str("[rules]\nlong-paragraph = \"off\"").unwrap();
+        let findings = lint(std::slice::from_ref(&file), Some(&config), None).unwrap();
+        assert!(findings.iter().all(|f| f.rule != "long-paragraph"));
+
+        let config: LintConfig = toml::from_str("[rules]\nno-such-rule = \"error\"").unwrap();
+        assert!(matches!(
+            lint(&[file], Some(&config), None),
+            Err(LintError::UnknownRule(name)) if name == "no-such-rule"
+        ));
+    }
+}
@@ -57,6 +57,28 @@ impl MarkdownSource {
         self.sections = sections;
         Ok(())
     }
+
+    /// Split the raw content into the source lines of each section
+    ///
+    /// # Returns
+    /// * Each section with its lines, from its heading line up to the line before
+    ///   the next section's heading (the first line is `section.line_number`)
+    pub fn section_lines(&self) -> Vec<(&MarkdownSection, Vec<&str>)> {
+        let lines: Vec<&str> = self.raw_content.lines().collect();
+        self.sections
+            .iter()
+            .enumerate()
+            .map(|(index, section)| {
+                let start = section.line_number.saturating_sub(1).min(lines.len());
+                let end = self
+                    .sections
+                    .get(index + 1)
+                    .map_or(lines.len(), |next| next.line_number.saturating_sub(1))
+                    .clamp(start, lines.len());
+                (section, lines[start..end].to_vec())
+            })
+            .collect()
+    }
 }
 
 /// A section within a markdown file (delimited by headings)
@@ -87,3 +109,19 @@ pub struct MarkdownSection {
     /// Optional metadata parsed from a `sysdoc` code block within this section
     pub metadata: Option<SectionMetadata>,
 }
+
+impl MarkdownSection {
+    /// Line of a metadata field in the source file
+    ///
+    /// # Parameters
+    /// * `field` - Field key as accepted by [`SectionMetadata::has_field`]
+    ///
+    /// # Returns
+    /// * The field's line, or the heading line if the field was not located
+    pub fn metadata_line(&self, field: &str) -> usize {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.field_line(field))
+            .unwrap_or(self.line_number)
+    }
+}
@@ -0,0 +1,237 @@
+//! TBD/TBR open items
+//!
+//! DID documents list every item still "to be determined" (TBD) or "to be
+//! resolved" (TBR). This module finds `TBD` and `TBR` markers in the parsed
+//! content of each section, with an optional number and note:
+//!
+//! ```markdown
+//! The watchdog timeout shall be TBD-003 (pending load analysis) ms.
+//! ```
+
+use super::blocks::MarkdownBlock;
+use super::markdown_source::{MarkdownSection, MarkdownSource};
+use super::section_number::SectionNumber;
+use super::text_run::TextRun;
+use super::types::Alignment;
+use regex::Regex;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+/// A `TBD`/`TBR` marker with an optional `-<number>` and `(note)`
+static MARKER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(TBD|TBR)(-\d+)?\b(?:\s*\(([^)]*)\))?").expect("valid open item regex")
+});
+
+/// A TBD or TBR marker found in the document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenItem {
+    /// The marker as written, e.g. "TBD" or "TBR-012"
+    pub marker: String,
+    /// Note in parentheses after the marker (empty if none)
+    pub note: String,
+    /// Section number of the section containing the marker
+    pub section_number: SectionNumber,
+    /// Heading text of the section containing the marker
+    pub heading: String,
+    /// Source file (relative to document root)
+    pub source_file: PathBuf,
+    /// Line number of the marker (the section heading for markers in CSV tables)
+    pub line: usize,
+}
+
+impl OpenItem {
+    /// Check whether the item is a TBR (to be resolved) rather than a TBD
+    pub fn is_tbr(&self) -> bool {
+        self.marker.starts_with("TBR")
+    }
+}
+
+/// A marker found in a piece of text
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Marker {
+    marker: String,
+    note: String,
+}
+
+/// Find the markers in a piece of text
+fn find_markers(text: &str) -> Vec<Marker> {
+    MARKER
+        .captures_iter(text)
+        .map(|captures| Marker {
+            marker: format!(
+                "{}{}",
+                &captures[1],
+                captures.get(2).map_or("", |number| number.as_str())
+            ),
+            note: captures
+                .get(3)
+                .map_or(String::new(), |note| note.as_str().trim().to_string()),
+        })
+        .collect()
+}
+
+/// Collect every open item in a set of source files, in document order
+///
+/// Markers are found in headings, paragraphs, lists, block quotes and tables
+/// (including CSV tables), but not in code. Each marker is located on the
+/// first source line of its section, after the previous marker, that contains it.
+pub fn collect_open_items(files: &[MarkdownSource]) -> Vec<OpenItem> {
+    let mut sorted: Vec<&MarkdownSource> = files.iter().collect();
+    sorted.sort_by(|a, b| a.section_number.cmp(&b.section_number));
+
+    let mut items = Vec::new();
+    for file in sorted {
+        for (section, lines) in file.section_lines() {
+            items.extend(section_open_items(section, &lines));
+        }
+    }
+    items
+}
+
+/// Collect the open items of a single section
+///
+/// # Parameters
+/// * `section` - The parsed section
+/// * `lines` - The section's source lines, starting with its heading line
+fn section_open_items(section: &MarkdownSection, lines: &[&str]) -> Vec<OpenItem> {
+    let mut texts = vec![section.heading_text.clone()];
+    section
+        .content
+        .iter()
+        .for_each(|block| block.collect_text(&|run| !run.code, &mut texts));
+
+    let mut cursor = (0, 0);
+    let mut items = Vec::new();
+    for marker in texts.iter().flat_map(|text| find_markers(text)) {
+        let found = locate_marker(lines, &mut cursor, &marker.marker);
+        items.push(OpenItem {
+            marker: marker.marker,
+            note: marker.note,
+            section_number: section.section_number.clone(),
+            heading: section.heading_text.clone(),
+            source_file: section.source_file.clone(),
+            line: found.map_or(section.line_number, |index| section.line_number + index),
+        });
+    }
+    items
+}
+
+/// Find the source line of a marker, after the markers already located
+///
+/// # Parameters
+/// * `lines` - The section's source lines
+/// * `cursor` - Line index and number of markers already located on that line;
+///   moved past the marker when it is found
+/// * `marker` - The marker as written, e.g. "TBD-2"
+///
+/// # Returns
+/// * Index of the line containing the marker, or `None` if no later line contains it
+fn locate_marker(lines: &[&str], cursor: &mut (usize, usize), marker: &str) -> Option<usize> {
+    let (start, located) = *cursor;
+    let (index, position) = lines
+        .iter()
+        .enumerate()
+        .skip(start)
+        .find_map(|(index, line)| {
+            let skip = if index == start { located } else { 0 };
+            find_markers(line)
+                .iter()
+                .skip(skip)
+                .position(|m| m.marker == marker)
+                .map(|position| (index, skip + position))
+        })?;
+    *cursor = (index, position + 1);
+    Some(index)
+}
+
+/// Build the "TBD/TBR List" table
+///
+/// # Returns
+/// * `MarkdownBlock::InlineTable` - One row per open item, in document order,
+///   with the item, its section number and heading, location and note
+pub fn build_open_items_table(items: &[OpenItem]) -> MarkdownBlock {
+    let text = |value: String| vec![TextRun::new(value)];
+    let headers = ["Item", "Section", "Title", "Location", "Note"]
+        .into_iter()
+        .map(|header| text(header.to_string()))
+        .collect();
+    let rows = items
+        .iter()
+        .map(|item| {
+            vec![
+                text(item.marker.clone()),
+                text(item.section_number.to_string()),
+                text(item.heading.clone()),
+                text(format!("{}:{}", item.source_file.display(), item.line)),
+                text(item.note.clone()),
+            ]
+        })
+        .collect();
+
+    MarkdownBlock::InlineTable {
+        alignments: vec![Alignment::None; 5],
+        headers,
+        rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_find_markers() {
+        let markers = find_markers(
+            "Timeout is TBD-003 (pending load analysis) ms, rate TBR, TBDs and STBD are not markers. TBD.",
+        );
+        let found: Vec<(&str, &str)> = markers
+            .iter()
+            .map(|m| (m.marker.as_str(), m.note.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("TBD-003", "pending load analysis"),
+                ("TBR", ""),
+                ("TBD", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_open_items() {
+        let mut source = MarkdownSource {
+            path: PathBuf::from("src/01_reqs.md"),
+            absolute_path: PathBuf::from("/test/src/01_reqs.md"),
+            section_number: SectionNumber::parse("01").unwrap(),
+            title: String::new(),
+            raw_content: "# Requirements\n\nRate is TBR.\n\n```text\nTBD in code\n```\n\n## Timing\n\n| Item | Value |\n|------|-------|\n| Timeout | TBD-2 (load analysis) |\n\nJitter is TBD.\n\nDrift is TBD, but `TBD` in code is not.\n".to_string(),
+            sections: Vec::new(),
+        };
+        source.parse(Path::new("/test")).unwrap();
+
+        let items = collect_open_items(&[source]);
+        let found: Vec<(&str, String, usize, &str)> = items
+            .iter()
+            .map(|item| {
+                (
+                    item.marker.as_str(),
+                    item.section_number.to_string(),
+                    item.line,
+                    item.note.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("TBR", "1".to_string(), 3, ""),
+                ("TBD-2", "1.1".to_string(), 13, "load analysis"),
+                ("TBD", "1.1".to_string(), 15, ""),
+                ("TBD", "1.1".to_string(), 17, ""),
+            ]
+        );
+        assert!(items[0].is_tbr());
+    }
+}
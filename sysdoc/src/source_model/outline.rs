@@ -0,0 +1,401 @@
+//! Explicit document outline
+//!
+//! By default the section number of a markdown file is encoded in its name
+//! (`01.02_purpose.md`). A document may instead list its files in order, either
+//! with `[outline]` in sysdoc.toml or in `src/SUMMARY.md`:
+//!
+//! ```markdown
+//! # Summary
+//!
+//! - [Introduction](introduction.md)
+//!   - [Purpose](purpose.md)
+//! - 9 [Glossary](glossary.md)
+//! ```
+//!
+//! Each file is numbered after the file before it at its level: nesting (or
+//! `level` in sysdoc.toml) makes it a subsection, and a number in front of the
+//! link (or `number` in sysdoc.toml) overrides the computed one.
+
+use super::section_number::{SectionNumber, MAX_SECTION_DEPTH};
+use crate::diagnostics::Diagnostic;
+use crate::document_config::{DocumentConfig, OutlineFileConfig};
+use crate::paths::{normalize, SOURCE_DIR};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use thiserror::Error;
+
+/// Name of the outline file in the `src/` directory
+pub const SUMMARY_FILE: &str = "SUMMARY.md";
+
+/// A list item linking to a file, optionally preceded by a section number
+static SUMMARY_ITEM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\s*)[-*+]\s+(?:(\d+(?:\.\d+)*)\s+)?\[([^\]]*)\]\(([^)\s]+)\)\s*$")
+        .expect("valid summary item regex")
+});
+
+/// A list item of any kind
+static LIST_ITEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*[-*+]\s").expect("valid list item regex"));
+
+/// Errors loading a document outline
+#[derive(Error, Debug)]
+pub enum OutlineError {
+    #[error("Failed to read {path}: {source}", path = .0.display(), source = .1)]
+    Io(PathBuf, #[source] std::io::Error),
+
+    #[error(
+        "The document has both [outline] in sysdoc.toml and src/{SUMMARY_FILE}; keep one of them"
+    )]
+    Conflict,
+
+    #[error("Line {0}: expected a list item linking to a file, like '- [Purpose](purpose.md)'")]
+    InvalidItem(usize),
+
+    #[error("Invalid section number '{number}' for '{path}'", path = .path.display())]
+    InvalidNumber { path: PathBuf, number: String },
+
+    #[error("'{path}' is at level {level}, but the file before it is at level {previous}", path = .path.display())]
+    LevelSkipped {
+        path: PathBuf,
+        level: usize,
+        previous: usize,
+    },
+
+    #[error("'{path}' is nested deeper than the maximum of {MAX_SECTION_DEPTH} levels", path = .0.display())]
+    TooDeep(PathBuf),
+
+    #[error("'{path}' is not a markdown file under src/", path = .0.display())]
+    MissingFile(PathBuf),
+
+    #[error("'{path}' is listed more than once", path = .0.display())]
+    Duplicate(PathBuf),
+
+    #[error("'{path}' is outside the document root", path = .0.display())]
+    OutsideRoot(PathBuf),
+}
+
+impl OutlineError {
+    /// Line of the outline file the error was found on, if known
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::InvalidItem(line) => Some(*line),
+            _ => None,
+        }
+    }
+}
+
+/// A markdown file placed by the outline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineEntry {
+    /// Path to the file (relative to document root)
+    pub path: PathBuf,
+
+    /// Section number computed from the file's position, or its override
+    pub number: SectionNumber,
+
+    /// Title given by the outline (None to derive it from the file name)
+    pub title: Option<String>,
+}
+
+/// The files of a document in order, with their section numbers
+#[derive(Debug, Clone, Default)]
+pub struct Outline {
+    /// File that declares the outline (sysdoc.toml or src/SUMMARY.md), relative to document root
+    pub source: PathBuf,
+
+    /// Listed files in document order
+    pub entries: Vec<OutlineEntry>,
+}
+
+impl Outline {
+    /// Load the outline of a document, if it declares one
+    ///
+    /// # Parameters
+    /// * `root` - Root directory of the document
+    /// * `config` - Document configuration (for `[outline]`)
+    ///
+    /// # Returns
+    /// * `Ok(Some(Outline))` - The listed files with their section numbers
+    /// * `Ok(None)` - The document has neither `[outline]` nor `src/SUMMARY.md`
+    /// * `Err(OutlineError)` - The outline cannot be read, or lists a file twice,
+    ///   a missing file, a file outside the document root, an invalid number or
+    ///   a skipped level
+    pub fn load(root: &Path, config: &DocumentConfig) -> Result<Option<Self>, OutlineError> {
+        let summary_path = root.join(SOURCE_DIR).join(SUMMARY_FILE);
+        let (source, items) = match (&config.outline, summary_path.is_file()) {
+            (Some(_), true) => return Err(OutlineError::Conflict),
+            (Some(outline), false) => (
+                PathBuf::from("sysdoc.toml"),
+                outline
+                    .files
+                    .iter()
+                    .map(OutlineItem::try_from)
+                    .collect::<Result<_, _>>()?,
+            ),
+            (None, true) => {
+                let content = std::fs::read_to_string(&summary_path)
+                    .map_err(|e| OutlineError::Io(summary_path.clone(), e))?;
+                let source = Path::new(SOURCE_DIR).join(SUMMARY_FILE);
+                (source, parse_summary(&content)?)
+            }
+            (None, false) => return Ok(None),
+        };
+
+        let entries = number_items(items)?;
+        let mut seen = std::collections::HashSet::new();
+        for entry in &entries {
+            if !seen.insert(&entry.path) {
+                return Err(OutlineError::Duplicate(entry.path.clone()));
+            }
+            let is_source = entry.path.starts_with(SOURCE_DIR)
+                && entry.path.extension().is_some_and(|ext| ext == "md");
+            if !is_source || !root.join(&entry.path).is_file() {
+                return Err(OutlineError::MissingFile(entry.path.clone()));
+            }
+        }
+        Ok(Some(Self { source, entries }))
+    }
+
+    /// Find the entry of a file
+    ///
+    /// # Parameters
+    /// * `path` - Path to the file (relative to document root)
+    pub fn entry(&self, path: &Path) -> Option<&OutlineEntry> {
+        self.entries.iter().find(|entry| entry.path == path)
+    }
+}
+
+/// A markdown file under `src/` that is neither numbered nor listed in the outline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnlistedFile {
+    /// Path of the file (relative to document root)
+    pub path: PathBuf,
+}
+
+impl From<&UnlistedFile> for Diagnostic {
+    fn from(file: &UnlistedFile) -> Self {
+        Diagnostic::warning(
+            "unlisted-file",
+            format!(
+                "Markdown file '{}' is not part of the document",
+                file.path.display()
+            ),
+        )
+        .in_file(&file.path)
+        .with_suggestion(
+            "number the file name (e.g., '01.02_name.md') or list it in [outline] or src/SUMMARY.md",
+        )
+    }
+}
+
+/// A listed file before numbering
+#[derive(Debug)]
+struct OutlineItem {
+    /// Path to the file (relative to document root)
+    path: PathBuf,
+    number: Option<String>,
+    /// Outline level (None to keep the previous file's level)
+    level: Option<usize>,
+    title: Option<String>,
+}
+
+impl TryFrom<&OutlineFileConfig> for OutlineItem {
+    type Error = OutlineError;
+
+    fn try_from(file: &OutlineFileConfig) -> Result<Self, OutlineError> {
+        Ok(match file {
+            OutlineFileConfig::Path(path) => Self {
+                path: resolve(Path::new(path))?,
+                number: None,
+                level: None,
+                title: None,
+            },
+            OutlineFileConfig::Entry {
+                file,
+                number,
+                level,
+                title,
+            } => Self {
+                path: resolve(Path::new(file))?,
+                number: number.clone(),
+                level: *level,
+                title: title.clone(),
+            },
+        })
+    }
+}
+
+/// Parse the list items of `src/SUMMARY.md`
+///
+/// Link targets are relative to `src/`; the nesting of an item gives its level.
+/// Lines that are not list items, such as headings, are ignored.
+fn parse_summary(content: &str) -> Result<Vec<OutlineItem>, OutlineError> {
+    let mut items = Vec::new();
+    let mut indents: Vec<usize> = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if !LIST_ITEM.is_match(line) {
+            continue;
+        }
+        let captures = SUMMARY_ITEM
+            .captures(line)
+            .ok_or(OutlineError::InvalidItem(index + 1))?;
+        let indent = captures[1].replace('\t', "    ").len();
+        while indents.last().is_some_and(|&last| last > indent) {
+            indents.pop();
+        }
+        if indents.last() != Some(&indent) {
+            indents.push(indent);
+        }
+        items.push(OutlineItem {
+            path: resolve(&Path::new(SOURCE_DIR).join(&captures[4]))?,
+            number: captures.get(2).map(|number| number.as_str().to_string()),
+            level: Some(indents.len()),
+            title: Some(captures[3].trim().to_string()).filter(|title| !title.is_empty()),
+        });
+    }
+    Ok(items)
+}
+
+/// Number the listed files in order
+///
+/// A file without a number override follows the previous file at its level:
+/// the first file at a new level is numbered 1, and the deeper levels restart.
+fn number_items(items: Vec<OutlineItem>) -> Result<Vec<OutlineEntry>, OutlineError> {
+    let mut counters: Vec<u32> = Vec::new();
+    let mut entries = Vec::with_capacity(items.len());
+    for item in items {
+        counters = match &item.number {
+            Some(number) => override_counters(&item.path, number)?,
+            None => next_counters(&item.path, counters, item.level)?,
+        };
+        let number = counters
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(".");
+        entries.push(OutlineEntry {
+            number: SectionNumber::parse(&number)
+                .ok_or(OutlineError::TooDeep(item.path.clone()))?,
+            path: item.path,
+            title: item.title,
+        });
+    }
+    Ok(entries)
+}
+
+/// Counters set by a number override (a trailing `.0` marker is dropped)
+fn override_counters(path: &Path, number: &str) -> Result<Vec<u32>, OutlineError> {
+    let invalid = || OutlineError::InvalidNumber {
+        path: path.to_path_buf(),
+        number: number.to_string(),
+    };
+    let parsed = SectionNumber::parse(number).ok_or_else(invalid)?;
+    let parsed = parsed.without_parent_marker().unwrap_or(parsed);
+    if parsed.parts().contains(&0) {
+        return Err(invalid());
+    }
+    Ok(parsed.parts().to_vec())
+}
+
+/// Counters of the file after `counters`, at `level` (or the same level)
+fn next_counters(
+    path: &Path,
+    mut counters: Vec<u32>,
+    level: Option<usize>,
+) -> Result<Vec<u32>, OutlineError> {
+    let level = level.unwrap_or(counters.len().max(1));
+    if level == 0 || level > counters.len() + 1 {
+        return Err(OutlineError::LevelSkipped {
+            path: path.to_path_buf(),
+            level,
+            previous: counters.len(),
+        });
+    }
+    if level > MAX_SECTION_DEPTH {
+        return Err(OutlineError::TooDeep(path.to_path_buf()));
+    }
+    counters.truncate(level);
+    if counters.len() < level {
+        counters.push(1);
+    } else if let Some(last) = counters.last_mut() {
+        *last += 1;
+    }
+    Ok(counters)
+}
+
+/// Resolve `.` and `..` components of a listed path (relative to document root)
+fn resolve(path: &Path) -> Result<PathBuf, OutlineError> {
+    normalize(path).ok_or_else(|| OutlineError::OutsideRoot(path.to_path_buf()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(entries: &[OutlineEntry]) -> Vec<String> {
+        entries.iter().map(|e| e.number.to_string()).collect()
+    }
+
+    #[test]
+    fn test_summary_nesting_and_overrides() {
+        let summary = "# Summary\n\n\
+            - [Introduction](introduction.md)\n  \
+              - [Purpose](./intro/purpose.md)\n  \
+              - [Scope](intro/scope.md)\n\
+            - [Design](design.md)\n    \
+                - 2.5 [Storage](storage.md)\n    \
+                - [Network](network.md)\n\
+            - 9 [Glossary](glossary.md)\n";
+
+        let entries = number_items(parse_summary(summary).unwrap()).unwrap();
+
+        assert_eq!(
+            numbers(&entries),
+            ["1", "1.1", "1.2", "2", "2.5", "2.6", "9"]
+        );
+        assert_eq!(entries[1].path, PathBuf::from("src/intro/purpose.md"));
+        assert_eq!(entries[1].title.as_deref(), Some("Purpose"));
+        assert!(matches!(
+            parse_summary("- [Draft]()\n"),
+            Err(OutlineError::InvalidItem(1))
+        ));
+        assert!(matches!(
+            parse_summary("- [Notes](../../notes.md)\n"),
+            Err(OutlineError::OutsideRoot(_))
+        ));
+        assert!(matches!(
+            OutlineItem::try_from(&OutlineFileConfig::Path("../notes.md".to_string())),
+            Err(OutlineError::OutsideRoot(_))
+        ));
+    }
+
+    #[test]
+    fn test_config_levels() {
+        let item = |path: &str, number: Option<&str>, level: Option<usize>| OutlineItem {
+            path: PathBuf::from(path),
+            number: number.map(str::to_string),
+            level,
+            title: None,
+        };
+
+        let entries = number_items(vec![
+            item("src/a.md", None, None),
+            item("src/b.md", None, Some(2)),
+            item("src/c.md", None, None),
+            item("src/d.md", Some("03.00"), None),
+            item("src/e.md", None, Some(1)),
+        ])
+        .unwrap();
+        assert_eq!(numbers(&entries), ["1", "1.1", "1.2", "3", "4"]);
+
+        assert!(matches!(
+            number_items(vec![item("src/a.md", None, Some(2))]),
+            Err(OutlineError::LevelSkipped { level: 2, .. })
+        ));
+        assert!(matches!(
+            number_items(vec![item("src/a.md", Some("1.x"), None)]),
+            Err(OutlineError::InvalidNumber { .. })
+        ));
+    }
+}
@@ -9,7 +9,7 @@ use super::markdown_source::MarkdownSection;
 use super::section_metadata::SectionMetadata;
 use super::section_number::SectionNumber;
 use super::text_run::{TextFormatting, TextRun};
-use super::types::Alignment;
+use super::types::{Alignment, SourceSpan};
 use pulldown_cmark::{Event, Tag, TagEnd};
 use std::path::{Path, PathBuf};
 
@@ -58,6 +58,9 @@ pub struct MarkdownParser {
     /// Current line number being processed (1-indexed)
     current_line_number: usize,
 
+    /// Byte range of the event being processed
+    current_range: std::ops::Range<usize>,
+
     /// Source file path (relative to document root)
     source_file: PathBuf,
 
@@ -71,6 +74,8 @@ struct CodeBlockContext {
     language: Option<String>,
     /// Accumulated code content
     content: String,
+    /// Byte offset of the first line of content in the source file
+    content_start: Option<usize>,
 }
 
 /// Context for building a list
@@ -141,6 +146,7 @@ impl MarkdownParser {
             current_code_block: None,
             source_content: String::new(),
             current_line_number: 1,
+            current_range: 0..0,
             source_file,
             metadata_errors: Vec::new(),
         }
@@ -181,6 +187,7 @@ impl MarkdownParser {
 
         for (event, range) in md_parser.into_offset_iter() {
             let line_number = parser.byte_offset_to_line(range.start);
+            parser.current_range = range;
             parser.process_event_with_line(event, line_number);
         }
 
@@ -436,6 +443,11 @@ impl MarkdownParser {
             + 1 // 1-indexed
     }
 
+    /// Location of the event being processed
+    fn current_span(&self) -> SourceSpan {
+        SourceSpan::new(&self.source_content, self.current_range.clone())
+    }
+
     /// Process a single markdown event with line number tracking
     fn process_event_with_line(&mut self, event: Event<'_>, line_number: usize) {
         self.current_line_number = line_number;
@@ -539,6 +551,7 @@ impl MarkdownParser {
                     // Regular link - track formatting
                     self.formatting.link_url = Some(url);
                     self.formatting.link_title = (!title.is_empty()).then(|| title.to_string());
+                    self.formatting.link_span = Some(self.current_span());
                 }
             }
             Tag::Image {
@@ -612,6 +625,7 @@ impl MarkdownParser {
             TagEnd::Link => {
                 self.formatting.link_url = None;
                 self.formatting.link_title = None;
+                self.formatting.link_span = None;
             }
             TagEnd::Image => {
                 // Images handled in start tag
@@ -633,6 +647,9 @@ impl MarkdownParser {
 
         // If we're in a code block, append to code block content
         if let Some(code_block) = self.current_code_block.as_mut() {
+            code_block
+                .content_start
+                .get_or_insert(self.current_range.start);
             code_block.content.push_str(&text);
             return;
         }
@@ -765,6 +782,7 @@ impl MarkdownParser {
         self.current_code_block = Some(CodeBlockContext {
             language,
             content: String::new(),
+            content_start: None,
         });
     }
 
@@ -782,7 +800,8 @@ impl MarkdownParser {
             .is_some_and(|lang| lang == "sysdoc" || lang.contains("{sysdoc}"));
 
         if is_sysdoc {
-            self.handle_sysdoc_metadata(&code_block.content);
+            let content_start = code_block.content_start.unwrap_or(self.current_range.start);
+            self.handle_sysdoc_metadata(&code_block.content, content_start);
             return;
         }
 
@@ -797,19 +816,28 @@ impl MarkdownParser {
     }
 
     /// Handle sysdoc metadata block content
-    fn handle_sysdoc_metadata(&mut self, content: &str) {
+    ///
+    /// # Parameters
+    /// * `content` - TOML content of the block
+    /// * `content_start` - Byte offset of the content in the source file
+    fn handle_sysdoc_metadata(&mut self, content: &str, content_start: usize) {
         match SectionMetadata::parse(content) {
-            Ok(metadata) => {
+            Ok(mut metadata) => {
+                metadata.locate_fields(content, &self.source_content, content_start);
                 // Store metadata in the current section
                 if let Some(section) = self.current_section.as_mut() {
                     section.metadata = Some(metadata);
                 }
             }
             Err(err) => {
-                // Store the error to be reported at the end of parsing
+                // Store the error to be reported at the end of parsing, at the
+                // line of the offending TOML when the error has a location
+                let line_number = err.span().map_or(self.current_line_number, |span| {
+                    self.byte_offset_to_line(content_start + span.start)
+                });
                 self.metadata_errors
                     .push(SourceModelError::MetadataParseError {
-                        line_number: self.current_line_number,
+                        line_number,
                         error: err.to_string(),
                     });
             }
@@ -841,6 +869,7 @@ impl MarkdownParser {
             title,
             format,
             exists,
+            span: self.current_span(),
         };
 
         self.add_block(block);
@@ -866,6 +895,7 @@ impl MarkdownParser {
             absolute_path,
             exists,
             data,
+            span: self.current_span(),
         };
 
         self.add_block(block);
@@ -1051,7 +1081,12 @@ impl MarkdownParser {
         // If metadata specifies include_file, load it and append as a code block
         if let Some(ref metadata) = section.metadata {
             if let Some(ref include_path) = metadata.include_file {
-                let included_block = self.create_included_code_block(include_path);
+                let span = metadata
+                    .field_spans
+                    .get("include_file")
+                    .cloned()
+                    .unwrap_or_default();
+                let included_block = self.create_included_code_block(include_path, span);
                 content.push(included_block);
             }
         }
@@ -1068,7 +1103,7 @@ impl MarkdownParser {
     }
 
     /// Create an IncludedCodeBlock from a file path specified in metadata
-    fn create_included_code_block(&self, include_path: &str) -> MarkdownBlock {
+    fn create_included_code_block(&self, include_path: &str, span: SourceSpan) -> MarkdownBlock {
         let path = PathBuf::from(include_path);
         let absolute_path = self.document_root.join(&path);
         let exists = absolute_path.exists();
@@ -1092,6 +1127,7 @@ impl MarkdownParser {
             language,
             content,
             exists,
+            span,
         }
     }
 }
@@ -2160,9 +2196,11 @@ Second table: [table2](table2.csv)"#;
                 title: _,
                 format: _,
                 exists: _,
+                span,
             } => {
-                // Verify path is correct
+                // Verify path and location are correct
                 assert_eq!(path, &PathBuf::from("image.png"));
+                assert_eq!((span.line, span.column), (3, 1));
                 // Note: alt_text handling may vary based on when image is extracted
                 // from the event stream (before or after text events are processed)
                 // absolute_path will be "./image.png" since we use "." as document root in tests
@@ -2172,6 +2210,43 @@ Second table: [table2](table2.csv)"#;
         }
     }
 
+    #[test]
+    fn test_parse_link_and_metadata_spans() {
+        // Arrange: A link on a later line, and a sysdoc block nested in a list
+        let markdown = "# Links\n\nSee [the design](design.md) for details.\n\n\
+                        - Item\n\n  ```sysdoc\n  section_id = \"SRS-1\"\n  traced_ids = [\n    \"SYS-1\",\n    \"SYS-2\",\n  ]\n\n  [attributes]\n  owner = \"J. Smith\"\n  ```\n";
+
+        // Act: Parse the markdown
+        let sections = MarkdownParser::parse(
+            markdown,
+            &PathBuf::from("."),
+            &test_section_number(),
+            &PathBuf::from("test.md"),
+        )
+        .unwrap();
+
+        // Assert: The link run carries the link's location
+        let link_span = sections[0]
+            .content
+            .iter()
+            .find_map(|block| match block {
+                MarkdownBlock::Paragraph(runs) => runs.iter().find_map(|r| r.link_span.clone()),
+                _ => None,
+            })
+            .expect("Should contain a link");
+        assert_eq!((link_span.line, link_span.column), (3, 5));
+
+        // Assert: Metadata fields are located, including values of multi-line arrays
+        let metadata = sections[0].metadata.as_ref().expect("Should have metadata");
+        assert_eq!(metadata.field_line("section_id"), Some(8));
+        assert_eq!(metadata.field_line("traced_ids"), Some(9));
+        assert_eq!(metadata.field_line("attributes.owner"), Some(15));
+        let value = metadata
+            .value_span(markdown, "traced_ids", "SYS-2")
+            .unwrap();
+        assert_eq!((value.line, value.column), (11, 5));
+    }
+
     #[test]
     fn test_parse_empty_content() {
         // Arrange: Empty string (this test now expects an error since no heading)
@@ -0,0 +1,822 @@
+//! Metadata query tables
+//!
+//! A `[[query_table]]` entry in a sysdoc block generates a table of the sections
+//! selected by a small query language over section metadata and attributes:
+//!
+//! ```text
+//! select section_id, heading, attributes.protocol
+//! where attributes.protocol = 'CAN' and status != 'Approved'
+//! order by section_number desc
+//! ```
+//!
+//! Queries are parsed when the sysdoc block is parsed and evaluated over every
+//! section of the document once all files are parsed.
+
+use super::attributes::AttributeValue;
+use super::blocks::MarkdownBlock;
+use super::markdown_source::MarkdownSection;
+use super::section_number::SectionNumber;
+use super::text_run::TextRun;
+use super::traceability::{group_by_key, join_distinct};
+use super::types::Alignment;
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+/// A section property that queries can select, filter, group and sort by
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryField {
+    /// The section's `section_id`
+    SectionId,
+    /// The section's calculated section number
+    SectionNumber,
+    /// The section's heading text
+    Heading,
+    /// The source file the section was parsed from
+    SourceFile,
+    /// The `status` field of the section metadata
+    Status,
+    /// The `verification_method` field of the section metadata
+    VerificationMethod,
+    /// The IDs the section traces to
+    TracedIds,
+    /// A custom attribute of the section
+    Attribute(String),
+}
+
+impl QueryField {
+    /// Look up a field by its name in a query
+    fn parse(name: &str) -> Option<Self> {
+        let field = match name {
+            "section_id" => Self::SectionId,
+            "section_number" => Self::SectionNumber,
+            "heading" => Self::Heading,
+            "source_file" => Self::SourceFile,
+            "status" => Self::Status,
+            "verification_method" => Self::VerificationMethod,
+            "traced_ids" => Self::TracedIds,
+            _ => Self::Attribute(name.strip_prefix("attributes.")?.to_string()),
+        };
+        match &field {
+            Self::Attribute(attribute) if attribute.is_empty() => None,
+            _ => Some(field),
+        }
+    }
+
+    /// Default header text for a column showing this field
+    fn default_header(&self) -> &str {
+        match self {
+            Self::SectionId => "Section ID",
+            Self::SectionNumber => "Section",
+            Self::Heading => "Title",
+            Self::SourceFile => "Source File",
+            Self::Status => "Status",
+            Self::VerificationMethod => "Verification Method",
+            Self::TracedIds => "Traced IDs",
+            Self::Attribute(name) => name,
+        }
+    }
+
+    /// Values of this field for a section (empty if unset, several for lists)
+    fn values(&self, section: &MarkdownSection) -> Vec<String> {
+        let metadata = section.metadata.as_ref();
+        match self {
+            Self::SectionId => metadata
+                .and_then(|m| m.section_id.clone())
+                .into_iter()
+                .collect(),
+            Self::SectionNumber => vec![section.section_number.to_string()],
+            Self::Heading => vec![section.heading_text.clone()],
+            Self::SourceFile => vec![section.source_file.display().to_string()],
+            Self::Status => metadata
+                .and_then(|m| m.status.clone())
+                .into_iter()
+                .collect(),
+            Self::VerificationMethod => metadata
+                .and_then(|m| m.verification_method.clone())
+                .into_iter()
+                .collect(),
+            Self::TracedIds => metadata
+                .and_then(|m| m.traced_ids.clone())
+                .unwrap_or_default(),
+            Self::Attribute(name) => match metadata.and_then(|m| m.attributes.get(name)) {
+                Some(AttributeValue::List(items)) => items.clone(),
+                Some(value) => vec![value.to_string()],
+                None => Vec::new(),
+            },
+        }
+    }
+
+    /// Display value of this field for a section
+    fn display(&self, section: &MarkdownSection) -> String {
+        self.values(section).join(", ")
+    }
+
+    /// Compare two values of this field
+    ///
+    /// Section numbers compare numerically (3.10 after 3.9), as do integers
+    /// and floats; everything else compares as text.
+    fn compare(&self, left: &str, right: &str) -> Ordering {
+        if *self == Self::SectionNumber {
+            if let (Some(left), Some(right)) =
+                (SectionNumber::parse(left), SectionNumber::parse(right))
+            {
+                return left.cmp(&right);
+            }
+        }
+        if let (Ok(left), Ok(right)) = (left.parse::<i64>(), right.parse::<i64>()) {
+            return left.cmp(&right);
+        }
+        match (left.parse::<f64>(), right.parse::<f64>()) {
+            (Ok(left), Ok(right)) => left.total_cmp(&right),
+            _ => left.cmp(right),
+        }
+    }
+}
+
+/// A column of a query table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryColumn {
+    /// The value of a field
+    Field(QueryField),
+    /// The number of sections in the row's group (requires `group by`)
+    Count,
+}
+
+/// A comparison operator in a `where` clause
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    /// `=`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `<`
+    Less,
+    /// `<=`
+    LessOrEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterOrEqual,
+}
+
+impl CompareOp {
+    /// Check whether the operator holds for the ordering of a value against the literal
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Equal => ordering.is_eq(),
+            Self::NotEqual => ordering.is_ne(),
+            Self::Less => ordering.is_lt(),
+            Self::LessOrEqual => ordering.is_le(),
+            Self::Greater => ordering.is_gt(),
+            Self::GreaterOrEqual => ordering.is_ge(),
+        }
+    }
+}
+
+/// A `where` condition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    /// All conditions hold
+    And(Vec<Condition>),
+    /// Any condition holds
+    Or(Vec<Condition>),
+    /// The condition does not hold
+    Not(Box<Condition>),
+    /// A value of the field compares to the literal as given (for `!=`, no value equals it)
+    Compare {
+        field: QueryField,
+        op: CompareOp,
+        value: String,
+    },
+    /// A value of the field contains the literal as a substring
+    Contains { field: QueryField, value: String },
+    /// A value of the field equals one of the literals
+    In {
+        field: QueryField,
+        values: Vec<String>,
+    },
+    /// The field has a value
+    IsSet(QueryField),
+}
+
+impl Condition {
+    /// Evaluate the condition for a section
+    ///
+    /// Follows SQL NULL semantics: a comparison with a field the section does
+    /// not set is unknown, and a section is only selected when its condition
+    /// holds, so neither `status = 'x'` nor `status != 'x'` selects a section
+    /// without a status.
+    ///
+    /// # Returns
+    /// * `Some(true)` / `Some(false)` - The condition holds / does not hold
+    /// * `None` - The condition is unknown because a compared field is not set
+    fn matches(&self, section: &MarkdownSection) -> Option<bool> {
+        match self {
+            Self::And(conditions) => {
+                let results: Vec<Option<bool>> =
+                    conditions.iter().map(|c| c.matches(section)).collect();
+                if results.contains(&Some(false)) {
+                    Some(false)
+                } else if results.contains(&None) {
+                    None
+                } else {
+                    Some(true)
+                }
+            }
+            Self::Or(conditions) => {
+                let results: Vec<Option<bool>> =
+                    conditions.iter().map(|c| c.matches(section)).collect();
+                if results.contains(&Some(true)) {
+                    Some(true)
+                } else if results.contains(&None) {
+                    None
+                } else {
+                    Some(false)
+                }
+            }
+            Self::Not(condition) => condition.matches(section).map(|holds| !holds),
+            Self::Compare { field, op, value } => match op {
+                CompareOp::NotEqual => any_value(field, section, |actual| {
+                    field.compare(actual, value).is_eq()
+                })
+                .map(|equal| !equal),
+                op => any_value(field, section, |actual| {
+                    op.holds(field.compare(actual, value))
+                }),
+            },
+            Self::Contains { field, value } => {
+                any_value(field, section, |actual| actual.contains(value.as_str()))
+            }
+            Self::In { field, values } => any_value(field, section, |actual| {
+                values
+                    .iter()
+                    .any(|value| field.compare(actual, value).is_eq())
+            }),
+            Self::IsSet(field) => Some(!field.values(section).is_empty()),
+        }
+    }
+}
+
+/// Check whether any value of a field satisfies a predicate
+///
+/// # Returns
+/// * `Some(bool)` - Whether any value satisfies the predicate
+/// * `None` - The section does not set the field
+fn any_value(
+    field: &QueryField,
+    section: &MarkdownSection,
+    predicate: impl Fn(&str) -> bool,
+) -> Option<bool> {
+    let values = field.values(section);
+    (!values.is_empty()).then(|| values.iter().any(|actual| predicate(actual)))
+}
+
+/// Compare two sections by the `order by` items of a query, in turn
+fn compare_sections(
+    order_by: &[(QueryField, bool)],
+    a: &MarkdownSection,
+    b: &MarkdownSection,
+) -> Ordering {
+    for (field, descending) in order_by {
+        let ordering = field.compare(&field.display(a), &field.display(b));
+        let ordering = if *descending {
+            ordering.reverse()
+        } else {
+            ordering
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// A parsed query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    /// Columns of the table, in display order
+    pub select: Vec<QueryColumn>,
+    /// Condition a section must meet to be included
+    pub filter: Option<Condition>,
+    /// Field whose equal values are merged into a single row
+    pub group_by: Option<QueryField>,
+    /// Fields to sort by, each descending if the flag is set
+    pub order_by: Vec<(QueryField, bool)>,
+}
+
+/// A token of the query language
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// A keyword, field name or bare value
+    Word(String),
+    /// A quoted string
+    Text(String),
+    Comma,
+    OpenParen,
+    CloseParen,
+    Operator(CompareOp),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Word(word) => write!(f, "'{word}'"),
+            Self::Text(text) => write!(f, "string '{text}'"),
+            Self::Comma => f.write_str("','"),
+            Self::OpenParen => f.write_str("'('"),
+            Self::CloseParen => f.write_str("')'"),
+            Self::Operator(_) => f.write_str("operator"),
+        }
+    }
+}
+
+/// Split a query into tokens
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            ',' => Token::Comma,
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            '=' => Token::Operator(CompareOp::Equal),
+            '!' if chars.next_if_eq(&'=').is_some() => Token::Operator(CompareOp::NotEqual),
+            '<' if chars.next_if_eq(&'=').is_some() => Token::Operator(CompareOp::LessOrEqual),
+            '<' => Token::Operator(CompareOp::Less),
+            '>' if chars.next_if_eq(&'=').is_some() => Token::Operator(CompareOp::GreaterOrEqual),
+            '>' => Token::Operator(CompareOp::Greater),
+            '\'' | '"' => Token::Text(quoted(&mut chars, c)?),
+            c if is_word_char(c) => {
+                let mut word = c.to_string();
+                while let Some(next) = chars.next_if(|&next| is_word_char(next)) {
+                    word.push(next);
+                }
+                Token::Word(word)
+            }
+            c => return Err(format!("unexpected character '{c}'")),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Read a quoted string up to its closing quote
+fn quoted(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    quote: char,
+) -> Result<String, String> {
+    let mut text = String::new();
+    for c in chars.by_ref() {
+        if c == quote {
+            return Ok(text);
+        }
+        text.push(c);
+    }
+    Err(format!("unterminated string {quote}{text}"))
+}
+
+/// Check whether a character can appear in a field name or bare value
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '-')
+}
+
+/// Recursive descent parser over query tokens
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    /// The next token, if any
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    /// Consume the next token
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Consume the next token if it is the given keyword (case-insensitive)
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found =
+            matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    /// Consume the given keyword or fail
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), String> {
+        if self.keyword(keyword) {
+            Ok(())
+        } else {
+            Err(format!("expected '{keyword}'"))
+        }
+    }
+
+    /// Consume the next token if it equals the given token
+    fn token(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    /// Parse a field name
+    fn field(&mut self) -> Result<QueryField, String> {
+        match self.next() {
+            Some(Token::Word(name)) => QueryField::parse(&name).ok_or_else(|| {
+                format!("unknown field '{name}' (custom attributes are named 'attributes.<name>')")
+            }),
+            _ => Err("expected a field name".to_string()),
+        }
+    }
+
+    /// Parse a literal value (quoted string or bare word such as a number)
+    fn value(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Text(text)) | Some(Token::Word(text)) => Ok(text),
+            _ => Err("expected a value".to_string()),
+        }
+    }
+
+    /// Parse a complete query
+    fn query(&mut self) -> Result<Query, String> {
+        self.expect_keyword("select")?;
+        let mut select = vec![self.column()?];
+        while self.token(&Token::Comma) {
+            select.push(self.column()?);
+        }
+
+        let filter = if self.keyword("where") {
+            Some(self.or()?)
+        } else {
+            None
+        };
+
+        let group_by = if self.keyword("group") {
+            self.expect_keyword("by")?;
+            Some(self.field()?)
+        } else {
+            None
+        };
+
+        let mut order_by = Vec::new();
+        if self.keyword("order") {
+            self.expect_keyword("by")?;
+            order_by.push(self.order_item()?);
+            while self.token(&Token::Comma) {
+                order_by.push(self.order_item()?);
+            }
+        }
+
+        if let Some(token) = self.peek() {
+            return Err(format!("unexpected {token} after the end of the query"));
+        }
+        if group_by.is_none() && select.contains(&QueryColumn::Count) {
+            return Err("'count' requires 'group by'".to_string());
+        }
+        Ok(Query {
+            select,
+            filter,
+            group_by,
+            order_by,
+        })
+    }
+
+    /// Parse a selected column
+    fn column(&mut self) -> Result<QueryColumn, String> {
+        if self.keyword("count") {
+            Ok(QueryColumn::Count)
+        } else {
+            self.field().map(QueryColumn::Field)
+        }
+    }
+
+    /// Parse an `order by` item with an optional direction
+    fn order_item(&mut self) -> Result<(QueryField, bool), String> {
+        let field = self.field()?;
+        let descending = self.keyword("desc");
+        if !descending {
+            self.keyword("asc");
+        }
+        Ok((field, descending))
+    }
+
+    /// Parse conditions joined by `or`
+    fn or(&mut self) -> Result<Condition, String> {
+        let mut conditions = vec![self.and()?];
+        while self.keyword("or") {
+            conditions.push(self.and()?);
+        }
+        Ok(match conditions.len() {
+            1 => conditions.remove(0),
+            _ => Condition::Or(conditions),
+        })
+    }
+
+    /// Parse conditions joined by `and`
+    fn and(&mut self) -> Result<Condition, String> {
+        let mut conditions = vec![self.unary()?];
+        while self.keyword("and") {
+            conditions.push(self.unary()?);
+        }
+        Ok(match conditions.len() {
+            1 => conditions.remove(0),
+            _ => Condition::And(conditions),
+        })
+    }
+
+    /// Parse a negated, parenthesized or simple condition
+    fn unary(&mut self) -> Result<Condition, String> {
+        if self.keyword("not") {
+            return Ok(Condition::Not(Box::new(self.unary()?)));
+        }
+        if self.token(&Token::OpenParen) {
+            let condition = self.or()?;
+            return if self.token(&Token::CloseParen) {
+                Ok(condition)
+            } else {
+                Err("expected ')'".to_string())
+            };
+        }
+        self.comparison()
+    }
+
+    /// Parse a condition on a single field
+    fn comparison(&mut self) -> Result<Condition, String> {
+        let field = self.field()?;
+        if let Some(Token::Operator(op)) = self.peek().cloned() {
+            self.position += 1;
+            let value = self.value()?;
+            return Ok(Condition::Compare { field, op, value });
+        }
+        if self.keyword("contains") {
+            let value = self.value()?;
+            return Ok(Condition::Contains { field, value });
+        }
+        if self.keyword("in") {
+            return self.in_list(field);
+        }
+        if self.keyword("is") {
+            let negated = self.keyword("not");
+            self.expect_keyword("set")?;
+            let condition = Condition::IsSet(field);
+            return Ok(if negated {
+                Condition::Not(Box::new(condition))
+            } else {
+                condition
+            });
+        }
+        Err("expected a comparison, 'contains', 'in' or 'is set'".to_string())
+    }
+
+    /// Parse the parenthesized value list of an `in` condition
+    fn in_list(&mut self, field: QueryField) -> Result<Condition, String> {
+        if !self.token(&Token::OpenParen) {
+            return Err("expected '(' after 'in'".to_string());
+        }
+        let mut values = vec![self.value()?];
+        while self.token(&Token::Comma) {
+            values.push(self.value()?);
+        }
+        if self.token(&Token::CloseParen) {
+            Ok(Condition::In { field, values })
+        } else {
+            Err("expected ')'".to_string())
+        }
+    }
+}
+
+impl Query {
+    /// Parse a query
+    ///
+    /// # Returns
+    /// * `Ok(Query)` - The parsed query
+    /// * `Err(String)` - Description of the syntax error
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            position: 0,
+        };
+        parser
+            .query()
+            .map_err(|error| format!("invalid query '{text}': {error}"))
+    }
+}
+
+/// Raw `[[query_table]]` content before the query is parsed
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawQueryTableSpec {
+    query: String,
+    #[serde(default)]
+    headers: Option<Vec<String>>,
+}
+
+/// Specification for a query table requested from a sysdoc block
+///
+/// ```toml
+/// [[query_table]]
+/// query = "select section_id, heading where status = 'TBD' order by section_number"
+/// headers = ["ID", "Title"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawQueryTableSpec")]
+pub struct QueryTableSpec {
+    /// The parsed query
+    pub query: Query,
+    /// Header text for each selected column
+    pub headers: Vec<String>,
+}
+
+impl TryFrom<RawQueryTableSpec> for QueryTableSpec {
+    type Error = String;
+
+    fn try_from(raw: RawQueryTableSpec) -> Result<Self, Self::Error> {
+        let query = Query::parse(&raw.query)?;
+        let headers = match raw.headers {
+            Some(headers) if headers.len() != query.select.len() => {
+                return Err(format!(
+                    "query_table selects {} columns but has {} headers",
+                    query.select.len(),
+                    headers.len()
+                ));
+            }
+            Some(headers) => headers,
+            None => query
+                .select
+                .iter()
+                .map(|column| match column {
+                    QueryColumn::Field(field) => field.default_header().to_string(),
+                    QueryColumn::Count => "Count".to_string(),
+                })
+                .collect(),
+        };
+        Ok(Self { query, headers })
+    }
+}
+
+impl QueryTableSpec {
+    /// Build the query table from every section of the document
+    ///
+    /// # Parameters
+    /// * `sections` - All sections of the document
+    ///
+    /// # Returns
+    /// * `MarkdownBlock::InlineTable` - The generated table
+    pub fn build_table(&self, sections: &[&MarkdownSection]) -> MarkdownBlock {
+        let query = &self.query;
+        let mut selected: Vec<&MarkdownSection> = sections
+            .iter()
+            .copied()
+            .filter(|section| {
+                query
+                    .filter
+                    .as_ref()
+                    .is_none_or(|f| f.matches(section) == Some(true))
+            })
+            .collect();
+        selected.sort_by(|a, b| a.section_number.cmp(&b.section_number));
+        selected.sort_by(|a, b| compare_sections(&query.order_by, a, b));
+
+        let rows = match &query.group_by {
+            Some(group_field) => self.grouped_rows(&selected, group_field),
+            None => selected
+                .iter()
+                .map(|section| {
+                    query
+                        .select
+                        .iter()
+                        .map(|column| match column {
+                            QueryColumn::Field(field) => field.display(section),
+                            QueryColumn::Count => "1".to_string(),
+                        })
+                        .collect()
+                })
+                .collect(),
+        };
+
+        MarkdownBlock::InlineTable {
+            alignments: vec![Alignment::None; self.headers.len()],
+            headers: self
+                .headers
+                .iter()
+                .map(|header| vec![TextRun::new(header.clone())])
+                .collect(),
+            rows: rows
+                .into_iter()
+                .map(|row: Vec<String>| {
+                    row.into_iter()
+                        .map(|cell| vec![TextRun::new(cell)])
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Merge sections that share a value in the group field into single rows
+    ///
+    /// Groups keep the order in which they first appear in the sorted sections.
+    /// Sections without a value for the group field are left out.
+    fn grouped_rows(
+        &self,
+        sections: &[&MarkdownSection],
+        group_field: &QueryField,
+    ) -> Vec<Vec<String>> {
+        group_by_key(sections.iter().copied(), |section| {
+            group_field.display(section)
+        })
+        .iter()
+        .map(|members| {
+            self.query
+                .select
+                .iter()
+                .map(|column| match column {
+                    QueryColumn::Count => members.len().to_string(),
+                    QueryColumn::Field(field) => {
+                        join_distinct(members.iter().flat_map(|section| field.values(section)))
+                    }
+                })
+                .collect()
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        let query = Query::parse(
+            "SELECT section_id, attributes.protocol WHERE (status = TBD or status is not set) \
+             AND attributes.protocol in ('CAN', \"ARINC 429\") ORDER BY section_number DESC, heading",
+        )
+        .unwrap();
+        assert_eq!(
+            query.select,
+            vec![
+                QueryColumn::Field(QueryField::SectionId),
+                QueryColumn::Field(QueryField::Attribute("protocol".to_string())),
+            ]
+        );
+        assert_eq!(
+            query.filter,
+            Some(Condition::And(vec![
+                Condition::Or(vec![
+                    Condition::Compare {
+                        field: QueryField::Status,
+                        op: CompareOp::Equal,
+                        value: "TBD".to_string(),
+                    },
+                    Condition::Not(Box::new(Condition::IsSet(QueryField::Status))),
+                ]),
+                Condition::In {
+                    field: QueryField::Attribute("protocol".to_string()),
+                    values: vec!["CAN".to_string(), "ARINC 429".to_string()],
+                },
+            ]))
+        );
+        assert_eq!(
+            query.order_by,
+            vec![
+                (QueryField::SectionNumber, true),
+                (QueryField::Heading, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_query_errors() {
+        let error = |text: &str| Query::parse(text).unwrap_err();
+        assert!(error("section_id").contains("expected 'select'"));
+        assert!(error("select owner").contains("unknown field 'owner'"));
+        assert!(error("select count").contains("'count' requires 'group by'"));
+        assert!(error("select heading where status = 'TBD").contains("unterminated string"));
+        assert!(error("select heading where status").contains("expected a comparison"));
+        assert!(error("select heading order section_id").contains("expected 'by'"));
+        assert!(error("select heading limit 5").contains("unexpected 'limit'"));
+    }
+
+    #[test]
+    fn test_parse_spec_headers() {
+        let spec: QueryTableSpec =
+            toml::from_str(r#"query = "select status, count group by status""#).unwrap();
+        assert_eq!(spec.headers, vec!["Status", "Count"]);
+
+        let result: Result<QueryTableSpec, _> = toml::from_str(
+            r#"query = "select status"
+headers = ["A", "B"]"#,
+        );
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("1 columns but has 2 headers"), "{}", error);
+    }
+}
@@ -0,0 +1,613 @@
+//! ReqIF reading
+//!
+//! Parses ReqIF files received from requirements management tools into spec
+//! objects (with their ID, title, markdown text and attributes) and the spec
+//! hierarchies that order them. Used both to load ReqIF files as trace catalogs
+//! and to generate a sysdoc source tree from a ReqIF baseline.
+
+use roxmltree::{Document, Node};
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+/// Attribute names recognised as a spec object's requirement ID, in priority order
+const ID_ATTRIBUTES: [&str; 3] = ["ReqIF.ForeignID", "ID", "Identifier"];
+
+/// Attribute names recognised as a spec object's title, in priority order
+const TITLE_ATTRIBUTES: [&str; 3] = ["ReqIF.ChapterName", "ReqIF.Name", "Title"];
+
+/// LONG-NAME of the SpecObjectType of chapter headings without a requirement ID
+pub const HEADING_TYPE: &str = "Heading";
+
+/// Attribute names recognised as a spec object's text, in priority order
+const TEXT_ATTRIBUTES: [&str; 3] = ["ReqIF.Text", "ReqIF.Description", "Text"];
+
+/// Errors that can occur when reading a ReqIF file
+#[derive(Error, Debug)]
+pub enum ReqifError {
+    #[error("XML error: {0}")]
+    Xml(#[from] roxmltree::Error),
+
+    #[error("Not a ReqIF file (root element is '{0}')")]
+    NotReqif(String),
+}
+
+/// A requirement (SpecObject) read from a ReqIF file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReqifObject {
+    /// ReqIF IDENTIFIER of the object
+    pub identifier: String,
+    /// Requirement ID (`ReqIF.ForeignID` or similar, falling back to the identifier)
+    pub id: String,
+    /// Title (`ReqIF.ChapterName`, `ReqIF.Name`, or the object's LONG-NAME)
+    pub title: String,
+    /// Text converted to markdown (`ReqIF.Text` or similar)
+    pub text: String,
+    /// All attribute values keyed by attribute LONG-NAME (XHTML converted to markdown)
+    pub attributes: BTreeMap<String, String>,
+    /// LONG-NAME of the object's SpecObjectType
+    pub object_type: String,
+}
+
+impl ReqifObject {
+    /// Whether the object is a chapter heading rather than a requirement
+    /// (its SpecObjectType is named [`HEADING_TYPE`])
+    pub fn is_heading(&self) -> bool {
+        self.object_type == HEADING_TYPE
+    }
+}
+
+/// A node of a specification hierarchy
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReqifNode {
+    /// IDENTIFIER of the referenced spec object
+    pub object: String,
+    /// Child nodes in document order
+    pub children: Vec<ReqifNode>,
+}
+
+/// A specification (document) read from a ReqIF file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReqifSpecification {
+    /// LONG-NAME of the specification
+    pub title: String,
+    /// Top-level hierarchy nodes
+    pub children: Vec<ReqifNode>,
+}
+
+/// The contents of a ReqIF file
+#[derive(Debug, Clone, Default)]
+pub struct ReqifDocument {
+    /// Title from the ReqIF header
+    pub title: String,
+    /// Spec objects keyed by IDENTIFIER
+    pub objects: BTreeMap<String, ReqifObject>,
+    /// Spec objects in file order (IDENTIFIERs)
+    pub object_order: Vec<String>,
+    /// Specifications in file order
+    pub specifications: Vec<ReqifSpecification>,
+    /// Spec relations as (source, target) IDENTIFIER pairs, in file order
+    pub relations: Vec<(String, String)>,
+}
+
+impl ReqifDocument {
+    /// Parse ReqIF XML
+    ///
+    /// # Parameters
+    /// * `xml` - Content of a .reqif file
+    ///
+    /// # Returns
+    /// * `Ok(ReqifDocument)` - The spec objects and hierarchies of the file
+    /// * `Err(ReqifError)` - The content is not well-formed XML or not ReqIF
+    pub fn parse(xml: &str) -> Result<Self, ReqifError> {
+        let document = Document::parse(xml)?;
+        let root = document.root_element();
+        if root.tag_name().name() != "REQ-IF" {
+            return Err(ReqifError::NotReqif(root.tag_name().name().to_string()));
+        }
+
+        let names = DefinitionNames::collect(root);
+        let mut reqif = Self {
+            title: descendant(root, "TITLE")
+                .and_then(|n| n.text())
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            ..Self::default()
+        };
+
+        for node in root.descendants().filter(|n| is_element(*n, "SPEC-OBJECT")) {
+            let object = read_object(node, &names);
+            reqif.object_order.push(object.identifier.clone());
+            reqif.objects.insert(object.identifier.clone(), object);
+        }
+
+        reqif.specifications = root
+            .descendants()
+            .filter(|n| is_element(*n, "SPECIFICATION"))
+            .map(|node| ReqifSpecification {
+                title: node.attribute("LONG-NAME").unwrap_or_default().to_string(),
+                children: read_hierarchy_children(node),
+            })
+            .collect();
+
+        reqif.relations = root
+            .descendants()
+            .filter(|n| is_element(*n, "SPEC-RELATION"))
+            .filter_map(|node| Some((relation_end(node, "SOURCE")?, relation_end(node, "TARGET")?)))
+            .collect();
+
+        Ok(reqif)
+    }
+
+    /// Requirement IDs of the objects a spec object relates to
+    ///
+    /// # Parameters
+    /// * `identifier` - IDENTIFIER of the source spec object
+    ///
+    /// # Returns
+    /// * Target IDs in relation order, without duplicates
+    pub fn related_ids(&self, identifier: &str) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        let targets = self
+            .relations
+            .iter()
+            .filter(|(source, _)| source == identifier)
+            .filter_map(|(_, target)| self.objects.get(target));
+        for target in targets {
+            if !ids.contains(&target.id) {
+                ids.push(target.id.clone());
+            }
+        }
+        ids
+    }
+
+    /// Iterate over all spec objects in file order
+    pub fn objects(&self) -> impl Iterator<Item = &ReqifObject> {
+        self.object_order
+            .iter()
+            .filter_map(|identifier| self.objects.get(identifier))
+    }
+
+    /// Iterate over the spec objects that are requirements (not chapter headings)
+    pub fn requirements(&self) -> impl Iterator<Item = &ReqifObject> {
+        self.objects().filter(|object| !object.is_heading())
+    }
+}
+
+/// LONG-NAMEs of spec object types, attribute definitions and enumeration values,
+/// keyed by IDENTIFIER
+struct DefinitionNames {
+    names: HashMap<String, String>,
+}
+
+impl DefinitionNames {
+    fn collect(root: Node) -> Self {
+        let names = root
+            .descendants()
+            .filter(|n| {
+                let tag = n.tag_name().name();
+                tag.starts_with("ATTRIBUTE-DEFINITION-")
+                    || tag == "ENUM-VALUE"
+                    || tag == "SPEC-OBJECT-TYPE"
+            })
+            .filter_map(|n| {
+                let identifier = n.attribute("IDENTIFIER")?;
+                let long_name = n.attribute("LONG-NAME").unwrap_or(identifier);
+                Some((identifier.to_string(), long_name.to_string()))
+            })
+            .collect();
+        Self { names }
+    }
+
+    fn get<'a>(&'a self, identifier: &'a str) -> &'a str {
+        self.names
+            .get(identifier)
+            .map_or(identifier, String::as_str)
+    }
+}
+
+/// Read a SPEC-OBJECT element
+fn read_object(node: Node, names: &DefinitionNames) -> ReqifObject {
+    let identifier = node.attribute("IDENTIFIER").unwrap_or_default().to_string();
+
+    let attributes: BTreeMap<String, String> = node
+        .descendants()
+        .filter(|n| n.tag_name().name().starts_with("ATTRIBUTE-VALUE-"))
+        .filter_map(|value| {
+            let definition = descendant(value, "DEFINITION")?
+                .children()
+                .find(Node::is_element)?
+                .text()?
+                .trim();
+            Some((
+                names.get(definition).to_string(),
+                read_attribute_value(value, names),
+            ))
+        })
+        .collect();
+
+    let first_of = |candidates: &[&str]| {
+        candidates
+            .iter()
+            .find_map(|name| attributes.get(*name).filter(|v| !v.is_empty()))
+            .cloned()
+    };
+
+    ReqifObject {
+        id: first_of(&ID_ATTRIBUTES).unwrap_or_else(|| identifier.clone()),
+        title: first_of(&TITLE_ATTRIBUTES)
+            .or_else(|| node.attribute("LONG-NAME").map(str::to_string))
+            .unwrap_or_default(),
+        text: first_of(&TEXT_ATTRIBUTES).unwrap_or_default(),
+        object_type: descendant(node, "SPEC-OBJECT-TYPE-REF")
+            .and_then(|type_ref| type_ref.text())
+            .map(|type_id| names.get(type_id.trim()).to_string())
+            .unwrap_or_default(),
+        identifier,
+        attributes,
+    }
+}
+
+/// Read the value of an ATTRIBUTE-VALUE-* element as text
+fn read_attribute_value(value: Node, names: &DefinitionNames) -> String {
+    match value.tag_name().name() {
+        "ATTRIBUTE-VALUE-XHTML" => descendant(value, "THE-VALUE")
+            .map(xhtml_to_markdown)
+            .unwrap_or_default(),
+        "ATTRIBUTE-VALUE-ENUMERATION" => value
+            .descendants()
+            .filter(|n| is_element(*n, "ENUM-VALUE-REF"))
+            .filter_map(|n| n.text())
+            .map(|id| names.get(id.trim()).to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => value
+            .attribute("THE-VALUE")
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+    }
+}
+
+/// Read the SPEC-HIERARCHY children of a SPECIFICATION or SPEC-HIERARCHY element
+fn read_hierarchy_children(node: Node) -> Vec<ReqifNode> {
+    let Some(children) = node.children().find(|n| is_element(*n, "CHILDREN")) else {
+        return Vec::new();
+    };
+
+    children
+        .children()
+        .filter(|n| is_element(*n, "SPEC-HIERARCHY"))
+        .filter_map(|hierarchy| {
+            let object = descendant(hierarchy, "OBJECT")?
+                .children()
+                .find(Node::is_element)?
+                .text()?
+                .trim()
+                .to_string();
+            Some(ReqifNode {
+                object,
+                children: read_hierarchy_children(hierarchy),
+            })
+        })
+        .collect()
+}
+
+/// Read the referenced object IDENTIFIER of a SPEC-RELATION's SOURCE or TARGET
+fn relation_end(relation: Node, end: &str) -> Option<String> {
+    let reference = relation
+        .children()
+        .find(|n| is_element(*n, end))?
+        .children()
+        .find(Node::is_element)?;
+    Some(reference.text()?.trim().to_string())
+}
+
+/// Check whether a node is an element with the given local name
+fn is_element(node: Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+/// Find the first descendant element with the given local name
+fn descendant<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.descendants().find(|n| is_element(*n, name))
+}
+
+/// Convert ReqIF XHTML content to markdown
+///
+/// Paragraphs, line breaks, lists, emphasis, code, links and tables are
+/// converted; other elements contribute their text content.
+pub fn xhtml_to_markdown(node: Node) -> String {
+    let mut blocks = Vec::new();
+    let mut inline = String::new();
+    collect_blocks(node, &mut blocks, &mut inline, 0);
+    flush_inline(&mut blocks, &mut inline);
+    blocks.join("\n\n")
+}
+
+/// Push pending inline text as a paragraph block
+fn flush_inline(blocks: &mut Vec<String>, inline: &mut String) {
+    let paragraph = inline
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string();
+    if !paragraph.is_empty() {
+        blocks.push(paragraph);
+    }
+    inline.clear();
+}
+
+/// Walk block-level content, accumulating inline text into paragraphs
+fn collect_blocks(node: Node, blocks: &mut Vec<String>, inline: &mut String, depth: usize) {
+    for child in node.children() {
+        if !child.is_element() {
+            inline.push_str(&collapse_whitespace(child.text().unwrap_or_default()));
+            continue;
+        }
+        match child.tag_name().name() {
+            "div" | "THE-VALUE" | "body" => collect_blocks(child, blocks, inline, depth),
+            "p" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                flush_inline(blocks, inline);
+                let text = inline_markdown(child);
+                let is_heading = child.tag_name().name().starts_with('h');
+                inline.push_str(&if is_heading && !text.trim().is_empty() {
+                    format!("**{}**", text.trim())
+                } else {
+                    text
+                });
+                flush_inline(blocks, inline);
+            }
+            "ul" | "ol" => {
+                flush_inline(blocks, inline);
+                blocks.push(list_markdown(child, depth));
+            }
+            "pre" => {
+                flush_inline(blocks, inline);
+                let code = child
+                    .descendants()
+                    .filter(Node::is_text)
+                    .filter_map(|n| n.text())
+                    .collect::<String>();
+                blocks.push(format!("```\n{}\n```", code.trim_end()));
+            }
+            "table" => {
+                flush_inline(blocks, inline);
+                blocks.push(table_markdown(child));
+            }
+            _ => inline.push_str(&inline_element(child)),
+        }
+    }
+}
+
+/// Convert the inline content of an element to markdown
+fn inline_markdown(node: Node) -> String {
+    node.children()
+        .map(|child| {
+            if child.is_element() {
+                inline_element(child)
+            } else {
+                collapse_whitespace(child.text().unwrap_or_default())
+            }
+        })
+        .collect()
+}
+
+/// Convert a single inline element to markdown
+fn inline_element(node: Node) -> String {
+    let content = inline_markdown(node);
+    let wrap = |marker: &str| {
+        if content.trim().is_empty() {
+            content.clone()
+        } else {
+            format!("{marker}{}{marker}", content.trim())
+        }
+    };
+    match node.tag_name().name() {
+        "strong" | "b" => wrap("**"),
+        "em" | "i" => wrap("*"),
+        "code" | "tt" | "kbd" | "samp" => wrap("`"),
+        "del" | "s" => wrap("~~"),
+        "br" => "\n".to_string(),
+        "a" => match node.attribute("href") {
+            Some(href) => format!("[{}]({})", content.trim(), href),
+            None => content,
+        },
+        _ => content,
+    }
+}
+
+/// Convert a list element to markdown, indenting nested lists
+fn list_markdown(list: Node, depth: usize) -> String {
+    let ordered = list.tag_name().name() == "ol";
+    let indent = "  ".repeat(depth);
+    list.children()
+        .filter(|n| is_element(*n, "li"))
+        .enumerate()
+        .map(|(index, item)| {
+            let marker = if ordered {
+                format!("{}.", index + 1)
+            } else {
+                "-".to_string()
+            };
+            let mut blocks = Vec::new();
+            let mut inline = String::new();
+            collect_blocks(item, &mut blocks, &mut inline, depth + 1);
+            flush_inline(&mut blocks, &mut inline);
+            format!("{indent}{marker} {}", blocks.join("\n").trim_start())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Convert a table element to a markdown pipe table (first row is the header)
+fn table_markdown(table: Node) -> String {
+    let rows: Vec<Vec<String>> = table
+        .descendants()
+        .filter(|n| is_element(*n, "tr"))
+        .map(|row| {
+            row.children()
+                .filter(|n| is_element(*n, "td") || is_element(*n, "th"))
+                .map(|cell| inline_markdown(cell).trim().replace('|', "\\|"))
+                .collect()
+        })
+        .collect();
+
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+
+    let format_row = |row: &Vec<String>| {
+        let cells: Vec<&str> = (0..columns)
+            .map(|i| row.get(i).map_or("", String::as_str))
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut lines = vec![
+        format_row(&rows[0]),
+        format!("|{}", " --- |".repeat(columns)),
+    ];
+    lines.extend(rows[1..].iter().map(format_row));
+    lines.join("\n")
+}
+
+/// Collapse runs of whitespace in XML text to single spaces
+fn collapse_whitespace(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut last_was_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !last_was_space {
+                result.push(' ');
+            }
+            last_was_space = true;
+        } else {
+            result.push(c);
+            last_was_space = false;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd" xmlns:xhtml="http://www.w3.org/1999/xhtml">
+  <THE-HEADER><REQ-IF-HEADER IDENTIFIER="h"><TITLE>Customer SRS</TITLE></REQ-IF-HEADER></THE-HEADER>
+  <CORE-CONTENT><REQ-IF-CONTENT>
+    <SPEC-TYPES>
+      <SPEC-OBJECT-TYPE IDENTIFIER="t">
+        <SPEC-ATTRIBUTES>
+          <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="ad-id" LONG-NAME="ReqIF.ForeignID"/>
+          <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="ad-name" LONG-NAME="ReqIF.ChapterName"/>
+          <ATTRIBUTE-DEFINITION-XHTML IDENTIFIER="ad-text" LONG-NAME="ReqIF.Text"/>
+          <ATTRIBUTE-DEFINITION-ENUMERATION IDENTIFIER="ad-prio" LONG-NAME="Priority"/>
+        </SPEC-ATTRIBUTES>
+      </SPEC-OBJECT-TYPE>
+    </SPEC-TYPES>
+    <DATATYPES>
+      <DATATYPE-DEFINITION-ENUMERATION IDENTIFIER="dt-prio">
+        <SPECIFIED-VALUES><ENUM-VALUE IDENTIFIER="ev-high" LONG-NAME="High"/></SPECIFIED-VALUES>
+      </DATATYPE-DEFINITION-ENUMERATION>
+    </DATATYPES>
+    <SPEC-OBJECTS>
+      <SPEC-OBJECT IDENTIFIER="o1">
+        <VALUES>
+          <ATTRIBUTE-VALUE-STRING THE-VALUE="SRS-001"><DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>ad-id</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION></ATTRIBUTE-VALUE-STRING>
+          <ATTRIBUTE-VALUE-STRING THE-VALUE="Power"><DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>ad-name</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION></ATTRIBUTE-VALUE-STRING>
+          <ATTRIBUTE-VALUE-ENUMERATION><DEFINITION><ATTRIBUTE-DEFINITION-ENUMERATION-REF>ad-prio</ATTRIBUTE-DEFINITION-ENUMERATION-REF></DEFINITION><VALUES><ENUM-VALUE-REF>ev-high</ENUM-VALUE-REF></VALUES></ATTRIBUTE-VALUE-ENUMERATION>
+          <ATTRIBUTE-VALUE-XHTML><DEFINITION><ATTRIBUTE-DEFINITION-XHTML-REF>ad-text</ATTRIBUTE-DEFINITION-XHTML-REF></DEFINITION>
+            <THE-VALUE><xhtml:div><xhtml:p>The system <xhtml:b>shall</xhtml:b> operate on
+              28V.</xhtml:p><xhtml:ul><xhtml:li>Nominal</xhtml:li><xhtml:li>Backup</xhtml:li></xhtml:ul></xhtml:div></THE-VALUE>
+          </ATTRIBUTE-VALUE-XHTML>
+        </VALUES>
+      </SPEC-OBJECT>
+      <SPEC-OBJECT IDENTIFIER="o2" LONG-NAME="Voltage"/>
+    </SPEC-OBJECTS>
+    <SPECIFICATIONS>
+      <SPECIFICATION IDENTIFIER="s" LONG-NAME="Requirements">
+        <CHILDREN>
+          <SPEC-HIERARCHY IDENTIFIER="h1">
+            <OBJECT><SPEC-OBJECT-REF>o1</SPEC-OBJECT-REF></OBJECT>
+            <CHILDREN>
+              <SPEC-HIERARCHY IDENTIFIER="h2"><OBJECT><SPEC-OBJECT-REF>o2</SPEC-OBJECT-REF></OBJECT></SPEC-HIERARCHY>
+            </CHILDREN>
+          </SPEC-HIERARCHY>
+        </CHILDREN>
+      </SPECIFICATION>
+    </SPECIFICATIONS>
+    <SPEC-RELATIONS>
+      <SPEC-RELATION IDENTIFIER="r1">
+        <SOURCE><SPEC-OBJECT-REF>o2</SPEC-OBJECT-REF></SOURCE>
+        <TARGET><SPEC-OBJECT-REF>o1</SPEC-OBJECT-REF></TARGET>
+      </SPEC-RELATION>
+    </SPEC-RELATIONS>
+  </REQ-IF-CONTENT></CORE-CONTENT>
+</REQ-IF>"#;
+
+    #[test]
+    fn test_parse_objects() {
+        let reqif = ReqifDocument::parse(SAMPLE).unwrap();
+        assert_eq!(reqif.title, "Customer SRS");
+
+        let object = &reqif.objects["o1"];
+        assert_eq!(object.id, "SRS-001");
+        assert_eq!(object.title, "Power");
+        assert_eq!(
+            object.text,
+            "The system **shall** operate on 28V.\n\n- Nominal\n- Backup"
+        );
+        assert_eq!(object.attributes["Priority"], "High");
+
+        let fallback = &reqif.objects["o2"];
+        assert_eq!(fallback.id, "o2");
+        assert_eq!(fallback.title, "Voltage");
+    }
+
+    #[test]
+    fn test_parse_hierarchy() {
+        let reqif = ReqifDocument::parse(SAMPLE).unwrap();
+        assert_eq!(reqif.specifications.len(), 1);
+        let spec = &reqif.specifications[0];
+        assert_eq!(spec.title, "Requirements");
+        assert_eq!(spec.children[0].object, "o1");
+        assert_eq!(spec.children[0].children[0].object, "o2");
+        assert_eq!(reqif.related_ids("o2"), vec!["SRS-001"]);
+        assert!(reqif.related_ids("o1").is_empty());
+    }
+
+    #[test]
+    fn test_xhtml_table_to_markdown() {
+        let xml = r#"<div><table><tr><th>Mode</th><th>Volts</th></tr><tr><td>A|B</td><td><i>28</i></td></tr></table></div>"#;
+        let document = Document::parse(xml).unwrap();
+        assert_eq!(
+            xhtml_to_markdown(document.root_element()),
+            "| Mode | Volts |\n| --- | --- |\n| A\\|B | *28* |"
+        );
+    }
+
+    #[test]
+    fn test_xhtml_preformatted_to_markdown() {
+        let xml = r#"<div xmlns:xhtml="http://www.w3.org/1999/xhtml"><xhtml:pre>&lt;stuff&gt;
+  <xhtml:b>x</xhtml:b> = 1</xhtml:pre></div>"#;
+        let document = Document::parse(xml).unwrap();
+        assert_eq!(
+            xhtml_to_markdown(document.root_element()),
+            "```\n<stuff>\n  x = 1\n```"
+        );
+    }
+
+    #[test]
+    fn test_not_reqif() {
+        assert!(matches!(
+            ReqifDocument::parse("<html/>"),
+            Err(ReqifError::NotReqif(_))
+        ));
+    }
+}
@@ -3,8 +3,14 @@
 //! This module defines metadata that can be embedded in markdown sections
 //! using sysdoc code blocks to support requirements traceability.
 
+use super::attributes::AttributeValue;
+use super::query::QueryTableSpec;
+use super::trace_graph::TraceGraphSpec;
+use super::traceability::TraceTableSpec;
+use super::types::SourceSpan;
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
 
 /// Configuration for traceability table generation
 ///
@@ -104,7 +110,7 @@ impl<'de> Deserialize<'de> for TableGeneration {
 ///
 /// The metadata enables traceability features like generating tables that map
 /// section IDs to traced requirements and vice versa.
-#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
 #[serde(default)]
 pub struct SectionMetadata {
     /// Unique identifier for this section (e.g., "REQ-001", "SDD-3.2.1")
@@ -113,6 +119,26 @@ pub struct SectionMetadata {
     /// List of IDs that this section traces to (e.g., requirements IDs)
     pub traced_ids: Option<Vec<String>>,
 
+    /// Optional status of this section (e.g., "Draft", "Approved")
+    pub status: Option<String>,
+
+    /// Optional verification method for this section (e.g., "Test", "Inspection")
+    pub verification_method: Option<String>,
+
+    /// Names of the automated tests that implement this test case section
+    ///
+    /// Matched against the test names in `[[test_results]]` files. A section
+    /// without `test_cases` is matched by its `section_id` instead.
+    pub test_cases: Option<Vec<String>>,
+
+    /// Custom attributes of this section (the `[attributes]` table)
+    ///
+    /// Values are strings, integers, booleans or arrays of strings. Attributes
+    /// declared in sysdoc.toml (`[attributes.<name>]`) are checked by validation.
+    ///
+    /// Example: `[attributes]` with `owner = "J. Smith"` and `safety_level = "C"`
+    pub attributes: BTreeMap<String, AttributeValue>,
+
     /// Configuration for generating a table mapping section_ids to their traced_ids
     ///
     /// Supports:
@@ -135,6 +161,42 @@ pub struct SectionMetadata {
     /// - Second column: comma-separated list of section_ids (sorted lexically)
     pub generate_traced_ids_to_section_ids_table: TableGeneration,
 
+    /// Multi-column trace tables to generate in this section
+    ///
+    /// Each `[[trace_table]]` entry selects columns drawn from section metadata
+    /// and may group, sort, and filter the rows. See [`TraceTableSpec`].
+    #[serde(rename = "trace_table")]
+    pub trace_tables: Vec<TraceTableSpec>,
+
+    /// Tables of the sections selected by a metadata query
+    ///
+    /// Each `[[query_table]]` entry has a `query` such as
+    /// `select section_id, heading where status = 'TBD'`. See [`QueryTableSpec`].
+    #[serde(rename = "query_table")]
+    pub query_tables: Vec<QueryTableSpec>,
+
+    /// Generate a table of test case sections with their pass/fail/not-run result
+    pub generate_test_results_table: bool,
+
+    /// Generate a matrix of the IDs traced by test case sections with their
+    /// aggregated verification status
+    pub generate_verification_status_table: bool,
+
+    /// Generate the "TBD/TBR List" table of every open TBD/TBR item in the document
+    pub generate_tbd_table: bool,
+
+    /// Generate a "Changes since baseline" table comparing the named baseline
+    /// (from `baselines/<name>.json`) with the current document
+    ///
+    /// Example: `changes_since_baseline = "v1.0"`
+    pub changes_since_baseline: Option<String>,
+
+    /// Embed a traceability graph (SVG figure) of the selected sections
+    ///
+    /// Example: `[trace_graph]` with `chapter = "3"` draws the sections under
+    /// chapter 3, the IDs they trace to, and the documents defining them.
+    pub trace_graph: Option<TraceGraphSpec>,
+
     /// Path to an external file to include as a code block at the end of the section
     ///
     /// The file content is read and appended as an unformatted code block after
@@ -143,6 +205,14 @@ pub struct SectionMetadata {
     ///
     /// Example: `include_file = "schemas/interface.xml"`
     pub include_file: Option<String>,
+
+    /// Where each field is written in the markdown file, keyed like
+    /// [`SectionMetadata::has_field`] (`section_id`, `attributes.owner`, ...)
+    ///
+    /// A field's span runs from its key to the next key, so it covers
+    /// multi-line arrays. Filled in by the parser.
+    #[serde(skip)]
+    pub field_spans: BTreeMap<String, SourceSpan>,
 }
 
 impl SectionMetadata {
@@ -158,15 +228,123 @@ impl SectionMetadata {
         toml::from_str(content)
     }
 
+    /// Record where each field of the block is written in the markdown file
+    ///
+    /// # Parameters
+    /// * `content` - TOML content of the block
+    /// * `source` - Content of the whole markdown file
+    /// * `content_start` - Byte offset in `source` at which `content` begins
+    pub fn locate_fields(&mut self, content: &str, source: &str, content_start: usize) {
+        let mut table = String::new();
+        let mut cursor = content_start;
+        let mut fields: Vec<(String, usize)> = Vec::new();
+        let mut ends = Vec::new();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            // Lines are searched for rather than offset from `content_start`
+            // because blocks nested in lists are indented in the file
+            let Some(start) = source
+                .get(cursor..)
+                .and_then(|rest| rest.find(line))
+                .map(|offset| cursor + offset)
+            else {
+                continue;
+            };
+            cursor = start + line.len();
+            if let Some(header) = line.strip_prefix('[') {
+                let name = header.trim_start_matches('[').split(']').next();
+                table = name.unwrap_or_default().trim().to_string();
+                ends.push(start);
+            } else if let Some((key, _)) = line.split_once('=') {
+                let key = key.trim().trim_matches('"');
+                let path = match table.as_str() {
+                    "" => key.to_string(),
+                    _ => format!("{}.{}", table, key),
+                };
+                fields.push((path, start));
+                ends.push(start);
+            }
+        }
+        ends.push(cursor);
+
+        for (path, start) in fields {
+            let end = ends
+                .iter()
+                .copied()
+                .find(|&end| end > start)
+                .unwrap_or(cursor);
+            self.field_spans
+                .entry(path)
+                .or_insert_with(|| SourceSpan::new(source, start..end));
+        }
+    }
+
+    /// Line of a metadata field in the markdown file, if it was located
+    ///
+    /// # Parameters
+    /// * `field` - Field key as accepted by [`SectionMetadata::has_field`]
+    pub fn field_line(&self, field: &str) -> Option<usize> {
+        self.field_spans.get(field).map(|span| span.line)
+    }
+
+    /// Location of a quoted value within a metadata field, such as one ID of
+    /// `traced_ids`, falling back to the field itself
+    ///
+    /// # Parameters
+    /// * `source` - Content of the markdown file
+    /// * `field` - Field key as accepted by [`SectionMetadata::has_field`]
+    /// * `value` - Value to find, without quotes
+    pub fn value_span(&self, source: &str, field: &str, value: &str) -> Option<SourceSpan> {
+        let span = self.field_spans.get(field)?;
+        Some(span.find(source, &format!("\"{}\"", value)))
+    }
+
     /// Check if this metadata has any traceability content
     pub fn has_traceability(&self) -> bool {
         self.section_id.is_some() || self.traced_ids.is_some()
     }
 
+    /// Check if this metadata describes a traceable item (a requirement, design
+    /// element or test case) even though it has no `section_id` yet
+    pub fn describes_item(&self) -> bool {
+        self.traced_ids.is_some()
+            || self.status.is_some()
+            || self.verification_method.is_some()
+            || self.test_cases.is_some()
+            || !self.attributes.is_empty()
+    }
+
+    /// Check if a metadata field is set
+    ///
+    /// # Parameters
+    /// * `field` - `section_id`, `traced_ids`, `status`, `verification_method`,
+    ///   `test_cases`, or `attributes.<name>` for a custom attribute
+    pub fn has_field(&self, field: &str) -> bool {
+        match field {
+            "section_id" => self.section_id.is_some(),
+            "traced_ids" => self.traced_ids.is_some(),
+            "status" => self.status.is_some(),
+            "verification_method" => self.verification_method.is_some(),
+            "test_cases" => self.test_cases.is_some(),
+            _ => field
+                .strip_prefix("attributes.")
+                .is_some_and(|name| self.attributes.contains_key(name)),
+        }
+    }
+
     /// Check if this metadata requests any table generation
     pub fn requests_table_generation(&self) -> bool {
         self.generate_section_id_to_traced_ids_table.is_enabled()
             || self.generate_traced_ids_to_section_ids_table.is_enabled()
+            || !self.trace_tables.is_empty()
+            || !self.query_tables.is_empty()
+            || self.generate_test_results_table
+            || self.generate_verification_status_table
+            || self.generate_tbd_table
+            || self.trace_graph.is_some()
+            || self.changes_since_baseline.is_some()
     }
 }
 
@@ -309,6 +487,71 @@ generate_traced_ids_to_section_ids_table = ["System Requirement", "Configuration
         );
     }
 
+    #[test]
+    fn test_parse_status_and_trace_table() {
+        let content = r#"
+section_id = "SDD-010"
+status = "Approved"
+verification_method = "Test"
+
+[[trace_table]]
+columns = ["traced_id", "section_id"]
+headers = ["Requirement", "Design Element"]
+group_by = "traced_id"
+"#;
+        let metadata = SectionMetadata::parse(content).unwrap();
+        assert_eq!(metadata.status, Some("Approved".to_string()));
+        assert_eq!(metadata.verification_method, Some("Test".to_string()));
+        assert_eq!(metadata.trace_tables.len(), 1);
+        assert_eq!(
+            metadata.trace_tables[0].headers,
+            vec!["Requirement", "Design Element"]
+        );
+        assert!(metadata.requests_table_generation());
+    }
+
+    #[test]
+    fn test_parse_trace_table_unknown_column() {
+        let content = r#"
+[[trace_table]]
+columns = ["section_id", "owner"]
+"#;
+        let result = SectionMetadata::parse(content);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        let content = r#"
+section_id = "SRS-010"
+
+[attributes]
+owner = "J. Smith"
+safety_level = "C"
+"#;
+        let metadata = SectionMetadata::parse(content).unwrap();
+        assert_eq!(metadata.attributes.len(), 2);
+        assert_eq!(
+            metadata.attributes["safety_level"],
+            AttributeValue::Text("C".to_string())
+        );
+        assert!(metadata.describes_item());
+    }
+
+    #[test]
+    fn test_parse_trace_graph() {
+        let content = r#"
+[trace_graph]
+chapter = "3"
+caption = "Design traceability"
+"#;
+        let metadata = SectionMetadata::parse(content).unwrap();
+        let spec = metadata.trace_graph.as_ref().unwrap();
+        assert_eq!(spec.chapter.as_deref(), Some("3"));
+        assert_eq!(spec.section_id_prefix, None);
+        assert!(metadata.requests_table_generation());
+    }
+
     #[test]
     fn test_parse_true_returns_error() {
         let content = r#"
@@ -0,0 +1,627 @@
+//! Offline spell checking
+//!
+//! Words in headings, paragraphs, lists, block quotes and table cells are
+//! checked against a bundled American English word list (or a word list named
+//! in `[spelling]`, such as a British one), the project dictionary
+//! (`dictionary.txt` unless `[spelling]` names another file) and the terms
+//! defined in the document's glossary and acronym sections.
+//! Code, links, acronyms and identifiers (words with digits, underscores, dots
+//! or inner capitals) are not checked.
+
+use super::blocks::MarkdownBlock;
+use super::markdown_source::{MarkdownSection, MarkdownSource};
+use super::text_run::TextRun;
+use crate::diagnostics::Diagnostic;
+use crate::document_config::{SpellingConfig, SpellingLanguage};
+use crate::text::edit_distance;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use thiserror::Error;
+
+/// Bundled American English word list
+const ENGLISH_WORDS: &str = include_str!("spelling/english.txt");
+
+/// The bundled word list, indexed on first use
+static ENGLISH: LazyLock<WordList> = LazyLock::new(|| WordList::parse(ENGLISH_WORDS));
+
+/// Maximum number of suggestions per unknown word
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Maximum edit distance of a suggestion from the unknown word
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Errors reading or writing the project dictionary
+#[derive(Error, Debug)]
+pub enum SpellingError {
+    #[error("Failed to read project dictionary '{0}': {1}")]
+    Read(PathBuf, #[source] std::io::Error),
+
+    #[error("Failed to write project dictionary '{0}': {1}")]
+    Write(PathBuf, #[source] std::io::Error),
+
+    #[error("Failed to read word list '{0}': {1}")]
+    ReadWordList(PathBuf, #[source] std::io::Error),
+
+    #[error("[spelling] language = \"en-GB\" needs a British word_list; the bundled word list is American English")]
+    MissingWordList,
+}
+
+/// A word not found in any dictionary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Misspelling {
+    /// The word as written
+    pub word: String,
+    /// Source file (relative to document root)
+    pub file: PathBuf,
+    /// Line number of the word (the section heading if it cannot be located)
+    pub line: usize,
+    /// Dictionary words close to the unknown word, closest first
+    pub suggestions: Vec<String>,
+}
+
+impl Misspelling {
+    /// Description of the problem without the location
+    pub fn message(&self) -> String {
+        if self.suggestions.is_empty() {
+            format!("Unknown word '{}'", self.word)
+        } else {
+            format!(
+                "Unknown word '{}' (did you mean {}?)",
+                self.word,
+                self.suggestions
+                    .iter()
+                    .map(|s| format!("'{s}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }
+    }
+}
+
+impl From<&Misspelling> for Diagnostic {
+    fn from(misspelling: &Misspelling) -> Self {
+        let diagnostic =
+            Diagnostic::warning("spelling", format!("Unknown word '{}'", misspelling.word))
+                .at_line(&misspelling.file, misspelling.line)
+                .highlighting(&misspelling.word);
+        match misspelling.suggestions.as_slice() {
+            [] => diagnostic.with_suggestion("add the word with 'sysdoc lint --add-word <WORD>'"),
+            suggestions => diagnostic.with_suggestion(format!(
+                "did you mean {}?",
+                suggestions
+                    .iter()
+                    .map(|s| format!("'{s}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Misspelling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}",
+            self.file.display(),
+            self.line,
+            self.message()
+        )
+    }
+}
+
+/// A word list with its words indexed by length for suggestions
+#[derive(Debug, Clone, Default)]
+struct WordList {
+    words: HashSet<String>,
+    /// Lowercase and original spelling of each word, by length in characters
+    by_length: Vec<Vec<(String, String)>>,
+}
+
+impl WordList {
+    /// Parse a word list: one word per line, `#` starts a comment line
+    fn parse(content: &str) -> Self {
+        let mut list = Self::default();
+        list.extend(project_words(content));
+        list
+    }
+
+    /// Add words to the list
+    fn extend(&mut self, words: impl IntoIterator<Item = String>) {
+        for word in words {
+            if !self.words.insert(word.clone()) {
+                continue;
+            }
+            let length = word.chars().count();
+            if self.by_length.len() <= length {
+                self.by_length.resize_with(length + 1, Vec::new);
+            }
+            self.by_length[length].push((word.to_lowercase(), word));
+        }
+    }
+
+    /// Words whose length is within `distance` characters of `length`
+    fn near_length(
+        &self,
+        length: usize,
+        distance: usize,
+    ) -> impl Iterator<Item = &(String, String)> {
+        let end = (length + distance + 1).min(self.by_length.len());
+        let start = length.saturating_sub(distance).min(end);
+        self.by_length[start..end].iter().flatten()
+    }
+}
+
+/// The set of accepted words
+#[derive(Debug)]
+pub struct Dictionary {
+    /// The bundled word list, or the word list named in `[spelling]`
+    base: Cow<'static, WordList>,
+    /// Words of the project dictionary and the glossary
+    extra: WordList,
+    /// Suggestions already computed, by unknown word
+    suggestions: RefCell<HashMap<String, Vec<String>>>,
+}
+
+impl Default for Dictionary {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+impl Dictionary {
+    /// The bundled American English word list
+    pub fn english() -> Self {
+        Self {
+            base: Cow::Borrowed(&ENGLISH),
+            extra: WordList::default(),
+            suggestions: RefCell::default(),
+        }
+    }
+
+    /// The dictionary for a document
+    ///
+    /// Combines the bundled word list (or the `word_list` of `[spelling]`), the
+    /// project dictionary (if it exists) and the terms defined in glossary and
+    /// acronym sections.
+    ///
+    /// # Parameters
+    /// * `root` - Root directory of the document
+    /// * `config` - The `[spelling]` settings (defaults if absent)
+    /// * `files` - Parsed markdown sources of the document
+    ///
+    /// # Returns
+    /// * `Ok(Dictionary)` - The combined dictionary
+    /// * `Err(SpellingError)` - The word list or the project dictionary cannot be
+    ///   read, or British spelling is selected without a word list
+    pub fn load(
+        root: &Path,
+        config: &SpellingConfig,
+        files: &[MarkdownSource],
+    ) -> Result<Self, SpellingError> {
+        let mut dictionary = Self::english();
+        match &config.word_list {
+            Some(word_list) => {
+                let path = root.join(word_list);
+                let content =
+                    fs::read_to_string(&path).map_err(|e| SpellingError::ReadWordList(path, e))?;
+                dictionary.base = Cow::Owned(WordList::parse(&content));
+            }
+            None if config.language == SpellingLanguage::EnGb => {
+                return Err(SpellingError::MissingWordList);
+            }
+            None => {}
+        }
+
+        let path = root.join(&config.dictionary);
+        if path.exists() {
+            let content = fs::read_to_string(&path).map_err(|e| SpellingError::Read(path, e))?;
+            dictionary.extra.extend(project_words(&content));
+        }
+
+        for section in files
+            .iter()
+            .flat_map(|file| file.sections.iter())
+            .filter(|section| is_glossary(section))
+        {
+            let terms: Vec<String> = glossary_terms(section)
+                .iter()
+                .flat_map(|term| words_of(term))
+                .flat_map(|word| [word.to_lowercase(), word])
+                .collect();
+            dictionary.extra.extend(terms);
+        }
+        Ok(dictionary)
+    }
+
+    /// Check whether a word is spelled correctly
+    ///
+    /// A capitalised word is also accepted in lower case, and a possessive
+    /// (`system's`) is accepted when the word it is formed from is.
+    pub fn contains(&self, word: &str) -> bool {
+        let lower = word.to_lowercase();
+        let stem = |w: &str| w.strip_suffix("'s").map(str::to_string);
+        [
+            Some(word.to_string()),
+            Some(lower.clone()),
+            stem(word),
+            stem(&lower),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|candidate| {
+            self.base.words.contains(&candidate) || self.extra.words.contains(&candidate)
+        })
+    }
+
+    /// Dictionary words within a small edit distance of an unknown word
+    ///
+    /// Closer words come first. Among words at the same distance, those with
+    /// the same letters (`teh` -> `the`), then those of the same length, then
+    /// those with the same first letter are preferred. Only words of similar
+    /// length are compared.
+    fn suggest(&self, word: &str) -> Vec<String> {
+        if let Some(cached) = self.suggestions.borrow().get(word) {
+            return cached.clone();
+        }
+        let target = word.to_lowercase();
+        let letters = sorted_letters(&target);
+        let first = target.chars().next();
+        let length = target.chars().count();
+        let mut candidates: Vec<(SuggestionRank, &String)> = self
+            .base
+            .near_length(length, MAX_SUGGESTION_DISTANCE)
+            .chain(self.extra.near_length(length, MAX_SUGGESTION_DISTANCE))
+            .filter_map(|(lower, candidate)| {
+                let distance = edit_distance(&target, lower, MAX_SUGGESTION_DISTANCE)?;
+                let rank = (
+                    distance,
+                    sorted_letters(lower) != letters,
+                    lower.chars().count().abs_diff(length),
+                    lower.chars().next() != first,
+                    lower.clone(),
+                );
+                Some((rank, candidate))
+            })
+            .collect();
+        candidates.sort();
+        candidates.dedup_by(|a, b| a.1 == b.1);
+        let suggestions: Vec<String> = candidates
+            .into_iter()
+            .map(|(_, candidate)| candidate.clone())
+            .take(MAX_SUGGESTIONS)
+            .collect();
+        self.suggestions
+            .borrow_mut()
+            .insert(word.to_string(), suggestions.clone());
+        suggestions
+    }
+}
+
+/// Sort key of a suggestion: edit distance, whether the letters differ from the
+/// unknown word's, length difference, whether the first letter differs, and the
+/// lowercase word
+type SuggestionRank = (usize, bool, usize, bool, String);
+
+/// Letters of a word in sorted order, to recognise transposed letters
+fn sorted_letters(word: &str) -> Vec<char> {
+    let mut letters: Vec<char> = word.chars().collect();
+    letters.sort_unstable();
+    letters
+}
+
+/// Words of a project dictionary file: one per line, `#` starts a comment line
+fn project_words(content: &str) -> impl Iterator<Item = String> + '_ {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+}
+
+/// Append words to the project dictionary, creating it if needed
+///
+/// # Parameters
+/// * `root` - Root directory of the document
+/// * `config` - The `[spelling]` settings (defaults if absent)
+/// * `words` - Words to add
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - The words that were added (words already present are skipped)
+/// * `Err(SpellingError)` - The dictionary could not be read or written
+pub fn add_words(
+    root: &Path,
+    config: &SpellingConfig,
+    words: &[String],
+) -> Result<Vec<String>, SpellingError> {
+    let path = root.join(&config.dictionary);
+    let existing = if path.exists() {
+        fs::read_to_string(&path).map_err(|e| SpellingError::Read(path.clone(), e))?
+    } else {
+        String::new()
+    };
+    let mut known: HashSet<String> = project_words(&existing).collect();
+    let added: Vec<String> = words
+        .iter()
+        .map(|word| word.trim().to_string())
+        .filter(|word| !word.is_empty() && known.insert(word.clone()))
+        .collect();
+    if added.is_empty() {
+        return Ok(added);
+    }
+
+    let separator = if existing.is_empty() || existing.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| SpellingError::Write(path.clone(), e))?;
+    writeln!(file, "{}{}", separator, added.join("\n"))
+        .map_err(|e| SpellingError::Write(path, e))?;
+    Ok(added)
+}
+
+/// Check the spelling of every section, in document order
+///
+/// # Parameters
+/// * `files` - Parsed markdown sources of the document
+/// * `dictionary` - Accepted words
+///
+/// # Returns
+/// * One finding per unknown word occurrence
+pub fn check_spelling(files: &[MarkdownSource], dictionary: &Dictionary) -> Vec<Misspelling> {
+    let mut sorted: Vec<&MarkdownSource> = files.iter().collect();
+    sorted.sort_by(|a, b| a.section_number.cmp(&b.section_number));
+
+    let mut findings = Vec::new();
+    for file in sorted {
+        for (section, lines) in file.section_lines() {
+            let numbered: Vec<(usize, &str)> = lines
+                .into_iter()
+                .enumerate()
+                .map(|(index, line)| (section.line_number + index, line))
+                .collect();
+            findings.extend(section_misspellings(section, &numbered, dictionary));
+        }
+    }
+    findings
+}
+
+/// Check the spelling of a single section
+///
+/// # Parameters
+/// * `section` - The parsed section
+/// * `lines` - Source lines of the section with their 1-indexed line numbers,
+///   used to locate each unknown word
+/// * `dictionary` - Accepted words
+pub(super) fn section_misspellings(
+    section: &MarkdownSection,
+    lines: &[(usize, &str)],
+    dictionary: &Dictionary,
+) -> Vec<Misspelling> {
+    // Code and link text are not checked
+    let prose = |run: &TextRun| !run.code && run.link_url.is_none();
+    let mut texts = vec![section.heading_text.clone()];
+    section
+        .content
+        .iter()
+        .for_each(|block| block.collect_text(&prose, &mut texts));
+
+    let mut cursor = 0;
+    let mut findings = Vec::new();
+    for word in texts.iter().flat_map(|text| words_of(text)) {
+        if dictionary.contains(&word) {
+            continue;
+        }
+        let found = lines[cursor.min(lines.len())..]
+            .iter()
+            .position(|(_, line)| contains_word(line, &word));
+        if let Some(offset) = found {
+            cursor += offset;
+        }
+        findings.push(Misspelling {
+            suggestions: dictionary.suggest(&word),
+            file: section.source_file.clone(),
+            line: found.map_or(section.line_number, |_| lines[cursor].0),
+            word,
+        });
+    }
+    findings
+}
+
+/// Check whether a section defines terms (a glossary or list of acronyms)
+fn is_glossary(section: &MarkdownSection) -> bool {
+    let heading = section.heading_text.to_lowercase();
+    ["glossary", "acronym", "abbreviation"]
+        .iter()
+        .any(|keyword| heading.contains(keyword))
+}
+
+/// Terms defined in a glossary section: the first cell of each table row and
+/// bold text (as in `**Term** - definition`)
+fn glossary_terms(section: &MarkdownSection) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut pending: Vec<&MarkdownBlock> = section.content.iter().collect();
+    while let Some(block) = pending.pop() {
+        match block {
+            MarkdownBlock::Paragraph(runs) | MarkdownBlock::Heading { runs, .. } => {
+                terms.extend(runs.iter().filter(|r| r.bold).map(|r| r.text.clone()));
+            }
+            MarkdownBlock::BlockQuote(blocks) => pending.extend(blocks),
+            MarkdownBlock::List { items, .. } => {
+                pending.extend(items.iter().flat_map(|item| item.content.iter()));
+            }
+            MarkdownBlock::InlineTable { rows, .. } => terms.extend(
+                rows.iter()
+                    .filter_map(|row| row.first())
+                    .map(|cell| cell.iter().map(|run| run.text.as_str()).collect()),
+            ),
+            MarkdownBlock::CsvTable {
+                data: Some(data), ..
+            } => terms.extend(data.iter().skip(1).filter_map(|row| row.first().cloned())),
+            _ => {}
+        }
+    }
+    terms
+}
+
+/// Split text into the words to check
+///
+/// Chunks containing digits, underscores, dots, slashes and similar characters
+/// are identifiers, paths or URLs and are skipped, as are acronyms (all
+/// capitals), words with inner capitals (`camelCase`) and single letters.
+fn words_of(text: &str) -> Vec<String> {
+    let is_identifier = |chunk: &str| {
+        chunk.chars().any(|c| {
+            c.is_ascii_digit()
+                || matches!(
+                    c,
+                    '_' | '.' | '/' | '\\' | '@' | ':' | '=' | '<' | '>' | '{' | '}' | '[' | ']'
+                )
+        })
+    };
+    text.split_whitespace()
+        .map(|chunk| chunk.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|chunk| !is_identifier(chunk))
+        .flat_map(|chunk| chunk.split(|c: char| !(c.is_alphabetic() || c == '\'' || c == '’')))
+        .map(|word| word.replace('’', "'").trim_matches('\'').to_string())
+        .filter(|word| {
+            let mut chars = word.chars();
+            chars.next().is_some() && word.chars().count() > 1 && chars.all(|c| !c.is_uppercase())
+        })
+        .collect()
+}
+
+/// Check whether a line contains a word on word boundaries
+fn contains_word(line: &str, word: &str) -> bool {
+    let line = line.replace('’', "'");
+    line.match_indices(word).any(|(offset, _)| {
+        let before = line[..offset].chars().next_back();
+        let after = line[offset + word.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_model::SectionNumber;
+
+    fn parse_source(content: &str) -> MarkdownSource {
+        let mut source = MarkdownSource {
+            path: PathBuf::from("src/01_design.md"),
+            absolute_path: PathBuf::from("/test/src/01_design.md"),
+            section_number: SectionNumber::parse("01").unwrap(),
+            title: String::new(),
+            raw_content: content.to_string(),
+            sections: Vec::new(),
+        };
+        source.parse(Path::new("/test")).unwrap();
+        source
+    }
+
+    #[test]
+    fn test_words_of() {
+        assert_eq!(
+            words_of("The CSCI's parseConfig() reads config_file.toml (see https://x.io), e.g. v2 — it’s fine-grained."),
+            vec!["The", "reads", "see", "it's", "fine", "grained"]
+        );
+    }
+
+    #[test]
+    fn test_english_dictionary() {
+        let us = Dictionary::english();
+        assert!(us.contains("color") && !us.contains("colour"));
+        assert!(us.contains("Requirements") && us.contains("system's"));
+        assert_eq!(
+            us.suggest("colour").first().map(String::as_str),
+            Some("color")
+        );
+        assert_eq!(us.suggest("teh").first().map(String::as_str), Some("the"));
+        assert_eq!(
+            us.suggest("recieve").first().map(String::as_str),
+            Some("receive")
+        );
+        assert!(us.contains("traceability") && us.contains("metadata"));
+    }
+
+    #[test]
+    fn test_word_list() {
+        let root = std::env::temp_dir().join(format!("sysdoc-word-list-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("en_GB.txt"), "# British\ncolour\nthe\n").unwrap();
+        fs::write(root.join("dictionary.txt"), "colours\n").unwrap();
+
+        let mut config = SpellingConfig {
+            language: SpellingLanguage::EnGb,
+            ..SpellingConfig::default()
+        };
+        assert!(matches!(
+            Dictionary::load(&root, &config, &[]),
+            Err(SpellingError::MissingWordList)
+        ));
+
+        config.word_list = Some("en_GB.txt".to_string());
+        let gb = Dictionary::load(&root, &config, &[]).unwrap();
+        assert!(gb.contains("colour") && gb.contains("colours") && !gb.contains("color"));
+        assert_eq!(gb.suggest("colur"), vec!["colour", "colours"]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_check_spelling() {
+        let source = parse_source(
+            "# Desgin\n\nThe pump shall recieve `recieve_cmd` from [the contoller](https://x.io).\n\n```text\nrecieve\n```\n\n## Glossary\n\n| Term | Definition |\n|------|------------|\n| Flowmeter | A sensor that measures the flow |\n\n## Sensors\n\nEach flowmeter reports its readngs.\n",
+        );
+        let files = [source];
+        let dictionary = Dictionary::load(
+            Path::new("/nonexistent"),
+            &SpellingConfig::default(),
+            &files,
+        )
+        .unwrap();
+
+        let findings: Vec<(String, usize)> = check_spelling(&files, &dictionary)
+            .into_iter()
+            .map(|finding| (finding.word, finding.line))
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                ("Desgin".to_string(), 1),
+                ("recieve".to_string(), 3),
+                ("readngs".to_string(), 17),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_words() {
+        let root = std::env::temp_dir().join(format!("sysdoc-spelling-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let config = SpellingConfig::default();
+        fs::write(root.join("dictionary.txt"), "# Project words\nflowmeter").unwrap();
+
+        let words = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let added = add_words(&root, &config, &words(&["flowmeter", "pumpset"])).unwrap();
+        assert_eq!(added, words(&["pumpset"]));
+        assert_eq!(
+            fs::read_to_string(root.join("dictionary.txt")).unwrap(),
+            "# Project words\nflowmeter\npumpset\n"
+        );
+
+        let dictionary = Dictionary::load(&root, &config, &[]).unwrap();
+        assert!(dictionary.contains("pumpset"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
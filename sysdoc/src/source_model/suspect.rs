@@ -0,0 +1,287 @@
+//! Suspect-link detection
+//!
+//! A trace link is suspect when the content of its target changed after the
+//! link was last reviewed. Reviewed fingerprints of each (section, traced ID)
+//! link are recorded in `trace.lock` at the document root by
+//! `sysdoc trace accept`; validation compares them against the current content
+//! of local sections, upstream sections and catalog entries.
+
+use super::markdown_source::MarkdownSource;
+use crate::diagnostics::Diagnostic;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// File name of the trace lock, relative to the document root
+pub const TRACE_LOCK_FILE: &str = "trace.lock";
+
+/// Header comment written at the top of the trace lock
+const TRACE_LOCK_HEADER: &str = "# Reviewed fingerprints of traced content.\n\
+     # Generated by `sysdoc trace accept`; do not edit by hand.\n\n";
+
+/// Errors that can occur when reading or writing the trace lock
+#[derive(Error, Debug)]
+pub enum TraceLockError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("TOML parse error: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("TOML serialize error: {0}")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+/// Reviewed fingerprints of trace targets, keyed by section ID then traced ID
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceLock {
+    /// section_id -> traced ID -> fingerprint of the target when the link was accepted
+    #[serde(default)]
+    pub links: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl TraceLock {
+    /// Load the trace lock from a document root
+    ///
+    /// # Returns
+    /// * `Ok(Some(TraceLock))` - The lock file exists and was parsed
+    /// * `Ok(None)` - The document has no lock file (suspect-link checks are disabled)
+    /// * `Err(TraceLockError)` - The lock file could not be read or parsed
+    pub fn load(root: &Path) -> Result<Option<Self>, TraceLockError> {
+        let path = root.join(TRACE_LOCK_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(Some(toml::from_str(&content)?))
+    }
+
+    /// Write the trace lock to a document root
+    pub fn save(&self, root: &Path) -> Result<(), TraceLockError> {
+        let content = toml::to_string(self)?;
+        std::fs::write(
+            root.join(TRACE_LOCK_FILE),
+            format!("{TRACE_LOCK_HEADER}{content}"),
+        )?;
+        Ok(())
+    }
+
+    /// Fingerprint recorded for a link, if it has been accepted
+    pub fn get(&self, section_id: &str, traced_id: &str) -> Option<&str> {
+        self.links
+            .get(section_id)?
+            .get(traced_id)
+            .map(String::as_str)
+    }
+
+    /// Record the fingerprint of a link
+    pub fn insert(&mut self, section_id: &str, traced_id: &str, fingerprint: String) {
+        self.links
+            .entry(section_id.to_string())
+            .or_default()
+            .insert(traced_id.to_string(), fingerprint);
+    }
+}
+
+/// Why a link is suspect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuspectReason {
+    /// The target's content changed since the link was accepted
+    Changed,
+    /// The link has never been accepted
+    Unreviewed,
+}
+
+/// A trace link whose target needs to be re-reviewed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuspectLink {
+    /// `section_id` of the tracing section
+    pub section_id: String,
+    /// Source file of the tracing section (relative to document root)
+    pub source_file: PathBuf,
+    /// Line number of the tracing section's heading
+    pub line: usize,
+    /// The traced ID whose content changed
+    pub traced_id: String,
+    /// Why the link is suspect
+    pub reason: SuspectReason,
+}
+
+impl SuspectLink {
+    /// Description of the suspect link without the location
+    pub fn message(&self) -> String {
+        let reason = match self.reason {
+            SuspectReason::Changed => "changed since the link was accepted",
+            SuspectReason::Unreviewed => "has not been accepted yet",
+        };
+        format!(
+            "Suspect link {} -> {}: '{}' {}",
+            self.section_id, self.traced_id, self.traced_id, reason
+        )
+    }
+}
+
+impl std::fmt::Display for SuspectLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}",
+            self.source_file.display(),
+            self.line,
+            self.message()
+        )
+    }
+}
+
+impl From<&SuspectLink> for Diagnostic {
+    fn from(suspect: &SuspectLink) -> Self {
+        Diagnostic::warning("suspect-link", suspect.message())
+            .at_line(&suspect.source_file, suspect.line)
+            .with_suggestion(format!(
+                "review the section, then run 'sysdoc trace accept --section {} --target {}'",
+                suspect.section_id, suspect.traced_id
+            ))
+    }
+}
+
+/// Fingerprint the content of a trace target
+///
+/// The content is normalized with [`normalize_content`] before hashing, so that
+/// reformatting whitespace does not make links suspect. The hash is 64-bit
+/// FNV-1a, which is stable across platforms and toolchain versions.
+pub fn fingerprint(content: &str) -> String {
+    let hash = normalize_content(content)
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{hash:016x}")
+}
+
+/// Collapse runs of whitespace and drop blank lines
+pub fn normalize_content(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Fingerprints of every section with a `section_id` in a set of source files
+///
+/// A section's content is its heading and body up to the next heading, without
+/// `sysdoc` metadata blocks, so that editing trace metadata does not affect
+/// the sections it traces to.
+pub fn section_fingerprints(files: &[MarkdownSource]) -> Vec<(String, String)> {
+    section_contents(files)
+        .into_iter()
+        .map(|(section_id, content)| (section_id, fingerprint(&content)))
+        .collect()
+}
+
+/// Normalized content of every section with a `section_id` in a set of source files
+///
+/// # Returns
+/// * `(section_id, content)` pairs, where content is the heading line and body
+///   up to the next heading without `sysdoc` blocks, as given by [`normalize_content`]
+pub fn section_contents(files: &[MarkdownSource]) -> Vec<(String, String)> {
+    files
+        .iter()
+        .flat_map(MarkdownSource::section_lines)
+        .filter_map(|(section, lines)| {
+            let section_id = section.metadata.as_ref()?.section_id.clone()?;
+            let content = strip_sysdoc_blocks(&lines);
+            Some((section_id, normalize_content(&content)))
+        })
+        .collect()
+}
+
+/// Remove fenced `sysdoc` code blocks from a run of markdown lines
+fn strip_sysdoc_blocks(lines: &[&str]) -> String {
+    let mut kept = Vec::with_capacity(lines.len());
+    let mut closing_fence: Option<&str> = None;
+    for line in lines {
+        let trimmed = line.trim_start();
+        match closing_fence {
+            Some(fence) => {
+                if trimmed.starts_with(fence) {
+                    closing_fence = None;
+                }
+            }
+            None => {
+                let fence = ["```", "~~~"]
+                    .into_iter()
+                    .find(|fence| trimmed.starts_with(fence));
+                match fence {
+                    Some(fence) if trimmed[fence.len()..].trim() == "sysdoc" => {
+                        closing_fence = Some(fence);
+                    }
+                    _ => kept.push(*line),
+                }
+            }
+        }
+    }
+    kept.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_ignores_whitespace() {
+        assert_eq!(
+            fingerprint("The system shall\n\n  boot.  \n"),
+            fingerprint("The system shall\nboot.")
+        );
+        assert_ne!(
+            fingerprint("The system shall boot."),
+            fingerprint("The system shall reboot.")
+        );
+        assert_eq!(fingerprint("").len(), 16);
+    }
+
+    #[test]
+    fn test_strip_sysdoc_blocks() {
+        let lines = [
+            "# Power",
+            "",
+            "```sysdoc",
+            "section_id = \"SRS-1\"",
+            "```",
+            "",
+            "```rust",
+            "let x = 1;",
+            "```",
+            "Text.",
+        ];
+        assert_eq!(
+            strip_sysdoc_blocks(&lines),
+            "# Power\n\n\n```rust\nlet x = 1;\n```\nText."
+        );
+    }
+
+    #[test]
+    fn test_trace_lock_round_trip() {
+        let dir = std::env::temp_dir().join(format!("sysdoc-trace-lock-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(TraceLock::load(&dir).unwrap(), None);
+
+        let mut lock = TraceLock::default();
+        lock.insert("SDD-1.2", "SRS-001", fingerprint("text"));
+        lock.save(&dir).unwrap();
+
+        let loaded = TraceLock::load(&dir).unwrap().unwrap();
+        assert_eq!(loaded, lock);
+        assert_eq!(
+            loaded.get("SDD-1.2", "SRS-001"),
+            Some(fingerprint("text").as_str())
+        );
+        assert_eq!(loaded.get("SDD-1.2", "SRS-002"), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,272 @@
+//! DID template conformance
+//!
+//! `sysdoc init` creates one markdown file per paragraph of the document's DID
+//! template. This module checks that those sections are still present with the
+//! template's number and heading, have been written (rather than holding only
+//! the `<!-- GUIDANCE: -->` comment), and set the metadata the template requires.
+
+use super::blocks::MarkdownBlock;
+use super::markdown_source::MarkdownSource;
+use super::section_number::SectionNumber;
+use super::validation::ValidationError;
+use crate::template_config::TemplateConfig;
+use std::path::Path;
+
+/// Marker opening a template guidance comment
+const GUIDANCE_MARKER: &str = "<!-- GUIDANCE:";
+
+/// A markdown section file declared by a template
+struct TemplatedSection<'a> {
+    /// File path relative to the document root
+    path: &'a Path,
+    /// Section number encoded in the file name
+    number: SectionNumber,
+    /// Heading of the section
+    heading: &'a str,
+    /// Metadata fields the section must set
+    required_metadata: &'a [String],
+}
+
+/// Collect the numbered markdown section files of a template, in section order
+fn templated_sections(template: &TemplateConfig) -> Vec<TemplatedSection<'_>> {
+    let mut sections: Vec<TemplatedSection> = template
+        .files
+        .iter()
+        .filter_map(|(path, file)| {
+            let path = Path::new(path);
+            if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
+                return None;
+            }
+            let (number, _) = path.file_stem()?.to_str()?.split_once('_')?;
+            Some(TemplatedSection {
+                path,
+                number: SectionNumber::parse(number)?,
+                heading: file.heading()?,
+                required_metadata: file.required_metadata(),
+            })
+        })
+        .collect();
+    sections.sort_by(|a, b| a.number.cmp(&b.number));
+    sections
+}
+
+/// Section number as displayed for the section's h1 heading (`01.00` -> `1`)
+fn display_number(number: &SectionNumber) -> String {
+    number
+        .without_parent_marker()
+        .filter(|_| number.is_parent_marker())
+        .unwrap_or_else(|| number.clone())
+        .to_string()
+}
+
+/// Check whether a file still holds only the template's guidance comment
+fn is_guidance_only(file: &MarkdownSource) -> bool {
+    file.raw_content.contains(GUIDANCE_MARKER)
+        && file.sections.iter().all(|section| {
+            section
+                .content
+                .iter()
+                .all(|block| matches!(block, MarkdownBlock::Html(_)))
+        })
+}
+
+/// Check a set of source files against a DID template
+///
+/// A templated section is matched to the source file at the template's path,
+/// or else to the file whose heading or section number the template gives it.
+///
+/// # Parameters
+/// * `files` - The document's markdown source files
+/// * `template` - The template named by `document_template`
+///
+/// # Returns
+/// * One error per missing, renamed, renumbered or unwritten section, and per
+///   missing required metadata field, in template order
+pub fn check_template_conformance(
+    files: &[MarkdownSource],
+    template: &TemplateConfig,
+) -> Vec<ValidationError> {
+    templated_sections(template)
+        .iter()
+        .flat_map(|expected| check_section(files, expected, &template.name))
+        .collect()
+}
+
+/// Check a single templated section
+fn check_section(
+    files: &[MarkdownSource],
+    expected: &TemplatedSection,
+    template: &str,
+) -> Vec<ValidationError> {
+    let heading_matches = |file: &MarkdownSource| {
+        file.sections.first().is_some_and(|section| {
+            section
+                .heading_text
+                .trim()
+                .eq_ignore_ascii_case(expected.heading.trim())
+        })
+    };
+    let found = files
+        .iter()
+        .find(|file| file.path == expected.path)
+        .or_else(|| files.iter().find(|file| heading_matches(file)))
+        .or_else(|| {
+            files
+                .iter()
+                .find(|file| file.section_number == expected.number)
+        });
+    let Some((file, section)) = found.and_then(|file| Some((file, file.sections.first()?))) else {
+        return vec![ValidationError::MissingTemplateSection {
+            template: template.to_string(),
+            section_number: display_number(&expected.number),
+            heading: expected.heading.to_string(),
+        }];
+    };
+
+    let mut errors = Vec::new();
+    if !heading_matches(file) {
+        errors.push(ValidationError::RenamedTemplateSection {
+            template: template.to_string(),
+            expected: expected.heading.to_string(),
+            found: section.heading_text.clone(),
+            referenced_in: file.path.clone(),
+            line: section.line_number,
+        });
+    } else if file.section_number != expected.number {
+        errors.push(ValidationError::MovedTemplateSection {
+            heading: section.heading_text.clone(),
+            expected: display_number(&expected.number),
+            found: section.section_number.to_string(),
+            referenced_in: file.path.clone(),
+            line: section.line_number,
+        });
+    }
+
+    if is_guidance_only(file) {
+        errors.push(ValidationError::GuidanceOnlySection {
+            heading: section.heading_text.clone(),
+            referenced_in: file.path.clone(),
+            line: section.line_number,
+        });
+    }
+
+    let missing_metadata = expected.required_metadata.iter().filter(|field| {
+        !section
+            .metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.has_field(field))
+    });
+    errors.extend(
+        missing_metadata.map(|field| ValidationError::MissingTemplateMetadata {
+            template: template.to_string(),
+            field: field.clone(),
+            heading: section.heading_text.clone(),
+            referenced_in: file.path.clone(),
+            line: section.line_number,
+        }),
+    );
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document_config::DiagnosticSeverity;
+    use std::path::PathBuf;
+
+    fn parse_source(path: &str, content: &str) -> MarkdownSource {
+        let number = Path::new(path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.split_once('_'))
+            .and_then(|(number, _)| SectionNumber::parse(number))
+            .unwrap();
+        let mut source = MarkdownSource {
+            path: PathBuf::from(path),
+            absolute_path: Path::new("/test").join(path),
+            section_number: number,
+            title: String::new(),
+            raw_content: content.to_string(),
+            sections: Vec::new(),
+        };
+        source.parse(Path::new("/test")).unwrap();
+        source
+    }
+
+    #[test]
+    fn test_template_conformance() {
+        let template: TemplateConfig = toml::from_str(
+            r#"
+name = "srs-test"
+document_type = "SRS"
+template_spec = "TEST"
+
+[files."sysdoc.toml"]
+content = ""
+
+[files."src/01_scope/01.00_scope.md"]
+heading = "Scope"
+guidance = "1. Scope."
+
+[files."src/01_scope/01.01_identification.md"]
+heading = "Identification"
+guidance = "1.1 Identification."
+
+[files."src/02_requirements/02.00_requirements.md"]
+heading = "Requirements"
+guidance = "2. Requirements."
+required_metadata = ["section_id", "attributes.owner"]
+
+[files."src/03_notes/03.00_notes.md"]
+heading = "Notes"
+guidance = "3. Notes."
+
+[files."src/04_appendix/04.00_appendix.md"]
+heading = "Appendix"
+guidance = "4. Appendix."
+"#,
+        )
+        .unwrap();
+        let files = vec![
+            parse_source(
+                "src/01_scope/01.00_scope.md",
+                "<!-- GUIDANCE:\n1. Scope.\n-->\n\n# Scope\n\nThis document covers the pump.\n",
+            ),
+            parse_source(
+                "src/01_scope/01.01_identification.md",
+                "<!-- GUIDANCE:\n1.1 Identification.\n-->\n\n# Identification\n\n",
+            ),
+            parse_source(
+                "src/02_requirements/02.00_requirements.md",
+                "# Needs\n\n```sysdoc\nsection_id = \"SRS-1\"\n```\n\nThe pump shall pump.\n",
+            ),
+            parse_source("src/05_notes/05.00_notes.md", "# Notes\n\nNone.\n"),
+        ];
+
+        let found = check_template_conformance(&files, &template);
+        let errors: Vec<String> = found.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "Section 'Identification' still contains only template guidance",
+                "Section 'Needs' is titled 'Requirements' in template 'srs-test'",
+                "Section 'Needs' is missing the metadata 'attributes.owner' required by template 'srs-test'",
+                "Section 'Notes' is numbered 5 but its template places it at 3",
+                "Section 4 'Appendix' required by template 'srs-test' is missing",
+            ]
+        );
+
+        // Unwritten sections are expected while a document is being written
+        let severities: Vec<DiagnosticSeverity> = found.iter().map(|e| e.severity()).collect();
+        assert_eq!(
+            severities,
+            vec![
+                DiagnosticSeverity::Warning,
+                DiagnosticSeverity::Error,
+                DiagnosticSeverity::Error,
+                DiagnosticSeverity::Error,
+                DiagnosticSeverity::Error,
+            ]
+        );
+    }
+}
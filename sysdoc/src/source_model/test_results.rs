@@ -0,0 +1,379 @@
+//! Test results
+//!
+//! Loads JUnit XML or CSV result files declared with `[[test_results]]` in
+//! sysdoc.toml and resolves the pass/fail/not-run status of test case sections
+//! (through their `test_cases`) and of the IDs those sections trace to.
+
+use super::blocks::MarkdownBlock;
+use super::text_run::TextRun;
+use super::types::Alignment;
+use crate::document_config::{TestResultsConfig, TestResultsFormat};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Result of a test, a test case section, or a verified requirement
+///
+/// Variants are ordered from best to worst so that aggregating takes the maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TestStatus {
+    /// Every test passed
+    Pass,
+    /// At least one test was skipped or has no result
+    NotRun,
+    /// At least one test failed
+    Fail,
+}
+
+impl TestStatus {
+    /// Label shown in generated tables
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Pass => "Pass",
+            Self::NotRun => "Not Run",
+            Self::Fail => "Fail",
+        }
+    }
+
+    /// Combine statuses: any failure fails, any missing result is not run
+    ///
+    /// # Returns
+    /// * The worst status, or `NotRun` when there are no statuses at all
+    pub fn aggregate(statuses: impl IntoIterator<Item = Self>) -> Self {
+        statuses.into_iter().max().unwrap_or(Self::NotRun)
+    }
+
+    /// Parse a status value from a CSV results file
+    fn from_csv(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "pass" | "passed" | "ok" | "success" => Some(Self::Pass),
+            "fail" | "failed" | "failure" | "error" => Some(Self::Fail),
+            "skip" | "skipped" | "not run" | "notrun" | "ignored" => Some(Self::NotRun),
+            _ => None,
+        }
+    }
+}
+
+/// Errors that can occur when loading a results file
+#[derive(Error, Debug)]
+pub enum TestResultsError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("XML error: {0}")]
+    Xml(#[from] roxmltree::Error),
+
+    #[error("Cannot infer results format from extension; set `format` to \"junit\" or \"csv\"")]
+    UnknownFormat,
+
+    #[error("Column '{0}' not found in results file")]
+    MissingColumn(String),
+
+    #[error("Unknown test status '{status}' for test '{test}'")]
+    UnknownStatus { test: String, status: String },
+}
+
+/// Results of all tests from all result files, keyed by test name
+#[derive(Debug, Clone, Default)]
+pub struct TestResults {
+    /// Status by test name (JUnit tests are also keyed by `classname.name`)
+    results: BTreeMap<String, TestStatus>,
+}
+
+impl TestResults {
+    /// Load all result files declared in the document configuration
+    ///
+    /// A test that appears more than once keeps its worst result.
+    ///
+    /// # Parameters
+    /// * `root` - Document root directory (result paths are relative to it)
+    /// * `configs` - Result file declarations from sysdoc.toml
+    ///
+    /// # Returns
+    /// * `Ok(TestResults)` - Results from all files
+    /// * `Err((PathBuf, TestResultsError))` - The file that failed to load and why
+    pub fn load(
+        root: &Path,
+        configs: &[TestResultsConfig],
+    ) -> Result<Self, (PathBuf, TestResultsError)> {
+        let mut results = Self::default();
+        for config in configs {
+            let path = PathBuf::from(&config.path);
+            let entries = load_results_file(root, config).map_err(|e| (path.clone(), e))?;
+            for (test, status) in entries {
+                results.insert(test, status);
+            }
+        }
+        Ok(results)
+    }
+
+    /// Record a result, keeping the worst status for repeated tests
+    pub fn insert(&mut self, test: String, status: TestStatus) {
+        let entry = self.results.entry(test).or_insert(status);
+        *entry = (*entry).max(status);
+    }
+
+    /// Look up the result of a test by name
+    pub fn get(&self, test: &str) -> Option<TestStatus> {
+        self.results.get(test).copied()
+    }
+
+    /// Status of a set of tests; tests without a result count as not run
+    pub fn status_of<'a>(&self, tests: impl IntoIterator<Item = &'a str>) -> TestStatus {
+        TestStatus::aggregate(
+            tests
+                .into_iter()
+                .map(|test| self.get(test).unwrap_or(TestStatus::NotRun)),
+        )
+    }
+}
+
+/// The resolved result of a test case section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCaseResult {
+    /// `section_id` of the test case section
+    pub section_id: String,
+    /// Heading of the test case section
+    pub heading: String,
+    /// Tests implementing the test case (its `test_cases`, or its `section_id`)
+    pub tests: Vec<String>,
+    /// Aggregated status of the tests
+    pub status: TestStatus,
+    /// IDs the test case verifies (its `traced_ids`)
+    pub traced_ids: Vec<String>,
+}
+
+/// Build the table of test case sections and their results
+///
+/// # Parameters
+/// * `cases` - Test case results, in the order rows should appear
+///
+/// # Returns
+/// * A table with Test Case, Title, Tests and Result columns
+pub fn build_test_results_table(cases: &[TestCaseResult]) -> MarkdownBlock {
+    let rows = cases
+        .iter()
+        .map(|case| {
+            vec![
+                vec![TextRun::new(case.section_id.clone())],
+                vec![TextRun::new(case.heading.clone())],
+                vec![TextRun::new(case.tests.join(", "))],
+                vec![TextRun::new(case.status.label().to_string())],
+            ]
+        })
+        .collect();
+
+    MarkdownBlock::InlineTable {
+        alignments: vec![Alignment::None; 4],
+        headers: ["Test Case", "Title", "Tests", "Result"]
+            .into_iter()
+            .map(|header| vec![TextRun::new(header.to_string())])
+            .collect(),
+        rows,
+    }
+}
+
+/// Build the verification status matrix of the IDs traced by test cases
+///
+/// Each traced ID's status aggregates the results of every test case tracing
+/// to it: it passes only if all of them pass.
+///
+/// # Parameters
+/// * `cases` - Test case results
+///
+/// # Returns
+/// * A table with Requirement, Verified By and Status columns, sorted by ID
+pub fn build_verification_status_table(cases: &[TestCaseResult]) -> MarkdownBlock {
+    let mut verified_by: BTreeMap<&str, Vec<&TestCaseResult>> = BTreeMap::new();
+    for case in cases {
+        for traced_id in &case.traced_ids {
+            verified_by.entry(traced_id).or_default().push(case);
+        }
+    }
+
+    let rows = verified_by
+        .into_iter()
+        .map(|(traced_id, cases)| {
+            let status = TestStatus::aggregate(cases.iter().map(|case| case.status));
+            let case_ids: Vec<&str> = cases.iter().map(|case| case.section_id.as_str()).collect();
+            vec![
+                vec![TextRun::new(traced_id.to_string())],
+                vec![TextRun::new(case_ids.join(", "))],
+                vec![TextRun::new(status.label().to_string())],
+            ]
+        })
+        .collect();
+
+    MarkdownBlock::InlineTable {
+        alignments: vec![Alignment::None; 3],
+        headers: ["Requirement", "Verified By", "Status"]
+            .into_iter()
+            .map(|header| vec![TextRun::new(header.to_string())])
+            .collect(),
+        rows,
+    }
+}
+
+/// Load the (test, status) pairs of a single results file
+fn load_results_file(
+    root: &Path,
+    config: &TestResultsConfig,
+) -> Result<Vec<(String, TestStatus)>, TestResultsError> {
+    let relative_path = PathBuf::from(&config.path);
+    let absolute_path = root.join(&relative_path);
+
+    let format = match config.format {
+        Some(format) => format,
+        None => {
+            TestResultsFormat::from_path(&relative_path).ok_or(TestResultsError::UnknownFormat)?
+        }
+    };
+
+    match format {
+        TestResultsFormat::Junit => read_junit(&std::fs::read_to_string(absolute_path)?),
+        TestResultsFormat::Csv => read_csv(&absolute_path, config),
+    }
+}
+
+/// Read results from JUnit XML
+///
+/// Each `<testcase>` passes unless it contains `<failure>` or `<error>` (fail)
+/// or `<skipped>` (not run). Tests are keyed by `name` and, when a `classname`
+/// is present, also by `classname.name`.
+fn read_junit(xml: &str) -> Result<Vec<(String, TestStatus)>, TestResultsError> {
+    let document = roxmltree::Document::parse(xml)?;
+    Ok(document
+        .descendants()
+        .filter(|n| n.has_tag_name("testcase"))
+        .flat_map(|testcase| {
+            let status = testcase
+                .children()
+                .filter(roxmltree::Node::is_element)
+                .map(|child| match child.tag_name().name() {
+                    "failure" | "error" => TestStatus::Fail,
+                    "skipped" => TestStatus::NotRun,
+                    _ => TestStatus::Pass,
+                })
+                .max()
+                .unwrap_or(TestStatus::Pass);
+            let name = testcase.attribute("name").unwrap_or_default();
+            let qualified = testcase
+                .attribute("classname")
+                .filter(|classname| !classname.is_empty())
+                .map(|classname| format!("{classname}.{name}"));
+            std::iter::once(name.to_string())
+                .chain(qualified)
+                .map(move |test| (test, status))
+        })
+        .collect())
+}
+
+/// Read results from a CSV file with test name and status columns
+fn read_csv(
+    path: &Path,
+    config: &TestResultsConfig,
+) -> Result<Vec<(String, TestStatus)>, TestResultsError> {
+    let mut reader = csv::Reader::from_path(path)?;
+    let headers = reader.headers()?.clone();
+    let column_index = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h.trim() == name)
+            .ok_or_else(|| TestResultsError::MissingColumn(name.to_string()))
+    };
+    let test_index = column_index(&config.test_column)?;
+    let status_index = column_index(&config.status_column)?;
+
+    let mut results = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let test = record
+            .get(test_index)
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        let status = record.get(status_index).unwrap_or_default();
+        if test.is_empty() {
+            continue;
+        }
+        let status =
+            TestStatus::from_csv(status).ok_or_else(|| TestResultsError::UnknownStatus {
+                test: test.clone(),
+                status: status.to_string(),
+            })?;
+        results.push((test, status));
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_junit() {
+        let xml = r#"<testsuites>
+  <testsuite name="parser">
+    <testcase classname="parser" name="parses_input"/>
+    <testcase classname="parser" name="rejects_garbage"><failure message="boom"/></testcase>
+    <testcase classname="parser" name="slow"><skipped/></testcase>
+  </testsuite>
+</testsuites>"#;
+        let mut results = TestResults::default();
+        for (test, status) in read_junit(xml).unwrap() {
+            results.insert(test, status);
+        }
+        assert_eq!(results.get("parses_input"), Some(TestStatus::Pass));
+        assert_eq!(results.get("parser.parses_input"), Some(TestStatus::Pass));
+        assert_eq!(results.get("rejects_garbage"), Some(TestStatus::Fail));
+        assert_eq!(results.get("slow"), Some(TestStatus::NotRun));
+
+        assert_eq!(
+            results.status_of(["parses_input", "slow"]),
+            TestStatus::NotRun
+        );
+        assert_eq!(
+            results.status_of(["parses_input", "missing"]),
+            TestStatus::NotRun
+        );
+        assert_eq!(
+            results.status_of(["slow", "rejects_garbage"]),
+            TestStatus::Fail
+        );
+        assert_eq!(results.status_of(["parses_input"]), TestStatus::Pass);
+    }
+
+    #[test]
+    fn test_load_csv_results() {
+        let dir = std::env::temp_dir().join(format!("sysdoc-test-results-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("results.csv"),
+            "test,status\nTC-1,PASSED\nTC-2,fail\nTC-1,skipped\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("bad.csv"), "test,status\nTC-3,maybe\n").unwrap();
+
+        let config = |path: &str| -> TestResultsConfig {
+            toml::from_str(&format!("path = \"{}\"", path)).unwrap()
+        };
+        let results = TestResults::load(&dir, &[config("results.csv")]).unwrap();
+        // Repeated tests keep their worst result
+        assert_eq!(results.get("TC-1"), Some(TestStatus::NotRun));
+        assert_eq!(results.get("TC-2"), Some(TestStatus::Fail));
+
+        let Err((path, error)) = TestResults::load(&dir, &[config("bad.csv")]) else {
+            panic!("Expected an unknown status error");
+        };
+        assert_eq!(path, PathBuf::from("bad.csv"));
+        assert_eq!(
+            error.to_string(),
+            "Unknown test status 'maybe' for test 'TC-3'"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
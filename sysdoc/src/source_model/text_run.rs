@@ -3,6 +3,8 @@
 //! A text run is a span of text with consistent formatting applied.
 //! This is the fundamental unit for rendering formatted text in DOCX.
 
+use super::types::SourceSpan;
+
 /// A span of text with consistent formatting
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TextRun {
@@ -32,6 +34,9 @@ pub struct TextRun {
 
     /// Link title (if this text is part of a hyperlink)
     pub link_title: Option<String>,
+
+    /// Location of the hyperlink (`[text](url)`) in the source file
+    pub link_span: Option<SourceSpan>,
 }
 
 impl TextRun {
@@ -53,6 +58,7 @@ impl TextRun {
             subscript: false,
             link_url: None,
             link_title: None,
+            link_span: None,
         }
     }
 
@@ -75,6 +81,7 @@ impl TextRun {
             subscript: formatting.subscript,
             link_url: formatting.link_url.clone(),
             link_title: formatting.link_title.clone(),
+            link_span: formatting.link_span.clone(),
         }
     }
 
@@ -122,6 +129,9 @@ pub struct TextFormatting {
 
     /// Link title (if inside a link)
     pub link_title: Option<String>,
+
+    /// Location of the link (if inside a link)
+    pub link_span: Option<SourceSpan>,
 }
 
 impl TextFormatting {
@@ -0,0 +1,526 @@
+//! Traceability graph diagrams
+//!
+//! Builds a graph of sections and the IDs they trace to, clustered by the
+//! document that defines each node, and renders it as Graphviz DOT or as a
+//! self-contained SVG. The SVG uses a fixed column layout (one column per
+//! document) so that it can be produced offline without Graphviz installed.
+
+use super::section_number::SectionNumber;
+use super::traceability::{SectionTrace, TraceTargets};
+use crate::text::escape_xml;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Directory (relative to the document root) where embedded trace graphs are written
+pub const TRACE_GRAPH_DIR: &str = ".sysdoc/trace-graphs";
+
+/// Cluster name for catalog entries, which have no defining document
+const CATALOG_CLUSTER: &str = "Catalog";
+
+/// Cluster name for traced IDs that do not resolve to a known target
+const UNRESOLVED_CLUSTER: &str = "Unresolved";
+
+/// Height of a node box in the SVG
+const NODE_HEIGHT: f64 = 26.0;
+/// Vertical gap between node boxes
+const NODE_GAP: f64 = 12.0;
+/// Padding inside a cluster box
+const CLUSTER_PADDING: f64 = 14.0;
+/// Height reserved for a cluster's label
+const CLUSTER_HEADER: f64 = 24.0;
+/// Horizontal gap between clusters (room for edges)
+const COLUMN_GAP: f64 = 90.0;
+/// Approximate width of one character of node text
+const CHAR_WIDTH: f64 = 7.0;
+/// Maximum number of characters of a title shown in a node
+const MAX_TITLE_CHARS: usize = 32;
+
+/// Selection of the sections shown in a trace graph (`[trace_graph]` in a sysdoc block)
+///
+/// ```toml
+/// [trace_graph]
+/// chapter = "3"
+/// caption = "Design traceability"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TraceGraphSpec {
+    /// Only include sections whose `section_id` starts with this prefix
+    pub section_id_prefix: Option<String>,
+    /// Only include sections numbered under this chapter (e.g., "3" or "3.2")
+    pub chapter: Option<String>,
+    /// Caption used as the image's alternative text
+    pub caption: Option<String>,
+}
+
+impl TraceGraphSpec {
+    /// Check whether a section is selected by this spec
+    fn includes(&self, trace: &SectionTrace) -> bool {
+        let id_matches = self
+            .section_id_prefix
+            .as_ref()
+            .is_none_or(|prefix| trace.section_id.starts_with(prefix.as_str()));
+        let chapter_matches = self
+            .chapter
+            .as_deref()
+            .and_then(SectionNumber::parse)
+            .is_none_or(|chapter| trace.section_number.parts().starts_with(chapter.parts()));
+        id_matches && chapter_matches
+    }
+}
+
+/// A trace graph embedded in a section, written to disk only when the document is built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedTraceGraph {
+    /// Source file of the section that requested the graph (relative to the document root)
+    pub source_file: PathBuf,
+    /// Line of the section's heading
+    pub line: usize,
+    /// SVG path referenced by the section's image block
+    pub absolute_path: PathBuf,
+    /// The graph to render
+    pub graph: TraceGraph,
+}
+
+/// A node of the trace graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphNode {
+    /// Section ID or traced ID
+    pub id: String,
+    /// Title of the section or requirement (may be empty)
+    pub title: String,
+}
+
+/// A group of nodes defined by the same document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphCluster {
+    /// Document ID, "Catalog", or "Unresolved"
+    pub name: String,
+    /// Nodes in display order
+    pub nodes: Vec<GraphNode>,
+}
+
+/// A traceability graph: sections and traced IDs, clustered by document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceGraph {
+    /// Clusters in display order (the local document first)
+    pub clusters: Vec<GraphCluster>,
+    /// Edges as (section ID, traced ID) pairs
+    pub edges: Vec<(String, String)>,
+}
+
+impl TraceGraph {
+    /// Build the graph for the sections selected by a spec
+    ///
+    /// # Parameters
+    /// * `spec` - Selection of sections to include
+    /// * `traces` - All local section traces
+    /// * `targets` - Known trace targets (local, upstream, and catalog)
+    /// * `document_id` - `document_id` of the local document
+    ///
+    /// # Returns
+    /// * The selected sections, every ID they trace to, and the links between them
+    pub fn build(
+        spec: &TraceGraphSpec,
+        traces: &[SectionTrace],
+        targets: &TraceTargets,
+        document_id: &str,
+    ) -> Self {
+        let mut selected: Vec<&SectionTrace> =
+            traces.iter().filter(|trace| spec.includes(trace)).collect();
+        selected.sort_by(|a, b| a.section_number.cmp(&b.section_number));
+
+        let edges: Vec<(String, String)> = selected
+            .iter()
+            .flat_map(|trace| {
+                trace
+                    .traced_ids
+                    .iter()
+                    .map(|id| (trace.section_id.clone(), id.clone()))
+            })
+            .collect();
+
+        let local_nodes: Vec<GraphNode> = selected
+            .iter()
+            .map(|trace| GraphNode {
+                id: trace.section_id.clone(),
+                title: trace.heading.clone(),
+            })
+            .collect();
+        let mut shown: BTreeSet<&str> = local_nodes.iter().map(|node| node.id.as_str()).collect();
+
+        // Traced IDs grouped by defining document; BTreeMap keeps clusters and nodes sorted
+        let mut target_clusters: BTreeMap<(u8, String), Vec<GraphNode>> = BTreeMap::new();
+        for (_, traced_id) in &edges {
+            if !shown.insert(traced_id) {
+                continue;
+            }
+            let (key, title) = match targets.get(traced_id) {
+                Some(target) if target.document_id == document_id => {
+                    ((0, document_id.to_string()), target.title.clone())
+                }
+                Some(target) if !target.document_id.is_empty() => {
+                    ((1, target.document_id.clone()), target.title.clone())
+                }
+                Some(target) => ((2, CATALOG_CLUSTER.to_string()), target.title.clone()),
+                None => ((3, UNRESOLVED_CLUSTER.to_string()), String::new()),
+            };
+            target_clusters.entry(key).or_default().push(GraphNode {
+                id: traced_id.clone(),
+                title,
+            });
+        }
+
+        let mut clusters = vec![GraphCluster {
+            name: document_id.to_string(),
+            nodes: local_nodes,
+        }];
+        for ((_, name), mut nodes) in target_clusters {
+            nodes.sort_by(|a, b| a.id.cmp(&b.id));
+            match clusters.iter_mut().find(|cluster| cluster.name == name) {
+                Some(cluster) => cluster.nodes.extend(nodes),
+                None => clusters.push(GraphCluster { name, nodes }),
+            }
+        }
+        clusters.retain(|cluster| !cluster.nodes.is_empty());
+
+        Self { clusters, edges }
+    }
+
+    /// Write the graph as SVG, with a DOT file of the same name alongside it
+    ///
+    /// # Parameters
+    /// * `svg_path` - Path of the SVG file; parent directories are created
+    ///
+    /// # Returns
+    /// * `Ok(())` - Both files were written
+    /// * `Err(std::io::Error)` - A directory or file could not be written
+    pub fn write(&self, svg_path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = svg_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(svg_path, self.to_svg())?;
+        std::fs::write(svg_path.with_extension("dot"), self.to_dot())
+    }
+
+    /// Render the graph as Graphviz DOT
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from(
+            "digraph traceability {\n  rankdir=LR;\n  node [shape=box, style=rounded, fontname=\"Helvetica\"];\n",
+        );
+        for (index, cluster) in self.clusters.iter().enumerate() {
+            dot.push_str(&format!(
+                "  subgraph cluster_{index} {{\n    label={};\n",
+                dot_string(&cluster.name)
+            ));
+            for node in &cluster.nodes {
+                dot.push_str(&format!(
+                    "    {} [label={}];\n",
+                    dot_string(&node.id),
+                    dot_string(&node_label(node, "\n"))
+                ));
+            }
+            dot.push_str("  }\n");
+        }
+        for (from, to) in &self.edges {
+            dot.push_str(&format!("  {} -> {};\n", dot_string(from), dot_string(to)));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Render the graph as a standalone SVG document
+    ///
+    /// Clusters are laid out as columns from left to right, nodes stacked
+    /// within each column. Edges are drawn as curves from the right side of the
+    /// tracing section to the left side of the traced node, or looping out to
+    /// the right when both are in the same column.
+    pub fn to_svg(&self) -> String {
+        let layout = Layout::new(self);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"Helvetica, Arial, sans-serif\" font-size=\"12\">\n\
+             <defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"7\" markerHeight=\"7\" orient=\"auto-start-reverse\"><path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"#555\"/></marker></defs>\n\
+             <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n",
+            w = layout.width,
+            h = layout.height
+        );
+
+        for (cluster, column) in self.clusters.iter().zip(&layout.columns) {
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"6\" fill=\"#f4f6fa\" stroke=\"#9aa5b8\"/>\n\
+                 <text x=\"{}\" y=\"{}\" font-weight=\"bold\">{}</text>\n",
+                column.x,
+                column.y,
+                column.width,
+                column.height,
+                column.x + CLUSTER_PADDING,
+                column.y + CLUSTER_HEADER - 6.0,
+                escape_xml(&cluster.name)
+            ));
+            let nodes = cluster
+                .nodes
+                .iter()
+                .filter_map(|node| Some((node, &layout.nodes.get(node.id.as_str())?.0)));
+            for (node, rect) in nodes {
+                svg.push_str(&svg_node(node, rect));
+            }
+        }
+
+        for (from, to) in &self.edges {
+            if let Some(path) = layout.edge_path(from, to) {
+                svg.push_str(&format!(
+                    "<path d=\"{path}\" fill=\"none\" stroke=\"#555\" marker-end=\"url(#arrow)\"/>\n"
+                ));
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Position and size of a cluster column or node box
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Column layout of a trace graph
+struct Layout<'a> {
+    columns: Vec<Rect>,
+    nodes: BTreeMap<&'a str, (Rect, usize)>,
+    width: f64,
+    height: f64,
+}
+
+impl<'a> Layout<'a> {
+    fn new(graph: &'a TraceGraph) -> Self {
+        let mut columns = Vec::new();
+        let mut nodes = BTreeMap::new();
+        let mut x = 10.0;
+
+        for (column_index, cluster) in graph.clusters.iter().enumerate() {
+            let longest = cluster
+                .nodes
+                .iter()
+                .map(|node| node_label(node, " - ").chars().count())
+                .chain(std::iter::once(cluster.name.chars().count()))
+                .max()
+                .unwrap_or(0);
+            let node_width = (longest as f64) * CHAR_WIDTH + 16.0;
+
+            for (row, node) in cluster.nodes.iter().enumerate() {
+                let rect = Rect {
+                    x: x + CLUSTER_PADDING,
+                    y: 10.0 + CLUSTER_HEADER + (row as f64) * (NODE_HEIGHT + NODE_GAP),
+                    width: node_width,
+                    height: NODE_HEIGHT,
+                };
+                nodes.insert(node.id.as_str(), (rect, column_index));
+            }
+
+            let rows = cluster.nodes.len() as f64;
+            let column = Rect {
+                x,
+                y: 10.0,
+                width: node_width + 2.0 * CLUSTER_PADDING,
+                height: CLUSTER_HEADER + rows * (NODE_HEIGHT + NODE_GAP) - NODE_GAP
+                    + CLUSTER_PADDING,
+            };
+            x += column.width + COLUMN_GAP;
+            columns.push(column);
+        }
+
+        let height = columns
+            .iter()
+            .map(|column| column.y + column.height)
+            .fold(0.0, f64::max)
+            + 10.0;
+        Self {
+            columns,
+            nodes,
+            width: x - COLUMN_GAP + 10.0,
+            height,
+        }
+    }
+
+    /// SVG path data for an edge, or None if either node is not laid out
+    fn edge_path(&self, from: &str, to: &str) -> Option<String> {
+        let (source, source_column) = self.nodes.get(from)?;
+        let (target, target_column) = self.nodes.get(to)?;
+        let source_y = source.y + NODE_HEIGHT / 2.0;
+        let target_y = target.y + NODE_HEIGHT / 2.0;
+        let source_x = source.x + source.width;
+
+        if target_column > source_column {
+            let target_x = target.x;
+            let bend = (target_x - source_x) / 2.0;
+            Some(format!(
+                "M {source_x} {source_y} C {} {source_y}, {} {target_y}, {target_x} {target_y}",
+                source_x + bend,
+                target_x - bend
+            ))
+        } else {
+            // Same or earlier column: loop out to the right of the source column
+            let target_x = target.x + target.width;
+            let loop_x = source_x.max(target_x) + COLUMN_GAP / 3.0;
+            Some(format!(
+                "M {source_x} {source_y} C {loop_x} {source_y}, {loop_x} {target_y}, {target_x} {target_y}"
+            ))
+        }
+    }
+}
+
+/// SVG group for one node box, with the untruncated title as a tooltip
+fn svg_node(node: &GraphNode, rect: &Rect) -> String {
+    let tooltip = if node.title.is_empty() {
+        node.id.clone()
+    } else {
+        format!("{} - {}", node.id, node.title)
+    };
+    format!(
+        "<g><title>{}</title><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"4\" fill=\"white\" stroke=\"#2b579a\"/>\
+         <text x=\"{}\" y=\"{}\">{}</text></g>\n",
+        escape_xml(&tooltip),
+        rect.x,
+        rect.y,
+        rect.width,
+        rect.height,
+        rect.x + 8.0,
+        rect.y + rect.height / 2.0 + 4.0,
+        escape_xml(&node_label(node, " - "))
+    )
+}
+
+/// Text shown for a node: its ID, followed by its title if it has one
+fn node_label(node: &GraphNode, separator: &str) -> String {
+    if node.title.is_empty() || node.title == node.id {
+        return node.id.clone();
+    }
+    let title: String = if node.title.chars().count() > MAX_TITLE_CHARS {
+        let truncated: String = node.title.chars().take(MAX_TITLE_CHARS - 1).collect();
+        format!("{}…", truncated.trim_end())
+    } else {
+        node.title.clone()
+    };
+    format!("{}{}{}", node.id, separator, title)
+}
+
+/// Quote a string for DOT
+fn dot_string(s: &str) -> String {
+    format!(
+        "\"{}\"",
+        s.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_model::TraceTarget;
+    use std::path::PathBuf;
+
+    fn trace(id: &str, number: &str, traced: &[&str]) -> SectionTrace {
+        SectionTrace {
+            section_id: id.to_string(),
+            section_number: SectionNumber::parse(number).unwrap(),
+            heading: format!("{id} heading"),
+            source_file: PathBuf::from("src/01_design.md"),
+            line_number: 1,
+            status: None,
+            verification_method: None,
+            traced_ids: traced.iter().map(|s| s.to_string()).collect(),
+            attributes: BTreeMap::new(),
+        }
+    }
+
+    fn sample() -> (Vec<SectionTrace>, TraceTargets) {
+        let traces = vec![
+            trace("SDD-1", "01", &["SRS-1", "SDD-2"]),
+            trace("SDD-2", "02.01", &["CAT-7", "TBD-1"]),
+            trace("SDD-3", "03", &["SRS-1"]),
+        ];
+        let target = |document_id: &str, title: &str| TraceTarget {
+            title: title.to_string(),
+            document_id: document_id.to_string(),
+            ..TraceTarget::default()
+        };
+        let targets = TraceTargets::from([
+            ("SDD-2".to_string(), target("SDD", "SDD-2 heading")),
+            ("SRS-1".to_string(), target("SRS", "Power")),
+            ("CAT-7".to_string(), target("", "Catalog item")),
+        ]);
+        (traces, targets)
+    }
+
+    #[test]
+    fn test_build_clusters() {
+        let (traces, targets) = sample();
+        let graph = TraceGraph::build(&TraceGraphSpec::default(), &traces, &targets, "SDD");
+
+        let clusters: Vec<(&str, Vec<&str>)> = graph
+            .clusters
+            .iter()
+            .map(|c| {
+                (
+                    c.name.as_str(),
+                    c.nodes.iter().map(|n| n.id.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            clusters,
+            vec![
+                ("SDD", vec!["SDD-1", "SDD-2", "SDD-3"]),
+                ("SRS", vec!["SRS-1"]),
+                ("Catalog", vec!["CAT-7"]),
+                ("Unresolved", vec!["TBD-1"]),
+            ]
+        );
+        assert_eq!(graph.edges.len(), 5);
+    }
+
+    #[test]
+    fn test_chapter_subset() {
+        let (traces, targets) = sample();
+        let spec = TraceGraphSpec {
+            chapter: Some("2".to_string()),
+            ..TraceGraphSpec::default()
+        };
+        let graph = TraceGraph::build(&spec, &traces, &targets, "SDD");
+        assert_eq!(graph.clusters[0].nodes.len(), 1);
+        assert_eq!(graph.clusters[0].nodes[0].id, "SDD-2");
+        assert_eq!(
+            graph.edges,
+            vec![
+                ("SDD-2".to_string(), "CAT-7".to_string()),
+                ("SDD-2".to_string(), "TBD-1".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_dot_and_svg() {
+        let (traces, targets) = sample();
+        let graph = TraceGraph::build(&TraceGraphSpec::default(), &traces, &targets, "SDD");
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph traceability {"));
+        assert!(dot.contains(
+            "subgraph cluster_1 {\n    label=\"SRS\";\n    \"SRS-1\" [label=\"SRS-1\\nPower\"];"
+        ));
+        assert!(dot.contains("  \"SDD-1\" -> \"SRS-1\";\n"));
+
+        let svg = graph.to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert_eq!(svg.matches("marker-end=\"url(#arrow)\"").count(), 5);
+        assert!(svg.contains(">SRS-1 - Power</text>"));
+        assert!(roxmltree::Document::parse(&svg).is_ok());
+    }
+}
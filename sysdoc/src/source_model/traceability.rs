@@ -0,0 +1,638 @@
+//! Configurable traceability tables
+//!
+//! This module defines the per-section traceability records collected from
+//! section metadata, and the `[[trace_table]]` specification that sysdoc blocks
+//! use to request multi-column trace matrices built from those records.
+
+use super::attributes::AttributeValue;
+use super::blocks::MarkdownBlock;
+use super::section_number::SectionNumber;
+use super::text_run::TextRun;
+use super::types::Alignment;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Traceability data for a single section that declares a `section_id`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionTrace {
+    /// The section's unique identifier
+    pub section_id: String,
+    /// Calculated section number of the section
+    pub section_number: SectionNumber,
+    /// Heading text of the section
+    pub heading: String,
+    /// Source file path (relative to document root)
+    pub source_file: PathBuf,
+    /// Line number of the section heading (1-indexed)
+    pub line_number: usize,
+    /// Optional status from the section metadata
+    pub status: Option<String>,
+    /// Optional verification method from the section metadata
+    pub verification_method: Option<String>,
+    /// IDs this section traces to
+    pub traced_ids: Vec<String>,
+    /// Custom attributes from the section metadata
+    pub attributes: BTreeMap<String, AttributeValue>,
+}
+
+/// Details of a known trace target (a local section, upstream section, or catalog entry)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceTarget {
+    /// Title of the target (section heading or catalog title)
+    pub title: String,
+    /// Text of the target (catalog requirement text; empty for sections)
+    pub text: String,
+    /// `document_id` of the document defining the target (empty for catalog entries)
+    pub document_id: String,
+    /// Section number of the target within its document (None for catalog entries)
+    pub section_number: Option<SectionNumber>,
+}
+
+/// All known trace targets keyed by ID
+pub type TraceTargets = BTreeMap<String, TraceTarget>;
+
+/// A column that can appear in a generated trace table
+///
+/// Columns are named in snake case (e.g., `"section_id"`); custom attributes of
+/// the tracing section are named `"attributes.<name>"`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum TraceColumn {
+    /// The tracing section's `section_id`
+    SectionId,
+    /// The tracing section's calculated section number
+    SectionNumber,
+    /// The tracing section's heading text
+    Heading,
+    /// The source file the tracing section was parsed from
+    SourceFile,
+    /// The `status` field of the tracing section's metadata
+    Status,
+    /// The `verification_method` field of the tracing section's metadata
+    VerificationMethod,
+    /// The traced (target) ID
+    TracedId,
+    /// The title of the traced ID, if it resolves to a known section or catalog entry
+    TracedTitle,
+    /// The requirement text of the traced ID, if it resolves to a catalog entry
+    TracedText,
+    /// The `document_id` of the document defining the traced ID
+    TracedDocument,
+    /// The section number of the traced ID within its document
+    TracedSectionNumber,
+    /// A custom attribute of the tracing section
+    Attribute(String),
+}
+
+impl TryFrom<String> for TraceColumn {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        let column = match name.as_str() {
+            "section_id" => Self::SectionId,
+            "section_number" => Self::SectionNumber,
+            "heading" => Self::Heading,
+            "source_file" => Self::SourceFile,
+            "status" => Self::Status,
+            "verification_method" => Self::VerificationMethod,
+            "traced_id" => Self::TracedId,
+            "traced_title" => Self::TracedTitle,
+            "traced_text" => Self::TracedText,
+            "traced_document" => Self::TracedDocument,
+            "traced_section_number" => Self::TracedSectionNumber,
+            _ => match name.strip_prefix("attributes.") {
+                Some(attribute) if !attribute.is_empty() => Self::Attribute(attribute.to_string()),
+                _ => return Err(format!(
+                    "unknown trace table column '{name}' (custom attributes are named 'attributes.<name>')"
+                )),
+            },
+        };
+        Ok(column)
+    }
+}
+
+impl TraceColumn {
+    /// Default header text used when a trace table does not specify `headers`
+    pub fn default_header(&self) -> &str {
+        match self {
+            Self::SectionId => "Section ID",
+            Self::SectionNumber => "Section",
+            Self::Heading => "Title",
+            Self::SourceFile => "Source File",
+            Self::Status => "Status",
+            Self::VerificationMethod => "Verification Method",
+            Self::TracedId => "Traced ID",
+            Self::TracedTitle => "Traced Title",
+            Self::TracedText => "Traced Text",
+            Self::TracedDocument => "Traced Document",
+            Self::TracedSectionNumber => "Traced Section",
+            Self::Attribute(name) => name,
+        }
+    }
+}
+
+/// Raw `[[trace_table]]` content before cross-field validation
+#[derive(Debug, Deserialize)]
+struct RawTraceTableSpec {
+    columns: Vec<TraceColumn>,
+    #[serde(default)]
+    headers: Option<Vec<String>>,
+    #[serde(default)]
+    group_by: Option<TraceColumn>,
+    #[serde(default)]
+    sort_by: Vec<TraceColumn>,
+    #[serde(default)]
+    section_id_prefix: Option<String>,
+    #[serde(default)]
+    traced_id_prefix: Option<String>,
+}
+
+/// Specification for a multi-column trace table requested from a sysdoc block
+///
+/// ```toml
+/// [[trace_table]]
+/// columns = ["traced_id", "traced_title", "section_id", "section_number"]
+/// headers = ["Requirement", "Title", "Design Element", "Section"]
+/// group_by = "traced_id"
+/// traced_id_prefix = "SRS-"
+/// ```
+///
+/// Each row of the underlying relation is one (section, traced ID) link. When
+/// `group_by` is set, links sharing the same value in that column are merged
+/// into a single row and the other columns list their distinct values.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawTraceTableSpec")]
+pub struct TraceTableSpec {
+    /// Columns to include, in display order
+    pub columns: Vec<TraceColumn>,
+    /// Header text for each column (same length as `columns`)
+    pub headers: Vec<String>,
+    /// Optional column whose equal values are merged into a single row
+    pub group_by: Option<TraceColumn>,
+    /// Columns to sort rows by (defaults to section ID, then traced ID)
+    pub sort_by: Vec<TraceColumn>,
+    /// Only include sections whose `section_id` starts with this prefix
+    pub section_id_prefix: Option<String>,
+    /// Only include traced IDs that start with this prefix
+    pub traced_id_prefix: Option<String>,
+}
+
+impl TryFrom<RawTraceTableSpec> for TraceTableSpec {
+    type Error = String;
+
+    fn try_from(raw: RawTraceTableSpec) -> Result<Self, Self::Error> {
+        if raw.columns.is_empty() {
+            return Err("trace_table requires at least one column".to_string());
+        }
+
+        let headers = match raw.headers {
+            Some(headers) if headers.len() != raw.columns.len() => {
+                return Err(format!(
+                    "trace_table has {} columns but {} headers",
+                    raw.columns.len(),
+                    headers.len()
+                ));
+            }
+            Some(headers) => headers,
+            None => raw
+                .columns
+                .iter()
+                .map(|column| column.default_header().to_string())
+                .collect(),
+        };
+
+        Ok(Self {
+            columns: raw.columns,
+            headers,
+            group_by: raw.group_by,
+            sort_by: raw.sort_by,
+            section_id_prefix: raw.section_id_prefix,
+            traced_id_prefix: raw.traced_id_prefix,
+        })
+    }
+}
+
+/// A single (section, traced ID) link, the unit row of a trace table
+struct TraceLink<'a> {
+    /// The tracing section
+    section: &'a SectionTrace,
+    /// The traced ID (None for sections that trace to nothing)
+    traced_id: Option<&'a str>,
+}
+
+impl TraceLink<'_> {
+    /// Get the display value of a column for this link
+    fn value(&self, column: &TraceColumn, targets: &TraceTargets) -> String {
+        match column {
+            TraceColumn::SectionId => self.section.section_id.clone(),
+            TraceColumn::SectionNumber => self.section.section_number.to_string(),
+            TraceColumn::Heading => self.section.heading.clone(),
+            TraceColumn::SourceFile => self.section.source_file.display().to_string(),
+            TraceColumn::Status => self.section.status.clone().unwrap_or_default(),
+            TraceColumn::VerificationMethod => {
+                self.section.verification_method.clone().unwrap_or_default()
+            }
+            TraceColumn::TracedId => self.traced_id.unwrap_or_default().to_string(),
+            TraceColumn::TracedTitle => self
+                .target(targets)
+                .map(|target| target.title.clone())
+                .unwrap_or_default(),
+            TraceColumn::TracedText => self
+                .target(targets)
+                .map(|target| target.text.clone())
+                .unwrap_or_default(),
+            TraceColumn::TracedDocument => self
+                .target(targets)
+                .map(|target| target.document_id.clone())
+                .unwrap_or_default(),
+            TraceColumn::TracedSectionNumber => self
+                .target(targets)
+                .and_then(|target| target.section_number.as_ref())
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            TraceColumn::Attribute(name) => self
+                .section
+                .attributes
+                .get(name)
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Look up the details of the traced ID, if it is a known target
+    fn target<'t>(&self, targets: &'t TraceTargets) -> Option<&'t TraceTarget> {
+        self.traced_id.and_then(|id| targets.get(id))
+    }
+
+    /// Compare two links by a single column
+    fn cmp_by(
+        &self,
+        other: &Self,
+        column: &TraceColumn,
+        targets: &TraceTargets,
+    ) -> std::cmp::Ordering {
+        match column {
+            // Section numbers compare numerically (1.10 after 1.9)
+            TraceColumn::SectionNumber => self
+                .section
+                .section_number
+                .cmp(&other.section.section_number),
+            TraceColumn::TracedSectionNumber => {
+                let number = |link: &Self| {
+                    link.target(targets)
+                        .and_then(|target| target.section_number.clone())
+                };
+                number(self).cmp(&number(other))
+            }
+            _ => self
+                .value(column, targets)
+                .cmp(&other.value(column, targets)),
+        }
+    }
+}
+
+impl TraceTableSpec {
+    /// Build the trace table block from the collected section traceability
+    ///
+    /// # Parameters
+    /// * `traces` - Traceability records for every section with a `section_id`
+    /// * `targets` - Known trace targets, used for `traced_title` and `traced_text`
+    ///
+    /// # Returns
+    /// * `MarkdownBlock::InlineTable` - The generated table
+    pub fn build_table(&self, traces: &[SectionTrace], targets: &TraceTargets) -> MarkdownBlock {
+        let mut links = self.collect_links(traces);
+
+        let sort_columns = if self.sort_by.is_empty() {
+            vec![TraceColumn::SectionId, TraceColumn::TracedId]
+        } else {
+            self.sort_by.clone()
+        };
+        links.sort_by(|a, b| {
+            sort_columns
+                .iter()
+                .map(|column| a.cmp_by(b, column, targets))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let rows = match &self.group_by {
+            Some(group_column) => self.grouped_rows(&links, group_column, targets),
+            None => links
+                .iter()
+                .map(|link| {
+                    self.columns
+                        .iter()
+                        .map(|column| link.value(column, targets))
+                        .collect()
+                })
+                .collect(),
+        };
+
+        let headers = self
+            .headers
+            .iter()
+            .map(|header| vec![TextRun::new(header.clone())])
+            .collect();
+
+        let rows = rows
+            .into_iter()
+            .map(|row: Vec<String>| {
+                row.into_iter()
+                    .map(|cell| vec![TextRun::new(cell)])
+                    .collect()
+            })
+            .collect();
+
+        MarkdownBlock::InlineTable {
+            alignments: vec![Alignment::None; self.columns.len()],
+            headers,
+            rows,
+        }
+    }
+
+    /// Expand section traces into individual links, applying prefix filters
+    fn collect_links<'a>(&self, traces: &'a [SectionTrace]) -> Vec<TraceLink<'a>> {
+        let section_matches = |trace: &SectionTrace| {
+            self.section_id_prefix
+                .as_ref()
+                .is_none_or(|prefix| trace.section_id.starts_with(prefix.as_str()))
+        };
+
+        let mut links = Vec::new();
+        for trace in traces.iter().filter(|trace| section_matches(trace)) {
+            if let Some(prefix) = &self.traced_id_prefix {
+                links.extend(
+                    trace
+                        .traced_ids
+                        .iter()
+                        .filter(|id| id.starts_with(prefix.as_str()))
+                        .map(|id| TraceLink {
+                            section: trace,
+                            traced_id: Some(id.as_str()),
+                        }),
+                );
+            } else if trace.traced_ids.is_empty() {
+                links.push(TraceLink {
+                    section: trace,
+                    traced_id: None,
+                });
+            } else {
+                links.extend(trace.traced_ids.iter().map(|id| TraceLink {
+                    section: trace,
+                    traced_id: Some(id.as_str()),
+                }));
+            }
+        }
+        links
+    }
+
+    /// Merge links that share a value in the group column into single rows
+    ///
+    /// Groups keep the order in which they first appear in the sorted links.
+    /// Links with an empty group value are dropped.
+    fn grouped_rows(
+        &self,
+        links: &[TraceLink<'_>],
+        group_column: &TraceColumn,
+        targets: &TraceTargets,
+    ) -> Vec<Vec<String>> {
+        group_by_key(links, |link| link.value(group_column, targets))
+            .iter()
+            .map(|members| {
+                self.columns
+                    .iter()
+                    .map(|column| {
+                        join_distinct(members.iter().map(|link| link.value(column, targets)))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Group items that share a key, keeping the order in which each key first appears
+///
+/// # Parameters
+/// * `items` - Items in display order
+/// * `key` - Group key of an item; items with an empty key are dropped
+///
+/// # Returns
+/// * The members of each group, in group order
+pub(super) fn group_by_key<T>(
+    items: impl IntoIterator<Item = T>,
+    key: impl Fn(&T) -> String,
+) -> Vec<Vec<T>> {
+    let mut group_order: Vec<String> = Vec::new();
+    let mut groups: BTreeMap<String, Vec<T>> = BTreeMap::new();
+    for item in items {
+        let item_key = key(&item);
+        if item_key.is_empty() {
+            continue;
+        }
+        if !groups.contains_key(&item_key) {
+            group_order.push(item_key.clone());
+        }
+        groups.entry(item_key).or_default().push(item);
+    }
+    group_order
+        .iter()
+        .filter_map(|item_key| groups.remove(item_key))
+        .collect()
+}
+
+/// Join the distinct, non-empty values of a grouped column with ", "
+pub(super) fn join_distinct(values: impl IntoIterator<Item = String>) -> String {
+    let mut distinct: Vec<String> = Vec::new();
+    for value in values {
+        if !value.is_empty() && !distinct.contains(&value) {
+            distinct.push(value);
+        }
+    }
+    distinct.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace(id: &str, number: &str, heading: &str, traced: &[&str]) -> SectionTrace {
+        SectionTrace {
+            section_id: id.to_string(),
+            section_number: SectionNumber::parse(number).unwrap(),
+            heading: heading.to_string(),
+            source_file: PathBuf::from("src/test.md"),
+            line_number: 1,
+            status: None,
+            verification_method: None,
+            traced_ids: traced.iter().map(|s| s.to_string()).collect(),
+            attributes: BTreeMap::new(),
+        }
+    }
+
+    fn cell_texts(block: &MarkdownBlock) -> (Vec<String>, Vec<Vec<String>>) {
+        let MarkdownBlock::InlineTable { headers, rows, .. } = block else {
+            panic!("Expected InlineTable");
+        };
+        let headers = headers.iter().map(|cell| cell[0].text.clone()).collect();
+        let rows = rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell[0].text.clone()).collect())
+            .collect();
+        (headers, rows)
+    }
+
+    #[test]
+    fn test_parse_spec_with_default_headers() {
+        let spec: TraceTableSpec =
+            toml::from_str(r#"columns = ["section_id", "traced_id"]"#).unwrap();
+        assert_eq!(spec.headers, vec!["Section ID", "Traced ID"]);
+        assert_eq!(spec.group_by, None);
+    }
+
+    #[test]
+    fn test_parse_spec_header_count_mismatch() {
+        let result: Result<TraceTableSpec, _> = toml::from_str(
+            r#"columns = ["section_id", "traced_id"]
+headers = ["Only One"]"#,
+        );
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("2 columns but 1 headers"), "{}", error);
+    }
+
+    #[test]
+    fn test_requirement_to_design_matrix() {
+        let traces = vec![
+            trace("SDD-001", "03.01", "Parser", &["SRS-002", "SRS-001"]),
+            trace("SDD-002", "03.02", "Exporter", &["SRS-001"]),
+            trace("SRS-001", "01.01", "Parse input", &[]),
+            trace("SRS-002", "01.02", "Report errors", &[]),
+        ];
+        let targets: TraceTargets = traces
+            .iter()
+            .map(|t| {
+                let target = TraceTarget {
+                    title: t.heading.clone(),
+                    ..TraceTarget::default()
+                };
+                (t.section_id.clone(), target)
+            })
+            .collect();
+
+        let spec: TraceTableSpec = toml::from_str(
+            r#"
+columns = ["traced_id", "traced_title", "section_id", "section_number"]
+headers = ["Requirement", "Title", "Design Element", "Section"]
+group_by = "traced_id"
+sort_by = ["traced_id", "section_number"]
+section_id_prefix = "SDD-"
+"#,
+        )
+        .unwrap();
+
+        let (headers, rows) = cell_texts(&spec.build_table(&traces, &targets));
+        assert_eq!(
+            headers,
+            vec!["Requirement", "Title", "Design Element", "Section"]
+        );
+        assert_eq!(
+            rows,
+            vec![
+                vec!["SRS-001", "Parse input", "SDD-001, SDD-002", "3.1, 3.2"],
+                vec!["SRS-002", "Report errors", "SDD-001", "3.1"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ungrouped_rows_include_untraced_sections() {
+        let traces = vec![
+            trace("SDD-002", "03.02", "Exporter", &["SRS-001"]),
+            trace("SDD-001", "03.01", "Parser", &[]),
+        ];
+        let spec: TraceTableSpec =
+            toml::from_str(r#"columns = ["section_id", "heading", "traced_id"]"#).unwrap();
+
+        let (_, rows) = cell_texts(&spec.build_table(&traces, &TraceTargets::new()));
+        assert_eq!(
+            rows,
+            vec![
+                vec!["SDD-001", "Parser", ""],
+                vec!["SDD-002", "Exporter", "SRS-001"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_attribute_column() {
+        let mut parser = trace("SDD-001", "03.01", "Parser", &[]);
+        parser.attributes.insert(
+            "owner".to_string(),
+            AttributeValue::Text("J. Smith".to_string()),
+        );
+        let traces = vec![parser, trace("SDD-002", "03.02", "Exporter", &[])];
+        let spec: TraceTableSpec =
+            toml::from_str(r#"columns = ["section_id", "attributes.owner"]"#).unwrap();
+
+        let (headers, rows) = cell_texts(&spec.build_table(&traces, &TraceTargets::new()));
+        assert_eq!(headers, vec!["Section ID", "owner"]);
+        assert_eq!(rows, vec![vec!["SDD-001", "J. Smith"], vec!["SDD-002", ""]]);
+
+        let result: Result<TraceTableSpec, _> = toml::from_str(r#"columns = ["owner"]"#);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("attributes.<name>"));
+    }
+
+    #[test]
+    fn test_traced_id_prefix_filter() {
+        let traces = vec![trace("SDD-001", "03.01", "Parser", &["SRS-001", "ICD-004"])];
+        let spec: TraceTableSpec = toml::from_str(
+            r#"columns = ["section_id", "traced_id"]
+traced_id_prefix = "ICD-""#,
+        )
+        .unwrap();
+
+        let (_, rows) = cell_texts(&spec.build_table(&traces, &TraceTargets::new()));
+        assert_eq!(rows, vec![vec!["SDD-001", "ICD-004"]]);
+    }
+
+    #[test]
+    fn test_upstream_document_columns() {
+        let traces = vec![
+            trace("SDD-001", "03.01", "Parser", &["SRS-010"]),
+            trace("SDD-002", "03.02", "Exporter", &["SRS-002"]),
+        ];
+        let targets: TraceTargets = [("SRS-010", "3.10"), ("SRS-002", "3.2")]
+            .into_iter()
+            .map(|(id, number)| {
+                let target = TraceTarget {
+                    document_id: "SRS-2024-001".to_string(),
+                    section_number: SectionNumber::parse(number),
+                    ..TraceTarget::default()
+                };
+                (id.to_string(), target)
+            })
+            .collect();
+        let spec: TraceTableSpec = toml::from_str(
+            r#"columns = ["traced_id", "traced_document", "traced_section_number"]
+sort_by = ["traced_section_number"]"#,
+        )
+        .unwrap();
+
+        let (headers, rows) = cell_texts(&spec.build_table(&traces, &targets));
+        assert_eq!(
+            headers,
+            vec!["Traced ID", "Traced Document", "Traced Section"]
+        );
+        assert_eq!(
+            rows,
+            vec![
+                vec!["SRS-002", "SRS-2024-001", "3.2"],
+                vec!["SRS-010", "SRS-2024-001", "3.10"],
+            ]
+        );
+    }
+}
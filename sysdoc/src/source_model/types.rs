@@ -19,3 +19,48 @@ impl From<pulldown_cmark::Alignment> for Alignment {
         }
     }
 }
+
+/// Location of an element in its markdown source file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceSpan {
+    /// Byte range of the element in the file
+    pub byte_range: std::ops::Range<usize>,
+    /// Line of the element's first byte (1-indexed)
+    pub line: usize,
+    /// Column of the element's first byte (1-indexed, in characters)
+    pub column: usize,
+}
+
+impl SourceSpan {
+    /// Locate a byte range in a source file
+    ///
+    /// # Parameters
+    /// * `source` - Content of the source file
+    /// * `byte_range` - Byte range of the element in `source`
+    pub fn new(source: &str, byte_range: std::ops::Range<usize>) -> Self {
+        let before = &source[..byte_range.start.min(source.len())];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            byte_range,
+        }
+    }
+
+    /// Locate the first occurrence of `text` within this span
+    ///
+    /// # Returns
+    /// * The span of `text`, or a copy of this span if `text` does not occur in it
+    pub fn find(&self, source: &str, text: &str) -> Self {
+        source
+            .get(self.byte_range.clone())
+            .and_then(|spanned| spanned.find(text))
+            .map_or_else(
+                || self.clone(),
+                |offset| {
+                    let start = self.byte_range.start + offset;
+                    Self::new(source, start..start + text.len())
+                },
+            )
+    }
+}
@@ -0,0 +1,89 @@
+//! Upstream sysdoc projects
+//!
+//! An upstream is a sibling sysdoc project (for example the SRS that an SDD
+//! traces to) declared with `[[upstream]]` in sysdoc.toml. Its sources are
+//! parsed but not validated, so that `traced_ids` and links in the downstream
+//! document can resolve against the upstream sections.
+
+use super::markdown_source::{MarkdownSection, MarkdownSource};
+use crate::document_config::DocumentConfig;
+use crate::text::slug;
+use std::path::{Path, PathBuf};
+
+/// A parsed upstream sysdoc project
+#[derive(Debug)]
+pub struct UpstreamDocument {
+    /// Path to the upstream root as declared in sysdoc.toml
+    pub path: PathBuf,
+
+    /// Canonical absolute path of the upstream root
+    pub root: PathBuf,
+
+    /// The upstream project's configuration
+    pub config: DocumentConfig,
+
+    /// Markdown source files of the upstream project (paths relative to `root`)
+    pub markdown_files: Vec<MarkdownSource>,
+}
+
+impl UpstreamDocument {
+    /// Iterate over all sections of the upstream document
+    pub fn sections(&self) -> impl Iterator<Item = &MarkdownSection> {
+        self.markdown_files
+            .iter()
+            .flat_map(|md_file| md_file.sections.iter())
+    }
+
+    /// Iterate over the `section_id`s declared in the upstream document
+    pub fn section_ids(&self) -> impl Iterator<Item = &str> {
+        self.sections()
+            .filter_map(|section| section.metadata.as_ref()?.section_id.as_deref())
+    }
+
+    /// Find the upstream source file at a canonical absolute path
+    ///
+    /// # Returns
+    /// * `Some(&MarkdownSource)` - The path is one of this project's source files
+    /// * `None` - The path is outside this project or not a parsed source file
+    pub fn find_file(&self, canonical_path: &Path) -> Option<&MarkdownSource> {
+        let relative = canonical_path.strip_prefix(&self.root).ok()?;
+        self.markdown_files
+            .iter()
+            .find(|md_file| md_file.path == relative)
+    }
+}
+
+/// Check whether a link anchor names a section
+///
+/// An anchor matches a section if it equals the section's `section_id` or the
+/// slug of its heading (e.g. `#input-parser`).
+pub fn section_has_anchor(section: &MarkdownSection, anchor: &str) -> bool {
+    let id_matches = section
+        .metadata
+        .as_ref()
+        .and_then(|m| m.section_id.as_deref())
+        .is_some_and(|id| id == anchor);
+
+    id_matches || slug(&section.heading_text) == anchor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_model::SectionNumber;
+
+    #[test]
+    fn test_section_has_anchor() {
+        let section = MarkdownSection {
+            heading_level: 2,
+            heading_text: "3.2 Überlast (28V)".to_string(),
+            section_number: SectionNumber::parse("3.2").unwrap(),
+            line_number: 1,
+            source_file: PathBuf::from("src/03_requirements.md"),
+            content: Vec::new(),
+            metadata: None,
+        };
+        assert!(section_has_anchor(&section, "3-2-überlast-28v"));
+        assert!(!section_has_anchor(&section, "32-Überlast-28v"));
+    }
+}
@@ -1,5 +1,7 @@
 //! Validation error types
 
+use crate::diagnostics::Diagnostic;
+use crate::document_config::DiagnosticSeverity;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -7,25 +9,31 @@ use thiserror::Error;
 #[derive(Error, Debug)]
 pub enum ValidationError {
     /// A referenced image file is missing
-    #[error("Missing image '{image_path}' referenced in '{referenced_in}'", image_path = image_path.display(), referenced_in = referenced_in.display())]
+    #[error("Missing image '{image_path}'", image_path = image_path.display())]
     MissingImage {
         referenced_in: PathBuf,
         image_path: PathBuf,
+        line: usize,
+        column: usize,
     },
     /// A referenced table file is missing
-    #[error("Missing table '{table_path}' referenced in '{referenced_in}'", table_path = table_path.display(), referenced_in = referenced_in.display())]
+    #[error("Missing table '{table_path}'", table_path = table_path.display())]
     MissingTable {
         referenced_in: PathBuf,
         table_path: PathBuf,
+        line: usize,
+        column: usize,
     },
     /// A referenced include file is missing
-    #[error("Missing include file '{include_path}' referenced in '{referenced_in}'", include_path = include_path.display(), referenced_in = referenced_in.display())]
+    #[error("Missing include file '{include_path}'", include_path = include_path.display())]
     MissingIncludeFile {
         referenced_in: PathBuf,
         include_path: PathBuf,
+        line: usize,
+        column: usize,
     },
     /// Duplicate section_id found in metadata
-    #[error("Duplicate section_id '{section_id}':\n  First occurrence:  {first_location}:{first_line}\n  Second occurrence: {second_location}:{second_line}", first_location = first_location.display(), second_location = second_location.display())]
+    #[error("Duplicate section_id '{section_id}'")]
     DuplicateSectionId {
         section_id: String,
         first_location: PathBuf,
@@ -34,11 +42,122 @@ pub enum ValidationError {
         second_line: usize,
     },
 
+    /// A traced ID does not exist in any catalog or as a local section_id
+    #[error("Unknown traced ID '{traced_id}'")]
+    UnknownTracedId {
+        traced_id: String,
+        referenced_in: PathBuf,
+        line: usize,
+        suggestion: Option<String>,
+    },
+
+    /// A source code annotation names a section_id that does not exist in the document
+    #[error("Unknown section ID '{section_id}' referenced from code")]
+    UnknownCodeReference {
+        section_id: String,
+        referenced_in: PathBuf,
+        line: usize,
+    },
+
+    /// A section_id does not match the document's ID scheme
+    #[error("Section ID '{section_id}' does not match the ID scheme '{pattern}'")]
+    SectionIdPattern {
+        section_id: String,
+        pattern: String,
+        referenced_in: PathBuf,
+        line: usize,
+    },
+
+    /// A section_id was retired in the ID registry and is being reused
+    #[error("Section ID '{section_id}' was retired and must not be reused (see id-registry.toml)")]
+    RetiredSectionId {
+        section_id: String,
+        referenced_in: PathBuf,
+        line: usize,
+    },
+
+    /// A declared attribute has a value of the wrong type or outside its allowed values
+    #[error("Attribute '{attribute}' of section '{heading}': {problem}")]
+    InvalidAttribute {
+        attribute: String,
+        heading: String,
+        problem: String,
+        referenced_in: PathBuf,
+        line: usize,
+    },
+
+    /// A section does not set an attribute that sysdoc.toml requires at its depth
+    #[error("Section '{section_id}' is missing the required attribute '{attribute}'")]
+    MissingAttribute {
+        attribute: String,
+        section_id: String,
+        referenced_in: PathBuf,
+        line: usize,
+    },
+
+    /// A section required by the document's DID template does not exist
+    #[error("Section {section_number} '{heading}' required by template '{template}' is missing")]
+    MissingTemplateSection {
+        template: String,
+        section_number: String,
+        heading: String,
+    },
+
+    /// A templated section's heading differs from the template
+    #[error("Section '{found}' is titled '{expected}' in template '{template}'")]
+    RenamedTemplateSection {
+        template: String,
+        expected: String,
+        found: String,
+        referenced_in: PathBuf,
+        line: usize,
+    },
+
+    /// A templated section is numbered differently from the template
+    #[error("Section '{heading}' is numbered {found} but its template places it at {expected}")]
+    MovedTemplateSection {
+        heading: String,
+        expected: String,
+        found: String,
+        referenced_in: PathBuf,
+        line: usize,
+    },
+
+    /// A templated section still contains only the template's guidance comment
+    #[error("Section '{heading}' still contains only template guidance")]
+    GuidanceOnlySection {
+        heading: String,
+        referenced_in: PathBuf,
+        line: usize,
+    },
+
+    /// A templated section does not set metadata the template requires
+    #[error(
+        "Section '{heading}' is missing the metadata '{field}' required by template '{template}'"
+    )]
+    MissingTemplateMetadata {
+        template: String,
+        field: String,
+        heading: String,
+        referenced_in: PathBuf,
+        line: usize,
+    },
+
+    /// A TBD/TBR item remains in a release build
+    #[error("Open item '{item}' must be resolved before release")]
+    OpenItem {
+        item: String,
+        referenced_in: PathBuf,
+        line: usize,
+    },
+
     /// A broken internal link was found
-    #[error("Broken link '{link_target}' in '{referenced_in}'", referenced_in = referenced_in.display())]
+    #[error("Broken link '{link_target}'")]
     BrokenLink {
         referenced_in: PathBuf,
         link_target: String,
+        line: usize,
+        column: usize,
     },
 
     /// Multiple validation errors
@@ -46,6 +165,182 @@ pub enum ValidationError {
     Multiple(Vec<ValidationError>),
 }
 
+impl ValidationError {
+    /// Stable diagnostic code of the error, usable in `[diagnostics]` overrides
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MissingImage { .. } => "missing-image",
+            Self::MissingTable { .. } => "missing-table",
+            Self::MissingIncludeFile { .. } => "missing-include",
+            Self::DuplicateSectionId { .. } => "duplicate-section-id",
+            Self::UnknownTracedId { .. } => "unknown-traced-id",
+            Self::UnknownCodeReference { .. } => "unknown-code-reference",
+            Self::SectionIdPattern { .. } => "section-id-pattern",
+            Self::RetiredSectionId { .. } => "retired-section-id",
+            Self::InvalidAttribute { .. } => "invalid-attribute",
+            Self::MissingAttribute { .. } => "missing-attribute",
+            Self::MissingTemplateSection { .. } => "missing-template-section",
+            Self::RenamedTemplateSection { .. } => "renamed-template-section",
+            Self::MovedTemplateSection { .. } => "moved-template-section",
+            Self::GuidanceOnlySection { .. } => "guidance-only-section",
+            Self::MissingTemplateMetadata { .. } => "missing-template-metadata",
+            Self::OpenItem { .. } => "open-item",
+            Self::BrokenLink { .. } => "broken-link",
+            Self::Multiple(_) => "multiple",
+        }
+    }
+
+    /// Convert the error into diagnostics, one per error in `Multiple`
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        match self {
+            Self::Multiple(errors) => errors.iter().flat_map(Self::diagnostics).collect(),
+            _ => vec![self.diagnostic()],
+        }
+    }
+
+    /// Default severity of the error's diagnostic
+    ///
+    /// A section still holding only template guidance is expected while a
+    /// document is being written, so it is a warning (`--release` makes it an error).
+    pub fn severity(&self) -> DiagnosticSeverity {
+        match self {
+            Self::GuidanceOnlySection { .. } => DiagnosticSeverity::Warning,
+            _ => DiagnosticSeverity::Error,
+        }
+    }
+
+    /// Convert a single error into a diagnostic
+    fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::new(self.code(), self.severity(), self.to_string());
+        match self {
+            Self::MissingImage {
+                referenced_in,
+                image_path: path,
+                line,
+                column,
+            }
+            | Self::MissingTable {
+                referenced_in,
+                table_path: path,
+                line,
+                column,
+            }
+            | Self::MissingIncludeFile {
+                referenced_in,
+                include_path: path,
+                line,
+                column,
+            } => diagnostic
+                .at(referenced_in, *line, *column)
+                .highlighting(path.display().to_string()),
+            Self::DuplicateSectionId {
+                section_id,
+                first_location,
+                first_line,
+                second_location,
+                second_line,
+            } => diagnostic
+                .at_line(second_location, *second_line)
+                .highlighting(section_id)
+                .with_suggestion(format!(
+                    "give each section a unique section_id (also used at {}:{})",
+                    first_location.display(),
+                    first_line
+                )),
+            Self::UnknownTracedId {
+                traced_id,
+                referenced_in,
+                line,
+                suggestion,
+            } => {
+                let diagnostic = diagnostic
+                    .at_line(referenced_in, *line)
+                    .highlighting(traced_id);
+                match suggestion {
+                    Some(id) => diagnostic.with_suggestion(format!("did you mean '{}'?", id)),
+                    None => diagnostic,
+                }
+            }
+            Self::UnknownCodeReference {
+                section_id,
+                referenced_in,
+                line,
+            }
+            | Self::SectionIdPattern {
+                section_id,
+                referenced_in,
+                line,
+                ..
+            }
+            | Self::RetiredSectionId {
+                section_id,
+                referenced_in,
+                line,
+            } => diagnostic
+                .at_line(referenced_in, *line)
+                .highlighting(section_id),
+            Self::InvalidAttribute {
+                attribute,
+                referenced_in,
+                line,
+                ..
+            } => diagnostic
+                .at_line(referenced_in, *line)
+                .highlighting(attribute),
+            Self::MissingAttribute {
+                attribute,
+                referenced_in,
+                line,
+                ..
+            } => diagnostic
+                .at_line(referenced_in, *line)
+                .with_suggestion(format!(
+                    "set attributes.{} in the section's sysdoc block",
+                    attribute
+                )),
+            Self::RenamedTemplateSection {
+                expected,
+                found,
+                referenced_in,
+                line,
+                ..
+            } => diagnostic
+                .at_line(referenced_in, *line)
+                .highlighting(found)
+                .with_suggestion(format!("rename the section to '{}'", expected)),
+            Self::MovedTemplateSection {
+                referenced_in,
+                line,
+                ..
+            }
+            | Self::GuidanceOnlySection {
+                referenced_in,
+                line,
+                ..
+            }
+            | Self::MissingTemplateMetadata {
+                referenced_in,
+                line,
+                ..
+            } => diagnostic.at_line(referenced_in, *line),
+            Self::OpenItem {
+                item,
+                referenced_in,
+                line,
+            } => diagnostic.at_line(referenced_in, *line).highlighting(item),
+            Self::BrokenLink {
+                referenced_in,
+                link_target,
+                line,
+                column,
+            } => diagnostic
+                .at(referenced_in, *line, *column)
+                .highlighting(link_target),
+            Self::MissingTemplateSection { .. } | Self::Multiple(_) => diagnostic,
+        }
+    }
+}
+
 /// Helper function to format multiple errors
 fn format_errors(errors: &[ValidationError]) -> String {
     errors
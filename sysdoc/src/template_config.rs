@@ -37,6 +37,9 @@ pub enum FileTemplate {
         guidance: String,
         /// Additional content to appear after the heading
         content: String,
+        /// Section metadata fields the section must set (checked by `sysdoc validate`)
+        #[serde(default)]
+        required_metadata: Vec<String>,
     },
     /// Markdown file with heading and guidance only
     Markdown {
@@ -44,6 +47,9 @@ pub enum FileTemplate {
         heading: String,
         /// Guidance text to be placed in HTML comment
         guidance: String,
+        /// Section metadata fields the section must set (checked by `sysdoc validate`)
+        #[serde(default)]
+        required_metadata: Vec<String>,
     },
     /// Simple file with just content (for non-markdown files like .toml, .gitignore)
     Simple {
@@ -52,6 +58,30 @@ pub enum FileTemplate {
     },
 }
 
+impl FileTemplate {
+    /// Heading of a markdown section file (None for simple files)
+    pub fn heading(&self) -> Option<&str> {
+        match self {
+            FileTemplate::MarkdownWithContent { heading, .. }
+            | FileTemplate::Markdown { heading, .. } => Some(heading),
+            FileTemplate::Simple { .. } => None,
+        }
+    }
+
+    /// Section metadata fields a markdown section file must set
+    pub fn required_metadata(&self) -> &[String] {
+        match self {
+            FileTemplate::MarkdownWithContent {
+                required_metadata, ..
+            }
+            | FileTemplate::Markdown {
+                required_metadata, ..
+            } => required_metadata,
+            FileTemplate::Simple { .. } => &[],
+        }
+    }
+}
+
 impl TemplateConfig {
     /// Load template configuration from a .toml file
     ///
@@ -84,6 +114,7 @@ impl TemplateConfig {
                 heading,
                 guidance,
                 content,
+                ..
             } => {
                 let guidance_comment = if !guidance.is_empty() {
                     format!("<!-- GUIDANCE:\n{}\n-->\n\n", guidance)
@@ -92,7 +123,9 @@ impl TemplateConfig {
                 };
                 format!("{}# {}\n{}", guidance_comment, heading, content)
             }
-            FileTemplate::Markdown { heading, guidance } => {
+            FileTemplate::Markdown {
+                heading, guidance, ..
+            } => {
                 let guidance_comment = if !guidance.is_empty() {
                     format!("<!-- GUIDANCE:\n{}\n-->\n\n", guidance)
                 } else {
@@ -196,10 +229,13 @@ software to which this document applies.
         let template = FileTemplate::Markdown {
             heading: "Test Heading".to_string(),
             guidance: "This is guidance text.\nMultiple lines.".to_string(),
+            required_metadata: Vec::new(),
         };
 
         let content = match template {
-            FileTemplate::Markdown { heading, guidance } => {
+            FileTemplate::Markdown {
+                heading, guidance, ..
+            } => {
                 format!("<!-- GUIDANCE:\n{}\n-->\n\n# {}\n\n", guidance, heading)
             }
             _ => panic!("Wrong variant"),
@@ -0,0 +1,106 @@
+//! Text helpers shared by the importers, exporters and source model
+
+/// Escape special XML characters
+///
+/// # Parameters
+/// * `s` - Text to place in XML content or an attribute value
+///
+/// # Returns
+/// * The text with `&`, `<`, `>`, `"` and `'` replaced by entity references
+pub fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Convert a title to a lowercase, hyphen-separated file name component
+///
+/// # Parameters
+/// * `title` - Section or document title
+///
+/// # Returns
+/// * The words of the title joined by `-`, or `section` if it has none
+pub fn slug(title: &str) -> String {
+    let slug = title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "section".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Edit distance between two strings, if it is at most `max`
+///
+/// Insertions, deletions, substitutions and transpositions of adjacent
+/// characters (`recieve` -> `receive`) each count as one edit.
+///
+/// # Parameters
+/// * `a` - First string
+/// * `b` - Second string
+/// * `max` - Largest distance of interest; the search stops once it is exceeded
+///
+/// # Returns
+/// * `Some(distance)` - The strings are at most `max` edits apart
+/// * `None` - The strings are more than `max` edits apart
+pub fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let mut before_previous: Vec<usize> = Vec::new();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            let transposed = i > 0 && j > 0 && *ca == b[j - 1] && a[i - 1] == *cb;
+            if transposed {
+                current[j + 1] = current[j + 1].min(before_previous[j - 1] + 1);
+            }
+        }
+        if current.iter().min().is_some_and(|&d| d > max) {
+            return None;
+        }
+        before_previous = std::mem::replace(&mut previous, current);
+    }
+    Some(previous[b.len()]).filter(|&d| d <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("teh", "the", 2), Some(1));
+        assert_eq!(edit_distance("recieve", "receive", 2), Some(1));
+        assert_eq!(edit_distance("SRS-REQ-01", "SRS-REQ-001", 2), Some(1));
+        assert_eq!(edit_distance("kitten", "sitting", 3), Some(3));
+        assert_eq!(edit_distance("kitten", "sitting", 2), None);
+        assert_eq!(edit_distance("a", "abcd", 2), None);
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("System Context"), "system-context");
+        assert_eq!(slug("I/O & Timing (v2)"), "i-o-timing-v2");
+        assert_eq!(slug("--"), "section");
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            escape_xml(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &apos;Jerry&apos;&lt;/a&gt;"
+        );
+    }
+}
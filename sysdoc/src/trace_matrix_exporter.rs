@@ -0,0 +1,460 @@
+//! Traceability matrix export to CSV, XLSX and JSON
+//!
+//! This module exports the full many-to-many trace relation of a parsed source
+//! model as a spreadsheet, independent of any trace tables embedded in the
+//! document itself. The JSON export lists every identified section with its
+//! metadata and custom attributes, and the links it traces to.
+//!
+//! # XLSX Approach
+//! An .xlsx file is a ZIP archive of SpreadsheetML parts. This exporter writes
+//! the minimal set of parts by hand: a workbook with a "Forward" sheet (source
+//! section → traced ID) and a "Reverse" sheet (traced ID → source section),
+//! each with a bold frozen header row and an autofilter over the data.
+
+use crate::source_model::{AttributeValue, SectionNumber, SourceModel};
+use crate::text::escape_xml;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+use thiserror::Error;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Trace matrix export errors
+#[derive(Error, Debug)]
+pub enum TraceMatrixError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("ZIP error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// One (source section, traced ID) link of the trace relation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceMatrixRow {
+    /// `section_id` of the tracing section
+    pub source_id: String,
+    /// Section number of the tracing section
+    pub source_section_number: SectionNumber,
+    /// Heading of the tracing section
+    pub source_title: String,
+    /// The traced ID
+    pub target_id: String,
+    /// `document_id` of the document defining the traced ID (empty if unknown or a catalog entry)
+    pub target_document: String,
+    /// Section number of the traced ID within its document (empty if not a section)
+    pub target_section_number: String,
+    /// Title of the traced ID (empty if unknown)
+    pub target_title: String,
+}
+
+/// Column headers of the forward direction (source → target)
+const FORWARD_HEADERS: [&str; 7] = [
+    "Source ID",
+    "Source Section",
+    "Source Title",
+    "Target ID",
+    "Target Document",
+    "Target Section",
+    "Target Title",
+];
+
+/// Column headers of the reverse direction (target → source)
+const REVERSE_HEADERS: [&str; 7] = [
+    "Target ID",
+    "Target Document",
+    "Target Section",
+    "Target Title",
+    "Source ID",
+    "Source Section",
+    "Source Title",
+];
+
+impl TraceMatrixRow {
+    /// Cell values in forward column order
+    fn forward_cells(&self) -> [String; 7] {
+        [
+            self.source_id.clone(),
+            self.source_section_number.to_string(),
+            self.source_title.clone(),
+            self.target_id.clone(),
+            self.target_document.clone(),
+            self.target_section_number.clone(),
+            self.target_title.clone(),
+        ]
+    }
+
+    /// Cell values in reverse column order
+    fn reverse_cells(&self) -> [String; 7] {
+        [
+            self.target_id.clone(),
+            self.target_document.clone(),
+            self.target_section_number.clone(),
+            self.target_title.clone(),
+            self.source_id.clone(),
+            self.source_section_number.to_string(),
+            self.source_title.clone(),
+        ]
+    }
+}
+
+/// Build the full trace relation of a source model
+///
+/// # Returns
+/// * `Vec<TraceMatrixRow>` - One row per (section, traced ID) link, sorted by
+///   source section number and then traced ID
+pub fn collect_rows(model: &SourceModel) -> Vec<TraceMatrixRow> {
+    let traces = model.collect_all_section_traceability();
+    let targets = model.collect_trace_targets(&traces);
+
+    let mut rows: Vec<TraceMatrixRow> = traces
+        .iter()
+        .flat_map(|trace| {
+            trace.traced_ids.iter().map(|traced_id| {
+                let target = targets.get(traced_id).cloned().unwrap_or_default();
+                TraceMatrixRow {
+                    source_id: trace.section_id.clone(),
+                    source_section_number: trace.section_number.clone(),
+                    source_title: trace.heading.clone(),
+                    target_id: traced_id.clone(),
+                    target_document: target.document_id,
+                    target_section_number: target
+                        .section_number
+                        .map(|number| number.to_string())
+                        .unwrap_or_default(),
+                    target_title: target.title,
+                }
+            })
+        })
+        .collect();
+
+    rows.sort_by(|a, b| {
+        a.source_section_number
+            .cmp(&b.source_section_number)
+            .then_with(|| a.target_id.cmp(&b.target_id))
+    });
+    rows
+}
+
+/// Top level of the JSON export
+#[derive(Debug, Serialize)]
+struct JsonExport<'a> {
+    document_id: &'a str,
+    sections: Vec<JsonSection<'a>>,
+}
+
+/// An identified section in the JSON export
+#[derive(Debug, Serialize)]
+struct JsonSection<'a> {
+    section_id: &'a str,
+    section_number: String,
+    heading: &'a str,
+    source_file: String,
+    line: usize,
+    status: Option<&'a str>,
+    verification_method: Option<&'a str>,
+    attributes: &'a BTreeMap<String, AttributeValue>,
+    links: Vec<JsonLink<'a>>,
+}
+
+/// A traced ID of a section in the JSON export
+#[derive(Debug, Serialize)]
+struct JsonLink<'a> {
+    target_id: &'a str,
+    target_document: &'a str,
+    target_section_number: &'a str,
+    target_title: &'a str,
+}
+
+/// Rows of the reverse direction, sorted by traced ID and then source section
+fn reverse_order(rows: &[TraceMatrixRow]) -> Vec<&TraceMatrixRow> {
+    let mut reversed: Vec<&TraceMatrixRow> = rows.iter().collect();
+    reversed.sort_by(|a, b| {
+        a.target_id
+            .cmp(&b.target_id)
+            .then_with(|| a.source_section_number.cmp(&b.source_section_number))
+    });
+    reversed
+}
+
+/// Export the forward trace relation to a CSV file
+///
+/// # Parameters
+/// * `rows` - Trace relation from [`collect_rows`]
+/// * `output_path` - Path where the .csv file will be written
+pub fn to_csv(rows: &[TraceMatrixRow], output_path: &Path) -> Result<(), TraceMatrixError> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut writer = csv::Writer::from_path(output_path)?;
+    writer.write_record(FORWARD_HEADERS)?;
+    for row in rows {
+        writer.write_record(row.forward_cells())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Export every identified section and its links to a JSON file
+///
+/// # Parameters
+/// * `model` - The parsed source model, for section metadata and attributes
+/// * `rows` - Trace relation from [`collect_rows`]
+/// * `output_path` - Path where the .json file will be written
+pub fn to_json(
+    model: &SourceModel,
+    rows: &[TraceMatrixRow],
+    output_path: &Path,
+) -> Result<(), TraceMatrixError> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut traces = model.collect_all_section_traceability();
+    traces.sort_by(|a, b| a.section_number.cmp(&b.section_number));
+    let sections: Vec<JsonSection> = traces
+        .iter()
+        .map(|trace| JsonSection {
+            section_id: &trace.section_id,
+            section_number: trace.section_number.to_string(),
+            heading: &trace.heading,
+            source_file: trace.source_file.display().to_string(),
+            line: trace.line_number,
+            status: trace.status.as_deref(),
+            verification_method: trace.verification_method.as_deref(),
+            attributes: &trace.attributes,
+            links: rows
+                .iter()
+                .filter(|row| row.source_id == trace.section_id)
+                .map(|row| JsonLink {
+                    target_id: &row.target_id,
+                    target_document: &row.target_document,
+                    target_section_number: &row.target_section_number,
+                    target_title: &row.target_title,
+                })
+                .collect(),
+        })
+        .collect();
+
+    let export = JsonExport {
+        document_id: &model.config.document_id,
+        sections,
+    };
+    std::fs::write(output_path, serde_json::to_string_pretty(&export)?)?;
+    Ok(())
+}
+
+/// Export the trace relation to an XLSX workbook with Forward and Reverse sheets
+///
+/// # Parameters
+/// * `rows` - Trace relation from [`collect_rows`]
+/// * `output_path` - Path where the .xlsx file will be written
+pub fn to_xlsx(rows: &[TraceMatrixRow], output_path: &Path) -> Result<(), TraceMatrixError> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let forward: Vec<[String; 7]> = rows.iter().map(TraceMatrixRow::forward_cells).collect();
+    let reverse: Vec<[String; 7]> = reverse_order(rows)
+        .into_iter()
+        .map(TraceMatrixRow::reverse_cells)
+        .collect();
+
+    let parts = [
+        ("[Content_Types].xml", CONTENT_TYPES_XML.to_string()),
+        ("_rels/.rels", ROOT_RELS_XML.to_string()),
+        (
+            "xl/workbook.xml",
+            workbook_xml(&[forward.len(), reverse.len()]),
+        ),
+        ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS_XML.to_string()),
+        ("xl/styles.xml", STYLES_XML.to_string()),
+        (
+            "xl/worksheets/sheet1.xml",
+            sheet_xml(&FORWARD_HEADERS, &forward),
+        ),
+        (
+            "xl/worksheets/sheet2.xml",
+            sheet_xml(&REVERSE_HEADERS, &reverse),
+        ),
+    ];
+
+    let file = std::fs::File::create(output_path)?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, content) in parts {
+        zip.start_file(name, options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Names of the worksheets, in workbook order
+const SHEET_NAMES: [&str; 2] = ["Forward", "Reverse"];
+
+const CONTENT_TYPES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/worksheets/sheet2.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#;
+
+const ROOT_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+/// Styles with a default cell format (0) and a bold header format (1)
+const STYLES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="2"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/></cellXfs></styleSheet>"#;
+
+/// Generate the workbook part, including the hidden filter ranges Excel expects
+///
+/// # Parameters
+/// * `row_counts` - Number of data rows in each sheet (excluding the header)
+fn workbook_xml(row_counts: &[usize]) -> String {
+    let sheets: String = SHEET_NAMES
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            format!(
+                r#"<sheet name="{name}" sheetId="{id}" r:id="rId{id}"/>"#,
+                id = i + 1
+            )
+        })
+        .collect();
+
+    let filters: String = SHEET_NAMES
+        .iter()
+        .zip(row_counts)
+        .enumerate()
+        .map(|(i, (name, rows))| {
+            format!(
+                r#"<definedName name="_xlnm._FilterDatabase" localSheetId="{i}" hidden="1">'{name}'!$A$1:$G${last}</definedName>"#,
+                last = rows + 1
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>{sheets}</sheets><definedNames>{filters}</definedNames></workbook>"#
+    )
+}
+
+/// Generate a worksheet part with a frozen, filtered header row
+///
+/// All cells are written as inline strings so no shared string table is needed.
+fn sheet_xml(headers: &[&str; 7], rows: &[[String; 7]]) -> String {
+    let last_row = rows.len() + 1;
+    let mut sheet_data = String::new();
+    sheet_data.push_str(&row_xml(1, headers.iter().copied(), true));
+    for (i, row) in rows.iter().enumerate() {
+        sheet_data.push_str(&row_xml(i + 2, row.iter().map(String::as_str), false));
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><dimension ref="A1:G{last_row}"/><sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/><selection pane="bottomLeft" activeCell="A2" sqref="A2"/></sheetView></sheetViews><sheetFormatPr defaultRowHeight="15"/><cols><col min="1" max="7" width="24" customWidth="1"/></cols><sheetData>{sheet_data}</sheetData><autoFilter ref="A1:G{last_row}"/></worksheet>"#
+    )
+}
+
+/// Generate a single worksheet row of inline string cells
+fn row_xml<'a>(row_number: usize, cells: impl Iterator<Item = &'a str>, header: bool) -> String {
+    let style = if header { r#" s="1""# } else { "" };
+    let cells: String = cells
+        .zip(b'A'..)
+        .map(|(value, column)| {
+            format!(
+                r#"<c r="{column}{row_number}" t="inlineStr"{style}><is><t xml:space="preserve">{value}</t></is></c>"#,
+                column = column as char,
+                value = escape_xml(value)
+            )
+        })
+        .collect();
+    format!(r#"<row r="{row_number}">{cells}</row>"#)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn row(source: &str, number: &str, target: &str) -> TraceMatrixRow {
+        TraceMatrixRow {
+            source_id: source.to_string(),
+            source_section_number: SectionNumber::parse(number).unwrap(),
+            source_title: format!("{} title", source),
+            target_id: target.to_string(),
+            target_document: "SRS".to_string(),
+            target_section_number: String::new(),
+            target_title: "Power & <Signals>".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_xlsx_has_forward_and_reverse_sheets() {
+        let rows = vec![
+            row("SDD-001", "03.01", "SRS-002"),
+            row("SDD-002", "03.02", "SRS-001"),
+        ];
+        let path = std::env::temp_dir().join(format!("sysdoc-trace-{}.xlsx", std::process::id()));
+        to_xlsx(&rows, &path).unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let mut read_part = |name: &str| {
+            let mut content = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            content
+        };
+
+        let workbook = read_part("xl/workbook.xml");
+        assert!(workbook.contains(r#"<sheet name="Forward""#));
+        assert!(workbook.contains(r#"<sheet name="Reverse""#));
+
+        let forward = read_part("xl/worksheets/sheet1.xml");
+        assert!(forward.contains(r#"state="frozen""#));
+        assert!(forward.contains(r#"<autoFilter ref="A1:G3"/>"#));
+        assert!(forward.contains("Power &amp; &lt;Signals&gt;"));
+        let forward_first = forward.find("SRS-002").unwrap();
+        assert!(forward_first < forward.find("SRS-001").unwrap());
+
+        // The reverse sheet is ordered by target ID
+        let reverse = read_part("xl/worksheets/sheet2.xml");
+        assert!(reverse.contains(r#"<c r="A1" t="inlineStr" s="1">"#));
+        assert!(reverse.find("SRS-001").unwrap() < reverse.find("SRS-002").unwrap());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let rows = vec![row("SDD-001", "03.01", "SRS-002")];
+        let path = std::env::temp_dir().join(format!("sysdoc-trace-{}.csv", std::process::id()));
+        to_csv(&rows, &path).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(
+            lines[0],
+            "Source ID,Source Section,Source Title,Target ID,Target Document,Target Section,Target Title"
+        );
+        assert_eq!(
+            lines[1],
+            "SDD-001,3.1,SDD-001 title,SRS-002,SRS,,Power & <Signals>"
+        );
+
+        std::fs::remove_file(&path).ok();
+    }
+}
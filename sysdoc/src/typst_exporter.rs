@@ -938,6 +938,7 @@ mod tests {
             subscript: false,
             link_url: None,
             link_title: None,
+            link_span: None,
         }];
         assert_eq!(runs_to_typst(&runs), "Hello");
     }
@@ -954,6 +955,7 @@ mod tests {
             subscript: false,
             link_url: None,
             link_title: None,
+            link_span: None,
         }];
         assert_eq!(runs_to_typst(&runs), "*Bold*");
     }
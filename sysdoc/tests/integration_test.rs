@@ -1,4 +1,18 @@
 use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Built-in templates, by their `sysdoc init` identifier
+const TEMPLATES: [&str; 9] = [
+    "sdd-standard-v1",
+    "srs-standard-v1",
+    "ssdd-standard-v1",
+    "sss-standard-v1",
+    "tr-standard-v1",
+    "stp-standard-v1",
+    "std-standard-v1",
+    "str-standard-v1",
+    "idd-standard-v1",
+];
 
 fn get_workspace_root() -> PathBuf {
     // Get the workspace root by going up from the manifest directory
@@ -38,19 +52,19 @@ fn test_complete_sdd_exists() {
     assert!(example_path.exists(), "examples directory should exist");
     assert!(
         example_path
-            .join("complete-sdd/src/02-architecture/tables/components.csv")
+            .join("complete-sdd/src/04_csci_architectural_design/tables/components.csv")
             .exists(),
         "complete-sdd should have CSV tables"
     );
     assert!(
         example_path
-            .join("complete-sdd/src/02-architecture/diagrams/system-context.drawio.svg")
+            .join("complete-sdd/src/01_scope/diagrams/system-context.drawio.svg")
             .exists(),
         "complete-sdd should have diagrams"
     );
     assert!(
         example_path
-            .join("complete-sdd/src/03-detailed-design/ui-screenshot.png")
+            .join("complete-sdd/src/04_csci_architectural_design/ui-screenshot.png")
             .exists(),
         "complete-sdd should have PNG images"
     );
@@ -126,3 +140,67 @@ fn test_fixtures_exist() {
         "CSV test should have test-data.csv"
     );
 }
+
+/// Run the sysdoc binary and return its combined stdout and stderr
+fn run_sysdoc(args: &[&str]) -> (Output, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_sysdoc"))
+        .args(args)
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .expect("sysdoc should run");
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    (output, text)
+}
+
+/// Test that a project freshly created from each built-in template validates,
+/// and that its unwritten sections only block a release
+#[test]
+fn test_initialized_projects_validate() {
+    let root = std::env::temp_dir().join(format!("sysdoc-init-{}", std::process::id()));
+    for template in TEMPLATES {
+        let project = root.join(template);
+        let project_arg = project.to_str().unwrap();
+        let (output, text) = run_sysdoc(&["init", template, project_arg]);
+        assert!(
+            output.status.success(),
+            "init {} failed:\n{}",
+            template,
+            text
+        );
+
+        let (output, text) = run_sysdoc(&["validate", project_arg]);
+        assert!(
+            output.status.success(),
+            "fresh {} project should validate:\n{}",
+            template,
+            text
+        );
+        assert!(text.contains("warning[guidance-only-section]"), "{}", text);
+
+        let (output, text) = run_sysdoc(&["validate", "--release", project_arg]);
+        assert!(
+            !output.status.success(),
+            "{} should not be releasable",
+            template
+        );
+        assert!(text.contains("error[guidance-only-section]"), "{}", text);
+    }
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+/// Test that the complete example validates
+#[test]
+fn test_complete_sdd_validates() {
+    let example = get_workspace_root().join("examples/complete-sdd");
+    // --release turns template conformance warnings into errors
+    let (output, text) = run_sysdoc(&["validate", "--release", example.to_str().unwrap()]);
+    assert!(
+        output.status.success(),
+        "complete-sdd should conform to its template:\n{}",
+        text
+    );
+}